@@ -0,0 +1,69 @@
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use mylittlemodel::linalg::{parallel, Matrix, Vector};
+
+// Run with `cargo bench --bench gemm`.
+
+const REPS: u32 = 3;
+
+fn best_of(mut f: impl FnMut()) -> Duration {
+    (0..REPS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+/// The textbook triple loop over column-major storage, walking the rows of
+/// `a` with stride `n`. This is what dense products used to compile down to.
+fn naive(n: usize, a: &[f32], b: &[f32]) -> Vec<f32> {
+    let mut out = vec![0f32; n * n];
+    for c in 0..n {
+        for r in 0..n {
+            out[c * n + r] = (0..n).map(|k| a[k * n + r] * b[c * n + k]).sum();
+        }
+    }
+    out
+}
+
+fn entry(i: usize) -> f32 {
+    ((i * 7919) % 101) as f32 / 101. - 0.5
+}
+
+fn bench<const N: usize>() where [(); N*N]: Sized {
+    let a = Matrix::<N, N>::from_vector(Vector::from_fun(entry));
+    let b = Matrix::<N, N>::from_vector(Vector::from_fun(|i| entry(i + 1)));
+    let at = a.T();
+    let raw_a: Vec<f32> = (0..N * N).map(entry).collect();
+    let raw_b: Vec<f32> = (0..N * N).map(|i| entry(i + 1)).collect();
+
+    let flops = 2. * (N as f64).powi(3);
+    let report = |what: &str, t: Duration| {
+        println!("{N:>5}  {what:<14} {:>9.1} ms  {:>6.2} GFLOP/s", t.as_secs_f64() * 1e3, flops / t.as_secs_f64() / 1e9);
+    };
+
+    let t_naive = best_of(|| { black_box(naive(N, black_box(&raw_a), black_box(&raw_b))); });
+    parallel::set_num_threads(1);
+    let t_cols = best_of(|| { black_box(black_box(&a) * black_box(&b)); });
+    let t_rows = best_of(|| { black_box(black_box(&at) * black_box(&b)); });
+    parallel::set_num_threads(0);
+    let t_threads = best_of(|| { black_box(black_box(&a) * black_box(&b)); });
+
+    report("naive", t_naive);
+    report("blocked", t_cols);
+    report("blocked, Aᵀ", t_rows);
+    report(&format!("{} thread(s)", parallel::num_threads()), t_threads);
+    println!("{N:>5}  speedup        {:>9.1}x", t_naive.as_secs_f64() / t_cols.as_secs_f64());
+}
+
+fn main() {
+    bench::<512>();
+    bench::<1024>();
+}
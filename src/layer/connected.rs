@@ -1,28 +1,31 @@
-use crate::linalg::{Matrix, Vector};
+use crate::linalg::lazy::{self, VectorExpr};
+use crate::linalg::{Matrix, Scalar, Vector};
 use crate::model::activation::ActivationFunction;
+use crate::model::checkpoint::{CheckpointError, LayerRecord};
 use crate::model::weights::{Biases, Weights};
 use super::ModelLayer;
 
 #[allow(dead_code)]
-pub struct FullyConnectedLayer<const IN: usize, const OUT: usize, A: ActivationFunction>
+pub struct FullyConnectedLayer<const IN: usize, const OUT: usize, A: ActivationFunction, S: Scalar = f32>
     where [(); OUT*IN]: Sized
 {
-    pub W: Matrix<OUT, IN>, // weights
-    pub b: Vector<OUT>,     // biases
-    pub n: Vector<OUT>,     // net linear outputs
-    pub a: Vector<OUT>,     // net nonlinear outputs
-    pub s: Vector<OUT>,     // dL/dn of this layer
-    pub Wᵀs: Vector<IN>,    // weighted dL/dn for backwards pass
+    pub W: Matrix<OUT, IN, S>, // weights
+    pub b: Vector<OUT, S>,     // biases
+    pub n: Vector<OUT, S>,     // net linear outputs
+    pub a: Vector<OUT, S>,     // net nonlinear outputs
+    pub s: Vector<OUT, S>,     // dL/dn of this layer
+    pub Wᵀs: Vector<IN, S>,    // weighted dL/dn for backwards pass
     pub activation_function: A,
 }
 
-impl<const IN: usize, const OUT: usize, A: ActivationFunction> FullyConnectedLayer<IN, OUT, A>
+impl<const IN: usize, const OUT: usize, A: ActivationFunction, S: Scalar> FullyConnectedLayer<IN, OUT, A, S>
     where
         [(); IN*OUT]: Sized,
         [(); OUT*IN]: Sized,
         [(); OUT*OUT]: Sized,
+        [(); IN*IN]: Sized,
 {
-    pub fn with(weights: Weights<IN, OUT>, biases: Biases<OUT>, activation_function: A) -> Self {
+    pub fn with(weights: Weights<IN, OUT, S>, biases: Biases<OUT, S>, activation_function: A) -> Self {
         FullyConnectedLayer {
             W: weights.into(),
             b: biases.into(),
@@ -35,57 +38,75 @@ impl<const IN: usize, const OUT: usize, A: ActivationFunction> FullyConnectedLay
     }
 }
 
-impl<const IN: usize, const OUT: usize, F: ActivationFunction> ModelLayer<IN, OUT> for FullyConnectedLayer<IN, OUT, F>
+impl<const IN: usize, const OUT: usize, F: ActivationFunction, S: Scalar> ModelLayer<IN, OUT, S> for FullyConnectedLayer<IN, OUT, F, S>
     where
         [(); IN*OUT]: Sized,
         [(); OUT*IN]: Sized,
         [(); OUT*OUT]: Sized,
 {
-    fn forward(&mut self, prev_output: &Vector<IN>) {
-        self.n = &(&self.W * prev_output) + &self.b;
+    fn forward(&mut self, prev_output: &Vector<IN, S>) {
+        // n = W a + b and a = f(n), fused, into the storage of the previous step.
         let f = self.activation_function.get_f();
-        self.a = self.n.map(f);
+        lazy::gemv(&self.W, prev_output).plus(&self.b).map(f).assign_with_input(&mut self.n, &mut self.a);
     }
 
-    fn backward(&mut self, Wᵀs_succ: &Vector<OUT>) {
+    fn backward(&mut self, Wᵀs_succ: &Vector<OUT, S>) {
         // needs: n_i and df_i, but WT_i+1 and s_i+1
         // thus: get upstream Wᵀs to compute and set own Wᵀs
         let df = self.activation_function.get_df();
-        let Ḟn = Matrix::diag(self.n.map(df));
-        self.s = &Ḟn * Wᵀs_succ;
+        // Ḟn is diagonal, so Ḟn Wᵀs is just the entrywise product.
+        self.s = self.n.map(df).hadamard(Wᵀs_succ);
         self.Wᵀs = &self.W.T() * &self.s;
     }
 
-    fn update_params(&mut self, learning_rate: f32, a_pred: &Vector<IN>) {
-        let ref dLdW = self.s.outer(a_pred);
-        self.W = &self.W - &(learning_rate * dLdW);
-
-        let ref dLdb = self.s;
-        self.b = &self.b - &(learning_rate * dLdb);
+    fn update_params(&mut self, learning_rate: S, a_pred: &Vector<IN, S>) {
+        // W -= lr * s ⊗ a and b -= lr * s, without allocating either gradient.
+        self.W.add_outer(-learning_rate, &self.s, a_pred);
+        self.b.axpy(-learning_rate, &self.s);
     }
 
-    fn nonlinear_output(&self) -> &Vector<OUT> {
+    fn nonlinear_output(&self) -> &Vector<OUT, S> {
         &self.a
     }
 
-    fn linear_output(&self) -> &Vector<OUT> {
+    fn linear_output(&self) -> &Vector<OUT, S> {
         &self.n
     }
 
-    fn f(&self) -> Box<dyn Fn(f32) -> f32 + 'static> {
+    fn f(&self) -> Box<dyn Fn(S) -> S + 'static> {
         Box::new(self.activation_function.get_f())
     }
 
-    fn df(&self) -> Box<dyn Fn(f32) -> f32 + 'static> {
+    fn df(&self) -> Box<dyn Fn(S) -> S + 'static> {
         Box::new(self.activation_function.get_df())
     }
 
-    fn set_sensitivities(&mut self, s: Vector<OUT>) {
+    fn set_sensitivities(&mut self, s: Vector<OUT, S>) {
         self.Wᵀs = &self.W.T() * &s;
         self.s = s;
     }
 
-    fn get_sensitivities(&self) -> &Vector<IN> {
+    fn get_sensitivities(&self) -> &Vector<IN, S> {
         &self.Wᵀs
     }
+
+    fn scale_weights(&mut self, alpha: S) {
+        self.W *= alpha;
+    }
+
+    fn record(&self) -> Option<LayerRecord> {
+        Some(LayerRecord::new(&self.W, &self.b, &self.activation_function))
+    }
+
+    fn check(&self, index: usize, record: &LayerRecord) -> Result<(), CheckpointError> {
+        record.check::<IN, OUT, F>(index)
+    }
+
+    fn restore(&mut self, index: usize, record: &LayerRecord) -> Result<(), CheckpointError> {
+        record.check::<IN, OUT, F>(index)?;
+        self.activation_function.set_params(record.activation_params());
+        self.W = record.W();
+        self.b = record.b();
+        Ok(())
+    }
 }
@@ -1,33 +1,64 @@
-use crate::linalg::{Matrix, Vector};
+use crate::linalg::{Matrix, Scalar, Vector};
 use crate::model::loss::LossFunction;
-use crate::model::ModelOutput;
+use crate::model::checkpoint::{CheckpointError, LayerRecord};
+use crate::model::{weights, ModelOutput};
 
 pub mod connected;
 
-pub trait ModelLayer<const IN: usize, const OUT: usize>
+pub trait ModelLayer<const IN: usize, const OUT: usize, S: Scalar = f32>
     where
         [(); IN*OUT]: Sized,
         [(); OUT*IN]: Sized,
         [(); OUT*OUT]: Sized,
 {
-    fn forward(&mut self, input_src: &Vector<IN>);
-    fn backward(&mut self, upstream_Wᵀs: &Vector<OUT>);
-    fn update_params(&mut self, learning_rate: f32, a_prev: &Vector<IN>);
-    fn nonlinear_output(&self) -> &Vector<OUT>;
-    fn linear_output(&self) -> &Vector<OUT>;
-    fn f(&self) -> Box<dyn Fn(f32) -> f32 + 'static>;
-    fn df(&self) -> Box<dyn Fn(f32) -> f32 + 'static>;
-    fn get_sensitivities(&self) -> &Vector<IN>;
-    fn set_sensitivities(&mut self, s: Vector<OUT>);
+    fn forward(&mut self, input_src: &Vector<IN, S>);
+    fn backward(&mut self, upstream_Wᵀs: &Vector<OUT, S>);
+    fn update_params(&mut self, learning_rate: S, a_prev: &Vector<IN, S>);
+    fn nonlinear_output(&self) -> &Vector<OUT, S>;
+    fn linear_output(&self) -> &Vector<OUT, S>;
+    fn f(&self) -> Box<dyn Fn(S) -> S + 'static>;
+    fn df(&self) -> Box<dyn Fn(S) -> S + 'static>;
+    fn get_sensitivities(&self) -> &Vector<IN, S>;
+    fn set_sensitivities(&mut self, s: Vector<OUT, S>);
+    fn scale_weights(&mut self, alpha: S);
+    /// The layer as a checkpoint stores it. Defaults to `None`, for layers
+    /// that don't take part in checkpoints.
+    fn record(&self) -> Option<LayerRecord> {
+        None
+    }
+
+    /// Checks that `record` fits this layer, number `index` in its chain.
+    fn check(&self, index: usize, _record: &LayerRecord) -> Result<(), CheckpointError> {
+        Err(CheckpointError::NotCheckpointed { layer: index })
+    }
+
+    /// Takes the parameters of `record`, or leaves the layer alone if `check` fails.
+    fn restore(&mut self, index: usize, _record: &LayerRecord) -> Result<(), CheckpointError> {
+        Err(CheckpointError::NotCheckpointed { layer: index })
+    }
 }
 
-pub trait ModelLayerChain<const IN: usize, const OUT: usize, T> {
+pub trait ModelLayerChain<const IN: usize, const OUT: usize, T, S: Scalar = f32> {
     fn train_single<L: LossFunction>(
         &mut self,
-        input_pair: (&Vector<IN>, &Vector<OUT>),
+        input_pair: (&Vector<IN, S>, &Vector<OUT, S>),
         loss_function: L,
-        learning_rate: f32,
-    ) -> ModelOutput<OUT>;
+        learning_rate: S,
+    ) -> ModelOutput<OUT, S>;
+
+    /// The forward pass alone, for inference.
+    fn predict(&mut self, input: &Vector<IN, S>) -> Vector<OUT, S>;
+
+    /// Rescales the weights layer by layer until every layer's linear
+    /// outputs over `inputs` have unit variance, see `weights::lsuv`.
+    fn lsuv(&mut self, inputs: &[Vector<IN, S>], tolerance: S);
+
+    /// Every layer as a checkpoint stores it, input side first, or the
+    /// first layer that doesn't take part in checkpoints.
+    fn records(&self) -> Result<Vec<LayerRecord>, CheckpointError>;
+
+    /// Restores every layer from `records`, or none of them if any doesn't fit.
+    fn restore(&mut self, records: &[LayerRecord]) -> Result<(), CheckpointError>;
 }
 
 // This is prime target for a macro.
@@ -36,14 +67,15 @@ impl<
     const B: usize,
     const C: usize,
     const D: usize,
-    L0: ModelLayer<A, B>,
-    L1: ModelLayer<B, C>,
-    L2: ModelLayer<C, D>,
+    S: Scalar,
+    L0: ModelLayer<A, B, S>,
+    L1: ModelLayer<B, C, S>,
+    L2: ModelLayer<C, D, S>,
 > ModelLayerChain<A, D, (
-    Box<dyn ModelLayer<A, B>>,
-    Box<dyn ModelLayer<B, C>>,
-    Box<dyn ModelLayer<C, D>>,
-)> for (
+    Box<dyn ModelLayer<A, B, S>>,
+    Box<dyn ModelLayer<B, C, S>>,
+    Box<dyn ModelLayer<C, D, S>>,
+), S> for (
     L0, L1, L2,
 ) where
     [(); A*B]: Sized,
@@ -58,10 +90,10 @@ impl<
 {
     fn train_single<LF: LossFunction>(
         &mut self,
-        input_pair: (&Vector<A>, &Vector<D>),
+        input_pair: (&Vector<A, S>, &Vector<D, S>),
         loss_function: LF,
-        learning_rate: f32,
-    ) -> ModelOutput<D> {
+        learning_rate: S,
+    ) -> ModelOutput<D, S> {
         let (item, target) = input_pair;
 
         self.0.forward(item);
@@ -79,7 +111,7 @@ impl<
         let (n_last, df_last) = (self.2.linear_output(), self.2.df());
 
         // This doesn't depend on choice of L.
-        let da_dn = n_last.map(df_last).into();
+        let da_dn = n_last.map(df_last);
         let da_dn = Matrix::diag(da_dn);
         // This depends on choice of L.
         let dL_da = dL_da(target, &model_output);
@@ -95,10 +127,44 @@ impl<
         self.1.update_params(learning_rate, self.0.nonlinear_output());
         self.2.update_params(learning_rate, self.1.nonlinear_output());
 
-        return ModelOutput {
+        ModelOutput {
             loss,
             errors,
             output: model_output,
         }
     }
+
+    fn predict(&mut self, input: &Vector<A, S>) -> Vector<D, S> {
+        self.0.forward(input);
+        self.1.forward(self.0.nonlinear_output());
+        self.2.forward(self.1.nonlinear_output());
+        self.2.nonlinear_output().clone()
+    }
+
+    fn lsuv(&mut self, inputs: &[Vector<A, S>], tolerance: S) {
+        let outputs = weights::lsuv(&mut self.0, inputs, tolerance);
+        let outputs = weights::lsuv(&mut self.1, &outputs, tolerance);
+        weights::lsuv(&mut self.2, &outputs, tolerance);
+    }
+
+    fn records(&self) -> Result<Vec<LayerRecord>, CheckpointError> {
+        let missing = |layer| CheckpointError::NotCheckpointed { layer };
+        Ok(vec![
+            self.0.record().ok_or(missing(0))?,
+            self.1.record().ok_or(missing(1))?,
+            self.2.record().ok_or(missing(2))?,
+        ])
+    }
+
+    fn restore(&mut self, records: &[LayerRecord]) -> Result<(), CheckpointError> {
+        let [r0, r1, r2] = records else {
+            return Err(CheckpointError::LayerCount { expected: 3, found: records.len() });
+        };
+        self.0.check(0, r0)?;
+        self.1.check(1, r1)?;
+        self.2.check(2, r2)?;
+        self.0.restore(0, r0)?;
+        self.1.restore(1, r1)?;
+        self.2.restore(2, r2)
+    }
 }
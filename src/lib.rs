@@ -1,8 +1,12 @@
 #![allow(non_snake_case, uncommon_codepoints)]  // for symbols and one-letter variables standard in the ML literature
 #![allow(incomplete_features)]                  // for dimension analysis of matrix math
 #![feature(generic_const_exprs)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 
 // TODO: restrict module visibility through selective re-exports.
 pub mod layer;
 pub mod linalg;
 pub mod model;
+pub mod random;
+
+pub use random::with_seed;
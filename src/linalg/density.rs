@@ -0,0 +1,95 @@
+//! Process-wide policy for moving results between sparse and dense storage.
+//!
+//! Out of the box a result keeps whatever flavor its op picked, and flavors
+//! only change through `densify`, `sparsify` and `rebalance`. Once a
+//! threshold is set, every sum, difference and product with a sparse
+//! operand rebalances its result as well, so a chain of sparse updates that
+//! fills in moves to dense storage, and a result that cancels out moves back.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// The bits of the threshold, or `UNSET`, which as an `f32` is a NaN and so
+/// can't collide with a valid threshold.
+static THRESHOLD: AtomicU32 = AtomicU32::new(UNSET);
+const UNSET: u32 = u32::MAX;
+
+/// Sets the fraction of nonzero entries above which sparse results become
+/// dense. `None` turns automatic conversion off again.
+pub fn set_threshold(threshold: Option<f32>) {
+    let bits = match threshold {
+        Some(t) => {
+            assert!((0. ..=1.).contains(&t), "density threshold {t} is not a fraction");
+            t.to_bits()
+        },
+        None => UNSET,
+    };
+    THRESHOLD.store(bits, Ordering::Relaxed);
+}
+
+pub fn threshold() -> Option<f32> {
+    match THRESHOLD.load(Ordering::Relaxed) {
+        UNSET => None,
+        bits => Some(f32::from_bits(bits)),
+    }
+}
+
+/// Whether `nnz` nonzeros out of `len` entries belong in sparse storage.
+/// Sparse storage is kept up to `threshold`, but dense storage is only left
+/// at half of it, so results hovering around the threshold don't convert
+/// back and forth on every op.
+pub(crate) fn prefers_sparse(nnz: usize, len: usize, threshold: f32, is_sparse: bool) -> bool {
+    let limit = if is_sparse { threshold } else { threshold / 2. };
+    nnz as f32 <= limit * len as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::linalg::{Matrix, Vector};
+
+    #[test]
+    fn density_counts_nonzeros_across_flavors() {
+        assert_eq!(Vector::<4>::from_arr([1., 0., 0., 2.]).density(), 0.5);
+        assert_eq!(Vector::<4>::one_hot(2).density(), 0.25);
+        assert_eq!(Vector::<4>::zero().density(), 0.);
+        assert_eq!(Matrix::<4, 4>::I().density(), 0.25);
+        assert_eq!(Matrix::<2, 4>::constant(3.).density(), 1.);
+        assert_eq!(Matrix::<2, 2>::from_triplets([(0, 1, 1.)]).density(), 0.25);
+    }
+
+    #[test]
+    fn densify_and_sparsify_keep_entries() {
+        let v = Vector::<5>::from_arr([0., 0.5, 0., -0.01, 3.]);
+        let s = v.clone().sparsify(0.);
+        assert!(matches!(s, Vector::Sparse(_)));
+        assert_eq!(s, Vector::from_pairs([(1, 0.5), (3, -0.01), (4, 3.)]));
+        assert!(matches!(s.clone().densify(), Vector::Dense(_)));
+        assert_eq!(s.densify(), v);
+        assert_eq!(v.sparsify(0.1), Vector::from_pairs([(1, 0.5), (4, 3.)]));
+
+        let m = Matrix::<2, 3>::from_cols(&[[1., 0.], [0., 0.], [0., -2.]]);
+        assert!(matches!(m.T().sparsify(0.), Matrix::Sparse(_)));
+        assert_eq!(m.T().sparsify(0.), m.T());
+        assert_eq!(Matrix::<3, 3>::I().sparsify(0.), Matrix::I());
+        assert!(matches!(Matrix::<3, 3>::I().densify(), Matrix::Dense(_)));
+        assert_eq!(m.sparsify(1.5), Matrix::from_triplets([(1, 2, -2.)]));
+    }
+
+    #[test]
+    fn rebalance_converts_with_hysteresis() {
+        // Three nonzeros in ten: denser than 0.2, but not twice as sparse as 0.4.
+        let pairs = [(0, 1.), (4, 2.), (9, 3.)];
+        let sparse = Vector::<10>::from_pairs(pairs);
+        let dense = sparse.clone().densify();
+        assert!(matches!(sparse.clone().rebalance(0.2), Vector::Dense(_)));
+        assert!(matches!(sparse.clone().rebalance(0.4), Vector::Sparse(_)));
+        assert!(matches!(dense.clone().rebalance(0.4), Vector::Dense(_)));
+        assert!(matches!(dense.clone().rebalance(0.6), Vector::Sparse(_)));
+        assert_eq!(dense.rebalance(0.6), sparse);
+        assert!(matches!(Vector::<10>::one_hot(3).rebalance(0.), Vector::OneHot(_)));
+
+        let m = Matrix::<2, 5>::from_vector(sparse);
+        assert!(matches!(m.clone().rebalance(0.2), Matrix::Dense(_)));
+        assert!(matches!(m.clone().densify().rebalance(0.6), Matrix::Sparse(_)));
+        assert!(matches!(Matrix::<3, 3>::I().rebalance(0.), Matrix::Identity(_)));
+    }
+}
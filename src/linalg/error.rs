@@ -0,0 +1,25 @@
+use std::fmt::Display;
+
+/// Why a decomposition or solve could not produce a result.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LinalgError {
+    /// The matrix has no inverse. Elimination found no nonzero pivot for
+    /// the given column, so the solution is either missing or not unique.
+    Singular { column: usize },
+    /// Cholesky found a diagonal entry that is not positive once the
+    /// previous columns are eliminated, so the matrix is not positive definite.
+    NotPositiveDefinite { column: usize },
+}
+
+impl Display for LinalgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinalgError::Singular { column } => write!(f, "matrix is singular (no pivot in column {column})"),
+            LinalgError::NotPositiveDefinite { column } => {
+                write!(f, "matrix is not positive definite (failed at column {column})")
+            },
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {}
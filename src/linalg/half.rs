@@ -0,0 +1,375 @@
+//! Software 16-bit floats, for storing weights in half the memory.
+//!
+//! Neither type has hardware arithmetic here: every operation widens to
+//! `f32`, computes, and rounds back to nearest even. Reductions (`dot`,
+//! `sum`, ...) accumulate in `f32` and round once at the end, since summing
+//! in 16 bits loses small terms against a large running total.
+//!
+//! - [`bf16`] keeps the 8-bit exponent of `f32` and 7 bits of mantissa, so it
+//!   has the range of `f32` but only two to three significant digits.
+//! - [`f16`] is IEEE 754 binary16: 5 exponent and 10 mantissa bits, so it is
+//!   more precise but overflows above 65504.
+
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display};
+use std::iter::Sum;
+use std::num::FpCategory;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
+
+use num_traits::{Float, Num, NumCast, One, ToPrimitive, Zero};
+
+use super::kernels::Kernels;
+use super::Scalar;
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Default)]
+pub struct bf16(u16);
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Default)]
+pub struct f16(u16);
+
+impl bf16 {
+    pub const fn from_bits(bits: u16) -> Self {
+        bf16(bits)
+    }
+
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn from_f32(x: f32) -> Self {
+        let bits = x.to_bits();
+        if x.is_nan() {
+            // Keep it a NaN even if the payload was all in the low half.
+            return bf16((bits >> 16) as u16 | 0x40);
+        }
+        let round = 0x7fff + ((bits >> 16) & 1);
+        bf16((bits.wrapping_add(round) >> 16) as u16)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        f32::from_bits((self.0 as u32) << 16)
+    }
+}
+
+impl f16 {
+    pub const fn from_bits(bits: u16) -> Self {
+        f16(bits)
+    }
+
+    pub const fn to_bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn from_f32(x: f32) -> Self {
+        let bits = x.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exp = ((bits >> 23) & 0xff) as i32;
+        let man = bits & 0x7f_ffff;
+
+        if exp == 0xff {
+            let nan = if man != 0 { 0x200 | (man >> 13) as u16 } else { 0 };
+            return f16(sign | 0x7c00 | nan);
+        }
+        // Rebias from 127 to 15.
+        let e = exp - 112;
+        if e >= 0x1f {
+            return f16(sign | 0x7c00);
+        }
+        if e <= 0 {
+            // Subnormal in half precision, or too small even for that.
+            if e < -10 {
+                return f16(sign);
+            }
+            let man = man | 0x80_0000;
+            let shift = (14 - e) as u32;
+            let (kept, rest, half) = (man >> shift, man & ((1 << shift) - 1), 1 << (shift - 1));
+            let round = (rest > half || (rest == half && kept & 1 == 1)) as u32;
+            return f16(sign | (kept + round) as u16);
+        }
+        let (kept, rest) = (man >> 13, man & 0x1fff);
+        let round = (rest > 0x1000 || (rest == 0x1000 && kept & 1 == 1)) as u32;
+        // A carry out of the mantissa correctly bumps the exponent, up to infinity.
+        f16(sign | (((e as u32) << 10 | kept) + round) as u16)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        let sign = ((self.0 & 0x8000) as u32) << 16;
+        let exp = ((self.0 >> 10) & 0x1f) as u32;
+        let man = (self.0 & 0x3ff) as u32;
+        match exp {
+            0 => {
+                let x = man as f32 / (1 << 24) as f32;
+                if sign == 0 { x } else { -x }
+            },
+            0x1f => f32::from_bits(sign | 0x7f80_0000 | man << 13),
+            _ => f32::from_bits(sign | (exp + 112) << 23 | man << 13),
+        }
+    }
+}
+
+/// Everything but the conversions and the constants particular to the format
+/// goes through `f32`.
+macro_rules! software_float {
+    ($t:ident, epsilon: $eps:expr, max: $max:expr, min_positive: $min_pos:expr) => {
+        impl PartialEq for $t {
+            fn eq(&self, other: &Self) -> bool {
+                $t::to_f32(*self) == $t::to_f32(*other)
+            }
+        }
+
+        impl PartialOrd for $t {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                $t::to_f32(*self).partial_cmp(&$t::to_f32(*other))
+            }
+        }
+
+        impl Debug for $t {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                Debug::fmt(&$t::to_f32(*self), f)
+            }
+        }
+
+        impl Display for $t {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                Display::fmt(&$t::to_f32(*self), f)
+            }
+        }
+
+        impl From<$t> for f32 {
+            fn from(x: $t) -> f32 {
+                x.to_f32()
+            }
+        }
+
+        software_float!(@binary $t, Add, add, AddAssign, add_assign, +);
+        software_float!(@binary $t, Sub, sub, SubAssign, sub_assign, -);
+        software_float!(@binary $t, Mul, mul, MulAssign, mul_assign, *);
+        software_float!(@binary $t, Div, div, DivAssign, div_assign, /);
+        software_float!(@binary $t, Rem, rem, RemAssign, rem_assign, %);
+
+        impl Neg for $t {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $t(self.0 ^ 0x8000)
+            }
+        }
+
+        impl Sum for $t {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                $t::from_f32(iter.map($t::to_f32).sum())
+            }
+        }
+
+        impl Zero for $t {
+            fn zero() -> Self {
+                $t(0)
+            }
+
+            fn is_zero(&self) -> bool {
+                self.0 & 0x7fff == 0
+            }
+        }
+
+        impl One for $t {
+            fn one() -> Self {
+                $t::from_f32(1f32)
+            }
+        }
+
+        impl Num for $t {
+            type FromStrRadixErr = <f32 as Num>::FromStrRadixErr;
+
+            fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+                f32::from_str_radix(s, radix).map($t::from_f32)
+            }
+        }
+
+        impl ToPrimitive for $t {
+            fn to_i64(&self) -> Option<i64> {
+                $t::to_f32(*self).to_i64()
+            }
+
+            fn to_u64(&self) -> Option<u64> {
+                $t::to_f32(*self).to_u64()
+            }
+
+            fn to_f32(&self) -> Option<f32> {
+                Some($t::to_f32(*self))
+            }
+
+            fn to_f64(&self) -> Option<f64> {
+                Some($t::to_f32(*self).into())
+            }
+        }
+
+        impl NumCast for $t {
+            fn from<N: ToPrimitive>(n: N) -> Option<Self> {
+                n.to_f32().map($t::from_f32)
+            }
+        }
+
+        impl Float for $t {
+            fn epsilon() -> Self { $t::from_bits($eps) }
+            fn max_value() -> Self { $t::from_bits($max) }
+            fn min_value() -> Self { -$t::from_bits($max) }
+            fn min_positive_value() -> Self { $t::from_bits($min_pos) }
+            fn nan() -> Self { $t::from_f32(f32::NAN) }
+            fn infinity() -> Self { $t::from_f32(f32::INFINITY) }
+            fn neg_infinity() -> Self { $t::from_f32(f32::NEG_INFINITY) }
+            fn neg_zero() -> Self { $t(0x8000) }
+
+            fn is_nan(self) -> bool { self.to_f32().is_nan() }
+            fn is_infinite(self) -> bool { self.to_f32().is_infinite() }
+            fn is_finite(self) -> bool { self.to_f32().is_finite() }
+            fn is_normal(self) -> bool { self.classify() == FpCategory::Normal }
+            fn is_sign_positive(self) -> bool { self.0 & 0x8000 == 0 }
+            fn is_sign_negative(self) -> bool { self.0 & 0x8000 != 0 }
+            fn classify(self) -> FpCategory {
+                match self.0 & 0x7fff {
+                    0 => FpCategory::Zero,
+                    m if m < $min_pos => FpCategory::Subnormal,
+                    m if m <= $max => FpCategory::Normal,
+                    _ if self.is_nan() => FpCategory::Nan,
+                    _ => FpCategory::Infinite,
+                }
+            }
+            fn integer_decode(self) -> (u64, i16, i8) { self.to_f32().integer_decode() }
+
+            fn mul_add(self, a: Self, b: Self) -> Self {
+                $t::from_f32(self.to_f32().mul_add(a.to_f32(), b.to_f32()))
+            }
+            fn powi(self, n: i32) -> Self { $t::from_f32(self.to_f32().powi(n)) }
+            fn powf(self, n: Self) -> Self { $t::from_f32(self.to_f32().powf(n.to_f32())) }
+            fn log(self, base: Self) -> Self { $t::from_f32(self.to_f32().log(base.to_f32())) }
+            fn max(self, other: Self) -> Self { $t::from_f32(self.to_f32().max(other.to_f32())) }
+            fn min(self, other: Self) -> Self { $t::from_f32(self.to_f32().min(other.to_f32())) }
+            #[allow(deprecated)]
+            fn abs_sub(self, other: Self) -> Self { $t::from_f32(self.to_f32().abs_sub(other.to_f32())) }
+            fn hypot(self, other: Self) -> Self { $t::from_f32(self.to_f32().hypot(other.to_f32())) }
+            fn atan2(self, other: Self) -> Self { $t::from_f32(self.to_f32().atan2(other.to_f32())) }
+            fn sin_cos(self) -> (Self, Self) {
+                let (s, c) = self.to_f32().sin_cos();
+                ($t::from_f32(s), $t::from_f32(c))
+            }
+
+            fn abs(self) -> Self { $t(self.0 & 0x7fff) }
+            software_float!(@unary $t, floor ceil round trunc fract signum recip sqrt exp exp2 ln log2
+                log10 cbrt sin cos tan asin acos atan exp_m1 ln_1p sinh cosh tanh asinh acosh atanh);
+        }
+
+        impl Scalar for $t {
+            const ZERO_REF: &'static Self = &$t(0);
+
+            fn from_f32(x: f32) -> Self {
+                $t::from_f32(x)
+            }
+
+            fn to_f32(self) -> f32 {
+                $t::to_f32(self)
+            }
+        }
+
+        impl Kernels for $t {
+            fn dot(a: &[Self], b: &[Self]) -> Self {
+                $t::from_f32(a.iter().zip(b).map(|(&x, &y)| x.to_f32() * y.to_f32()).sum())
+            }
+
+            fn sum(a: &[Self]) -> Self {
+                a.iter().copied().sum()
+            }
+
+            fn sum_of_squares(a: &[Self]) -> Self {
+                $t::from_f32(a.iter().map(|&x| x.to_f32() * x.to_f32()).sum())
+            }
+
+            fn axpy(y: &mut [Self], alpha: Self, x: &[Self]) {
+                let alpha = alpha.to_f32();
+                for (o, &x) in y.iter_mut().zip(x) {
+                    *o = $t::from_f32($t::to_f32(*o) + alpha * x.to_f32());
+                }
+            }
+        }
+    };
+
+    (@binary $t:ident, $op:ident, $method:ident, $op_assign:ident, $method_assign:ident, $sym:tt) => {
+        impl $op for $t {
+            type Output = Self;
+
+            fn $method(self, rhs: Self) -> Self {
+                $t::from_f32(self.to_f32() $sym rhs.to_f32())
+            }
+        }
+
+        impl $op_assign for $t {
+            fn $method_assign(&mut self, rhs: Self) {
+                *self = *self $sym rhs;
+            }
+        }
+    };
+
+    (@unary $t:ident, $($method:ident)*) => {
+        $(fn $method(self) -> Self { $t::from_f32(self.to_f32().$method()) })*
+    };
+}
+
+software_float!(bf16, epsilon: 0x3c00, max: 0x7f7f, min_positive: 0x0080);
+software_float!(f16, epsilon: 0x1400, max: 0x7bff, min_positive: 0x0400);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::{Matrix, Vector};
+
+    #[test]
+    fn conversions_round_to_nearest_even() {
+        assert_eq!(bf16::from_f32(1.).to_bits(), 0x3f80);
+        assert_eq!(f16::from_f32(1.).to_bits(), 0x3c00);
+        assert_eq!(f16::from_f32(65504.).to_bits(), 0x7bff);
+        assert!(f16::from_f32(65536.).is_infinite());
+        // Halfway between 1 and the next f16, 1 + 2⁻¹⁰, ties to the even 1.
+        assert_eq!(f16::from_f32(1. + 2f32.powi(-11)).to_bits(), 0x3c00);
+        assert_eq!(f16::from_f32(1. + 3. * 2f32.powi(-11)).to_bits(), 0x3c02);
+        // The smallest subnormal, and half of it, which ties to zero.
+        assert_eq!(f16::from_f32(2f32.powi(-24)).to_bits(), 0x0001);
+        assert_eq!(f16::from_f32(2f32.powi(-25)).to_bits(), 0x0000);
+        assert!(bf16::from_f32(f32::NAN).is_nan() && f16::from_f32(f32::NAN).is_nan());
+    }
+
+    #[test]
+    fn representable_values_survive_a_round_trip() {
+        for x in [0., -0., 1., -2.5, 0.15625, 1024., 3.0517578e-5] {
+            assert_eq!(f16::from_f32(x).to_f32(), x);
+        }
+        for x in [0., 1., -2.5, 3.0e38, 1.0e-38] {
+            let y = bf16::from_f32(x).to_f32();
+            assert!((y - x).abs() <= x.abs() * 2f32.powi(-8), "{x} became {y}");
+        }
+    }
+
+    #[test]
+    fn epsilon_is_the_gap_above_one() {
+        assert_eq!(bf16::epsilon().to_f32(), 2f32.powi(-7));
+        assert_eq!(f16::epsilon().to_f32(), 2f32.powi(-10));
+        assert_eq!((f16::one() + f16::epsilon()).to_bits(), 0x3c01);
+    }
+
+    #[test]
+    fn reductions_accumulate_in_f32() {
+        // Summed in bf16 the total would stall at 256, where adding one is
+        // below half an ulp.
+        let ones = Vector::<1000, bf16>::from_arr([bf16::one(); 1000]);
+        assert_eq!(ones.dot(&ones).to_f32(), 1000.);
+        assert_eq!(ones.sum().to_f32(), 1000.);
+    }
+
+    #[test]
+    fn half_matrices_go_through_the_stack() {
+        let h = |x: f32| f16::from_f32(x);
+        let m = Matrix::<2, 2, f16>::from_cols(&[[h(1.), h(2.)], [h(-0.5), h(4.)]]);
+        let y = &(&m * &Vector::from_arr([h(2.), h(1.)])) + h(0.25);
+        assert_eq!([y[0].to_f32(), y[1].to_f32()], [1.75, 8.25]);
+    }
+}
@@ -0,0 +1,201 @@
+//! Slice kernels behind the dense flavors. Every kernel exists as a plain
+//! scalar loop, generic over the entry type, and for `f32` also as a
+//! `std::simd` version, used when the `simd` feature is on (the default).
+//! The scalar version doubles as the reference the SIMD version is tested
+//! against. Outputs are written into caller-provided slices of the same
+//! length as the inputs.
+
+use std::iter::Sum;
+
+use num_traits::{Float, NumAssign};
+
+mod scalar;
+#[cfg(feature = "simd")]
+mod simd;
+
+/// Picks the kernels for one entry type. The provided methods are the scalar
+/// loops, so a type only overrides what it can do better, like `f32` with
+/// SIMD, or the 16-bit types by accumulating in `f32`.
+pub trait Kernels: Float + NumAssign + Sum {
+    fn add(a: &[Self], b: &[Self], out: &mut [Self]) { scalar::add(a, b, out) }
+    fn sub(a: &[Self], b: &[Self], out: &mut [Self]) { scalar::sub(a, b, out) }
+    fn mul(a: &[Self], b: &[Self], out: &mut [Self]) { scalar::mul(a, b, out) }
+    fn scale(a: &[Self], alpha: Self, out: &mut [Self]) { scalar::scale(a, alpha, out) }
+    fn offset(a: &[Self], beta: Self, out: &mut [Self]) { scalar::offset(a, beta, out) }
+    fn map(a: &[Self], f: impl Fn(Self) -> Self, out: &mut [Self]) { scalar::map(a, f, out) }
+    fn add_assign(y: &mut [Self], x: &[Self]) { scalar::add_assign(y, x) }
+    fn scale_assign(y: &mut [Self], alpha: Self) { scalar::scale_assign(y, alpha) }
+    fn offset_assign(y: &mut [Self], beta: Self) { scalar::offset_assign(y, beta) }
+    fn map_assign(y: &mut [Self], f: impl Fn(Self) -> Self) { scalar::map_assign(y, f) }
+    fn axpy(y: &mut [Self], alpha: Self, x: &[Self]) { scalar::axpy(y, alpha, x) }
+    fn dot(a: &[Self], b: &[Self]) -> Self { scalar::dot(a, b) }
+    fn sum(a: &[Self]) -> Self { scalar::sum(a) }
+    fn sum_of_squares(a: &[Self]) -> Self { scalar::sum_of_squares(a) }
+    fn outer(u: &[Self], v: &[Self], out: &mut [Self]) { scalar::outer(u, v, out) }
+}
+
+#[cfg(not(feature = "simd"))]
+impl Kernels for f32 {}
+
+#[cfg(feature = "simd")]
+impl Kernels for f32 {
+    fn add(a: &[f32], b: &[f32], out: &mut [f32]) { simd::add(a, b, out) }
+    fn sub(a: &[f32], b: &[f32], out: &mut [f32]) { simd::sub(a, b, out) }
+    fn mul(a: &[f32], b: &[f32], out: &mut [f32]) { simd::mul(a, b, out) }
+    fn scale(a: &[f32], alpha: f32, out: &mut [f32]) { simd::scale(a, alpha, out) }
+    fn offset(a: &[f32], beta: f32, out: &mut [f32]) { simd::offset(a, beta, out) }
+    fn map(a: &[f32], f: impl Fn(f32) -> f32, out: &mut [f32]) { simd::map(a, f, out) }
+    fn add_assign(y: &mut [f32], x: &[f32]) { simd::add_assign(y, x) }
+    fn scale_assign(y: &mut [f32], alpha: f32) { simd::scale_assign(y, alpha) }
+    fn offset_assign(y: &mut [f32], beta: f32) { simd::offset_assign(y, beta) }
+    fn map_assign(y: &mut [f32], f: impl Fn(f32) -> f32) { simd::map_assign(y, f) }
+    fn axpy(y: &mut [f32], alpha: f32, x: &[f32]) { simd::axpy(y, alpha, x) }
+    fn dot(a: &[f32], b: &[f32]) -> f32 { simd::dot(a, b) }
+    fn sum(a: &[f32]) -> f32 { simd::sum(a) }
+    fn sum_of_squares(a: &[f32]) -> f32 { simd::sum_of_squares(a) }
+    fn outer(u: &[f32], v: &[f32], out: &mut [f32]) { simd::outer(u, v, out) }
+}
+
+impl Kernels for f64 {}
+
+pub(crate) fn add<T: Kernels>(a: &[T], b: &[T], out: &mut [T]) { <T as Kernels>::add(a, b, out) }
+pub(crate) fn sub<T: Kernels>(a: &[T], b: &[T], out: &mut [T]) { <T as Kernels>::sub(a, b, out) }
+pub(crate) fn mul<T: Kernels>(a: &[T], b: &[T], out: &mut [T]) { <T as Kernels>::mul(a, b, out) }
+pub(crate) fn scale<T: Kernels>(a: &[T], alpha: T, out: &mut [T]) { <T as Kernels>::scale(a, alpha, out) }
+pub(crate) fn offset<T: Kernels>(a: &[T], beta: T, out: &mut [T]) { <T as Kernels>::offset(a, beta, out) }
+pub(crate) fn map<T: Kernels>(a: &[T], f: impl Fn(T) -> T, out: &mut [T]) { <T as Kernels>::map(a, f, out) }
+pub(crate) fn add_assign<T: Kernels>(y: &mut [T], x: &[T]) { <T as Kernels>::add_assign(y, x) }
+pub(crate) fn scale_assign<T: Kernels>(y: &mut [T], alpha: T) { <T as Kernels>::scale_assign(y, alpha) }
+pub(crate) fn offset_assign<T: Kernels>(y: &mut [T], beta: T) { <T as Kernels>::offset_assign(y, beta) }
+pub(crate) fn map_assign<T: Kernels>(y: &mut [T], f: impl Fn(T) -> T) { <T as Kernels>::map_assign(y, f) }
+/// `y += alpha * x`
+pub(crate) fn axpy<T: Kernels>(y: &mut [T], alpha: T, x: &[T]) { <T as Kernels>::axpy(y, alpha, x) }
+pub(crate) fn dot<T: Kernels>(a: &[T], b: &[T]) -> T { <T as Kernels>::dot(a, b) }
+pub(crate) fn sum<T: Kernels>(a: &[T]) -> T { <T as Kernels>::sum(a) }
+pub(crate) fn sum_of_squares<T: Kernels>(a: &[T]) -> T { <T as Kernels>::sum_of_squares(a) }
+/// Column-major `u vᵀ`.
+pub(crate) fn outer<T: Kernels>(u: &[T], v: &[T], out: &mut [T]) { <T as Kernels>::outer(u, v, out) }
+
+// The integer kernels behind the quantized flavor. Products of `i8`s are
+// accumulated in `i32`, which holds any dot product shorter than 2¹⁷.
+
+pub(crate) fn dot_i8(a: &[i8], b: &[i8]) -> i32 {
+    #[cfg(feature = "simd")] { simd::dot_i8(a, b) }
+    #[cfg(not(feature = "simd"))] { scalar::dot_i8(a, b) }
+}
+
+/// `y += alpha * x`
+pub(crate) fn axpy_i8(y: &mut [i32], alpha: i32, x: &[i8]) {
+    #[cfg(feature = "simd")] { simd::axpy_i8(y, alpha, x) }
+    #[cfg(not(feature = "simd"))] { scalar::axpy_i8(y, alpha, x) }
+}
+
+#[cfg(all(test, feature = "simd"))]
+mod tests {
+    use super::{scalar, simd};
+
+    const EPS: f32 = 1e-4;
+
+    // Lengths around the lane width, plus a long one for the reductions.
+    const LENS: [usize; 6] = [0, 1, 7, 8, 19, 1000];
+
+    fn sample(n: usize, seed: usize) -> Vec<f32> {
+        (0..n).map(|i| ((i * 31 + seed * 17) % 23) as f32 / 7. - 1.5).collect()
+    }
+
+    fn assert_close(a: &[f32], b: &[f32]) {
+        assert_eq!(a.len(), b.len());
+        for (i, (x, y)) in a.iter().zip(b).enumerate() {
+            assert!((x - y).abs() <= EPS * (1. + y.abs()), "entry {i}: {x} vs {y}");
+        }
+    }
+
+    #[test]
+    fn elementwise_kernels_match_scalar() {
+        for n in LENS {
+            let (a, b) = (sample(n, 1), sample(n, 2));
+            let (mut v, mut s) = (vec![0f32; n], vec![0f32; n]);
+
+            simd::add(&a, &b, &mut v);
+            scalar::add(&a, &b, &mut s);
+            assert_close(&v, &s);
+
+            simd::sub(&a, &b, &mut v);
+            scalar::sub(&a, &b, &mut s);
+            assert_close(&v, &s);
+
+            simd::mul(&a, &b, &mut v);
+            scalar::mul(&a, &b, &mut s);
+            assert_close(&v, &s);
+
+            simd::scale(&a, -0.75, &mut v);
+            scalar::scale(&a, -0.75, &mut s);
+            assert_close(&v, &s);
+
+            simd::offset(&a, 2.5, &mut v);
+            scalar::offset(&a, 2.5, &mut s);
+            assert_close(&v, &s);
+
+            simd::map(&a, |x| x.max(0.) * 3., &mut v);
+            scalar::map(&a, |x| x.max(0.) * 3., &mut s);
+            assert_close(&v, &s);
+
+            simd::add_assign(&mut v, &b);
+            scalar::add_assign(&mut s, &b);
+            assert_close(&v, &s);
+
+            simd::axpy(&mut v, 0.5, &a);
+            scalar::axpy(&mut s, 0.5, &a);
+            assert_close(&v, &s);
+
+            simd::scale_assign(&mut v, 1.25);
+            scalar::scale_assign(&mut s, 1.25);
+            assert_close(&v, &s);
+
+            simd::offset_assign(&mut v, -0.5);
+            scalar::offset_assign(&mut s, -0.5);
+            assert_close(&v, &s);
+
+            simd::map_assign(&mut v, |x| x * x - 1.);
+            scalar::map_assign(&mut s, |x| x * x - 1.);
+            assert_close(&v, &s);
+        }
+    }
+
+    #[test]
+    fn reductions_match_scalar() {
+        for n in LENS {
+            let (a, b) = (sample(n, 3), sample(n, 4));
+            assert_close(&[simd::sum(&a)], &[scalar::sum(&a)]);
+            assert_close(&[simd::sum_of_squares(&a)], &[scalar::sum_of_squares(&a)]);
+            assert_close(&[simd::dot(&a, &b)], &[scalar::dot(&a, &b)]);
+        }
+    }
+
+    #[test]
+    fn integer_kernels_match_scalar() {
+        for n in LENS {
+            let a: Vec<i8> = (0..n).map(|i| ((i * 37) % 256) as u8 as i8).collect();
+            let b: Vec<i8> = (0..n).map(|i| ((i * 91 + 5) % 256) as u8 as i8).collect();
+            assert_eq!(simd::dot_i8(&a, &b), scalar::dot_i8(&a, &b));
+
+            let (mut v, mut s) = (vec![3i32; n], vec![3i32; n]);
+            simd::axpy_i8(&mut v, -127, &a);
+            scalar::axpy_i8(&mut s, -127, &a);
+            assert_eq!(v, s);
+        }
+    }
+
+    #[test]
+    fn outer_matches_scalar() {
+        let (u, v) = (sample(19, 5), sample(6, 6));
+        let (mut x, mut y) = (vec![0f32; 19 * 6], vec![0f32; 19 * 6]);
+        simd::outer(&u, &v, &mut x);
+        scalar::outer(&u, &v, &mut y);
+        assert_close(&x, &y);
+
+        // An empty `u` leaves nothing to fill, whatever the length of `v`.
+        simd::outer(&[], &v, &mut []);
+        scalar::outer(&[], &v, &mut []);
+    }
+}
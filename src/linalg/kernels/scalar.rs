@@ -0,0 +1,103 @@
+use super::Kernels;
+
+pub(crate) fn add<T: Kernels>(a: &[T], b: &[T], out: &mut [T]) {
+    for ((o, &x), &y) in out.iter_mut().zip(a).zip(b) {
+        *o = x + y;
+    }
+}
+
+pub(crate) fn sub<T: Kernels>(a: &[T], b: &[T], out: &mut [T]) {
+    for ((o, &x), &y) in out.iter_mut().zip(a).zip(b) {
+        *o = x - y;
+    }
+}
+
+/// Elementwise (Hadamard) product.
+pub(crate) fn mul<T: Kernels>(a: &[T], b: &[T], out: &mut [T]) {
+    for ((o, &x), &y) in out.iter_mut().zip(a).zip(b) {
+        *o = x * y;
+    }
+}
+
+pub(crate) fn scale<T: Kernels>(a: &[T], alpha: T, out: &mut [T]) {
+    for (o, &x) in out.iter_mut().zip(a) {
+        *o = x * alpha;
+    }
+}
+
+pub(crate) fn offset<T: Kernels>(a: &[T], beta: T, out: &mut [T]) {
+    for (o, &x) in out.iter_mut().zip(a) {
+        *o = x + beta;
+    }
+}
+
+pub(crate) fn map<T: Kernels>(a: &[T], f: impl Fn(T) -> T, out: &mut [T]) {
+    for (o, &x) in out.iter_mut().zip(a) {
+        *o = f(x);
+    }
+}
+
+pub(crate) fn add_assign<T: Kernels>(y: &mut [T], x: &[T]) {
+    for (o, &x) in y.iter_mut().zip(x) {
+        *o += x;
+    }
+}
+
+pub(crate) fn scale_assign<T: Kernels>(y: &mut [T], alpha: T) {
+    for o in y.iter_mut() {
+        *o *= alpha;
+    }
+}
+
+pub(crate) fn offset_assign<T: Kernels>(y: &mut [T], beta: T) {
+    for o in y.iter_mut() {
+        *o += beta;
+    }
+}
+
+pub(crate) fn map_assign<T: Kernels>(y: &mut [T], f: impl Fn(T) -> T) {
+    for o in y.iter_mut() {
+        *o = f(*o);
+    }
+}
+
+/// `y += alpha * x`
+pub(crate) fn axpy<T: Kernels>(y: &mut [T], alpha: T, x: &[T]) {
+    for (o, &x) in y.iter_mut().zip(x) {
+        *o += alpha * x;
+    }
+}
+
+pub(crate) fn dot<T: Kernels>(a: &[T], b: &[T]) -> T {
+    a.iter().zip(b).map(|(&x, &y)| x * y).sum()
+}
+
+pub(crate) fn sum<T: Kernels>(a: &[T]) -> T {
+    a.iter().copied().sum()
+}
+
+pub(crate) fn sum_of_squares<T: Kernels>(a: &[T]) -> T {
+    a.iter().map(|&x| x * x).sum()
+}
+
+pub(crate) fn dot_i8(a: &[i8], b: &[i8]) -> i32 {
+    a.iter().zip(b).map(|(&x, &y)| i32::from(x) * i32::from(y)).sum()
+}
+
+/// `y += alpha * x`
+pub(crate) fn axpy_i8(y: &mut [i32], alpha: i32, x: &[i8]) {
+    for (o, &x) in y.iter_mut().zip(x) {
+        *o += alpha * i32::from(x);
+    }
+}
+
+/// Column-major `u vᵀ`.
+pub(crate) fn outer<T: Kernels>(u: &[T], v: &[T], out: &mut [T]) {
+    assert_eq!(out.len(), u.len() * v.len());
+    if u.is_empty() {
+        return;
+    }
+    for (col, &y) in out.chunks_exact_mut(u.len()).zip(v) {
+        scale(u, y, col);
+    }
+}
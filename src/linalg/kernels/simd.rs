@@ -0,0 +1,193 @@
+use std::simd::num::{SimdFloat, SimdInt};
+use std::simd::Simd;
+
+use super::scalar;
+
+const LANES: usize = 8;
+
+type F32s = Simd<f32, LANES>;
+type I8s = Simd<i8, LANES>;
+type I32s = Simd<i32, LANES>;
+
+/// Splits off the tail that does not fill a whole register.
+fn split(n: usize) -> usize {
+    n - n % LANES
+}
+
+/// Applies `f` register by register and hands the tail to `tail`.
+fn zip_with(
+    a: &[f32],
+    b: &[f32],
+    out: &mut [f32],
+    f: impl Fn(F32s, F32s) -> F32s,
+    tail: impl Fn(&[f32], &[f32], &mut [f32]),
+) {
+    let n = out.len();
+    assert!(a.len() == n && b.len() == n);
+    let body = split(n);
+    for ((o, x), y) in out[..body].chunks_exact_mut(LANES)
+        .zip(a[..body].chunks_exact(LANES))
+        .zip(b[..body].chunks_exact(LANES))
+    {
+        f(F32s::from_slice(x), F32s::from_slice(y)).copy_to_slice(o);
+    }
+    tail(&a[body..], &b[body..], &mut out[body..]);
+}
+
+pub(super) fn add(a: &[f32], b: &[f32], out: &mut [f32]) {
+    zip_with(a, b, out, |x, y| x + y, scalar::add);
+}
+
+pub(super) fn sub(a: &[f32], b: &[f32], out: &mut [f32]) {
+    zip_with(a, b, out, |x, y| x - y, scalar::sub);
+}
+
+pub(super) fn mul(a: &[f32], b: &[f32], out: &mut [f32]) {
+    zip_with(a, b, out, |x, y| x * y, scalar::mul);
+}
+
+pub(super) fn scale(a: &[f32], alpha: f32, out: &mut [f32]) {
+    let n = out.len();
+    assert_eq!(a.len(), n);
+    let body = split(n);
+    let alphas = F32s::splat(alpha);
+    for (o, x) in out[..body].chunks_exact_mut(LANES).zip(a[..body].chunks_exact(LANES)) {
+        (F32s::from_slice(x) * alphas).copy_to_slice(o);
+    }
+    scalar::scale(&a[body..], alpha, &mut out[body..]);
+}
+
+pub(super) fn offset(a: &[f32], beta: f32, out: &mut [f32]) {
+    let n = out.len();
+    assert_eq!(a.len(), n);
+    let body = split(n);
+    let betas = F32s::splat(beta);
+    for (o, x) in out[..body].chunks_exact_mut(LANES).zip(a[..body].chunks_exact(LANES)) {
+        (F32s::from_slice(x) + betas).copy_to_slice(o);
+    }
+    scalar::offset(&a[body..], beta, &mut out[body..]);
+}
+
+/// `f` is an opaque scalar function, so it is applied lane by lane on
+/// fixed-width blocks, which is what lets the compiler vectorize it
+/// once it is inlined.
+pub(super) fn map(a: &[f32], f: impl Fn(f32) -> f32, out: &mut [f32]) {
+    let n = out.len();
+    assert_eq!(a.len(), n);
+    let body = split(n);
+    for (o, x) in out[..body].chunks_exact_mut(LANES).zip(a[..body].chunks_exact(LANES)) {
+        let x: [f32; LANES] = x.try_into().unwrap();
+        o.copy_from_slice(&x.map(&f));
+    }
+    scalar::map(&a[body..], f, &mut out[body..]);
+}
+
+pub(super) fn add_assign(y: &mut [f32], x: &[f32]) {
+    let n = y.len();
+    assert_eq!(x.len(), n);
+    let body = split(n);
+    for (o, x) in y[..body].chunks_exact_mut(LANES).zip(x[..body].chunks_exact(LANES)) {
+        (F32s::from_slice(o) + F32s::from_slice(x)).copy_to_slice(o);
+    }
+    scalar::add_assign(&mut y[body..], &x[body..]);
+}
+
+pub(super) fn scale_assign(y: &mut [f32], alpha: f32) {
+    let body = split(y.len());
+    let alphas = F32s::splat(alpha);
+    for o in y[..body].chunks_exact_mut(LANES) {
+        (F32s::from_slice(o) * alphas).copy_to_slice(o);
+    }
+    scalar::scale_assign(&mut y[body..], alpha);
+}
+
+pub(super) fn offset_assign(y: &mut [f32], beta: f32) {
+    let body = split(y.len());
+    let betas = F32s::splat(beta);
+    for o in y[..body].chunks_exact_mut(LANES) {
+        (F32s::from_slice(o) + betas).copy_to_slice(o);
+    }
+    scalar::offset_assign(&mut y[body..], beta);
+}
+
+/// See `map`.
+pub(super) fn map_assign(y: &mut [f32], f: impl Fn(f32) -> f32) {
+    let body = split(y.len());
+    for o in y[..body].chunks_exact_mut(LANES) {
+        let x: [f32; LANES] = (&*o).try_into().unwrap();
+        o.copy_from_slice(&x.map(&f));
+    }
+    scalar::map_assign(&mut y[body..], f);
+}
+
+/// `y += alpha * x`
+pub(super) fn axpy(y: &mut [f32], alpha: f32, x: &[f32]) {
+    let n = y.len();
+    assert_eq!(x.len(), n);
+    let body = split(n);
+    let alphas = F32s::splat(alpha);
+    for (o, x) in y[..body].chunks_exact_mut(LANES).zip(x[..body].chunks_exact(LANES)) {
+        (F32s::from_slice(o) + alphas * F32s::from_slice(x)).copy_to_slice(o);
+    }
+    scalar::axpy(&mut y[body..], alpha, &x[body..]);
+}
+
+/// Sums lane-wise into one register and reduces it at the end, so the
+/// result differs from the scalar sum only by rounding.
+fn reduce(a: &[f32], b: &[f32], f: impl Fn(F32s, F32s) -> F32s, tail: impl Fn(&[f32], &[f32]) -> f32) -> f32 {
+    let n = a.len();
+    assert_eq!(b.len(), n);
+    let body = split(n);
+    let acc = a[..body].chunks_exact(LANES)
+        .zip(b[..body].chunks_exact(LANES))
+        .fold(F32s::splat(0f32), |acc, (x, y)| acc + f(F32s::from_slice(x), F32s::from_slice(y)));
+    acc.reduce_sum() + tail(&a[body..], &b[body..])
+}
+
+pub(super) fn dot(a: &[f32], b: &[f32]) -> f32 {
+    reduce(a, b, |x, y| x * y, scalar::dot)
+}
+
+pub(super) fn sum(a: &[f32]) -> f32 {
+    reduce(a, a, |x, _| x, |x, _| scalar::sum(x))
+}
+
+pub(super) fn sum_of_squares(a: &[f32]) -> f32 {
+    reduce(a, a, |x, _| x * x, |x, _| scalar::sum_of_squares(x))
+}
+
+/// Widens to `i32` lanes before multiplying, so nothing overflows.
+pub(super) fn dot_i8(a: &[i8], b: &[i8]) -> i32 {
+    let n = a.len();
+    assert_eq!(b.len(), n);
+    let body = split(n);
+    let acc = a[..body].chunks_exact(LANES)
+        .zip(b[..body].chunks_exact(LANES))
+        .fold(I32s::splat(0), |acc, (x, y)| {
+            acc + I8s::from_slice(x).cast::<i32>() * I8s::from_slice(y).cast::<i32>()
+        });
+    acc.reduce_sum() + scalar::dot_i8(&a[body..], &b[body..])
+}
+
+/// `y += alpha * x`
+pub(super) fn axpy_i8(y: &mut [i32], alpha: i32, x: &[i8]) {
+    let n = y.len();
+    assert_eq!(x.len(), n);
+    let body = split(n);
+    let alphas = I32s::splat(alpha);
+    for (o, x) in y[..body].chunks_exact_mut(LANES).zip(x[..body].chunks_exact(LANES)) {
+        (I32s::from_slice(o) + alphas * I8s::from_slice(x).cast::<i32>()).copy_to_slice(o);
+    }
+    scalar::axpy_i8(&mut y[body..], alpha, &x[body..]);
+}
+
+/// Column-major `u vᵀ`.
+pub(super) fn outer(u: &[f32], v: &[f32], out: &mut [f32]) {
+    assert_eq!(out.len(), u.len() * v.len());
+    if u.is_empty() {
+        return;
+    }
+    for (col, &y) in out.chunks_exact_mut(u.len()).zip(v) {
+        scale(u, y, col);
+    }
+}
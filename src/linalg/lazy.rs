@@ -0,0 +1,350 @@
+//! Opt-in lazy expressions over `Matrix` and `Vector`.
+//!
+//! The eager operators allocate a fresh result at every step, so e.g.
+//! `(&(&W * &x) + &b).map(f)` allocates three vectors to produce one. Here
+//! the same computation is first recorded, as in
+//! `lazy::gemv(&W, &x).plus(&b).map(f)`, and nothing happens until it is
+//! evaluated. Evaluation allocates at most the result (nothing at all when
+//! assigning into a dense target) and walks the output once, chunk by chunk:
+//! each chunk is fully computed, product, sums and activation, before moving
+//! on to the next. Chunks are spread across threads as configured in
+//! [`parallel`](super::parallel).
+//!
+//! References to `Vector` and `Matrix` are expressions themselves, and the
+//! combinators live on the [`VectorExpr`] and [`MatrixExpr`] traits.
+
+use super::kernels;
+use super::matrix::Matrix;
+use super::order::Order;
+use super::parallel;
+use super::scalar::Scalar;
+use super::vector::Vector;
+
+/// An expression that evaluates to a `Vector<D, T>`.
+pub trait VectorExpr<const D: usize, T: Scalar = f32>: Sync {
+    /// `out += alpha * self[at..at + out.len()]`.
+    fn accumulate(&self, alpha: T, at: usize, out: &mut [T]);
+
+    /// Rough count of multiply-adds, which decides whether to use threads.
+    fn work(&self) -> usize {
+        D
+    }
+
+    /// `out = self[at..at + out.len()]`.
+    fn write(&self, at: usize, out: &mut [T]) {
+        out.fill(T::zero());
+        self.accumulate(T::one(), at, out);
+    }
+
+    /// Computes the expression into a newly allocated dense vector.
+    fn eval(&self) -> Vector<D, T> {
+        let mut data = vec![T::zero(); D].into_boxed_slice();
+        parallel::for_each_chunk(&mut data, 1, self.work(), |at, out| self.write(at, out));
+        Vector::from_boxed_slice(data)
+    }
+
+    /// Computes the expression into `target`, reusing its storage if it is
+    /// already dense. `target` must not appear in the expression, which the
+    /// borrow checker enforces.
+    fn assign_to(&self, target: &mut Vector<D, T>) {
+        let data = &mut target.make_dense().data;
+        parallel::for_each_chunk(data, 1, self.work(), |at, out| self.write(at, out));
+    }
+
+    /// `target += self`, without materializing `self`.
+    fn add_to(&self, target: &mut Vector<D, T>) {
+        let data = &mut target.make_dense().data;
+        parallel::for_each_chunk(data, 1, self.work(), |at, out| self.accumulate(T::one(), at, out));
+    }
+
+    fn plus<E: VectorExpr<D, T>>(self, rhs: E) -> Sum<Self, E> where Self: Sized {
+        Sum(self, rhs)
+    }
+
+    fn minus<E: VectorExpr<D, T>>(self, rhs: E) -> Sum<Self, Scaled<E, T>> where Self: Sized {
+        Sum(self, Scaled(-T::one(), rhs))
+    }
+
+    fn scale(self, alpha: T) -> Scaled<Self, T> where Self: Sized {
+        Scaled(alpha, self)
+    }
+
+    /// Applies `f` entrywise, e.g. an activation function.
+    fn map<F: Fn(T) -> T + Sync>(self, f: F) -> Map<Self, F> where Self: Sized {
+        Map(self, f)
+    }
+}
+
+/// An expression that evaluates to a `Matrix<R, C, T>`. It is produced a run
+/// of whole lines at a time, in whichever order the destination is stored.
+pub trait MatrixExpr<const R: usize, const C: usize, T: Scalar = f32>: Sync where [(); R*C]: Sized {
+    /// `out += alpha * self` over the lines of `order` that `out` covers,
+    /// starting at line `i0`.
+    fn accumulate(&self, order: Order, alpha: T, i0: usize, out: &mut [T]);
+
+    /// Rough count of multiply-adds, which decides whether to use threads.
+    fn work(&self) -> usize {
+        R*C
+    }
+
+    /// Computes the expression into a newly allocated dense matrix.
+    fn eval(&self) -> Matrix<R, C, T> {
+        let mut data = vec![T::zero(); R*C].into_boxed_slice();
+        parallel::for_each_chunk(&mut data, R, self.work(), |j0, out| self.accumulate(Order::COLS, T::one(), j0, out));
+        Matrix::from_boxed_slice(data)
+    }
+
+    /// `target += self` in place, e.g. `W -= lr * s aᵀ` as
+    /// `lazy::outer(&s, &a).scale(-lr).add_to(&mut W)`.
+    fn add_to(&self, target: &mut Matrix<R, C, T>) {
+        target.update_lines(self.work(), |order, i0, out| self.accumulate(order, T::one(), i0, out));
+    }
+
+    fn plus<E: MatrixExpr<R, C, T>>(self, rhs: E) -> Sum<Self, E> where Self: Sized {
+        Sum(self, rhs)
+    }
+
+    fn minus<E: MatrixExpr<R, C, T>>(self, rhs: E) -> Sum<Self, Scaled<E, T>> where Self: Sized {
+        Sum(self, Scaled(-T::one(), rhs))
+    }
+
+    fn scale(self, alpha: T) -> Scaled<Self, T> where Self: Sized {
+        Scaled(alpha, self)
+    }
+}
+
+/// The product `m * x`.
+pub fn gemv<'a, const R: usize, const C: usize, T: Scalar>(m: &'a Matrix<R, C, T>, x: &'a Vector<C, T>) -> Gemv<'a, R, C, T>
+    where [(); R*C]: Sized
+{
+    // Only dense products are worth deferring. The structured flavors
+    // multiply cheaply and mostly into sparse results, so they run eagerly.
+    let eager = (!matches!(m, Matrix::Dense(_))).then(|| m * x);
+    Gemv { m, x, eager }
+}
+
+/// The outer product `u vᵀ`.
+pub fn outer<'a, const R: usize, const C: usize, T: Scalar>(u: &'a Vector<R, T>, v: &'a Vector<C, T>) -> Outer<'a, R, C, T> {
+    Outer(u, v)
+}
+
+pub struct Gemv<'a, const R: usize, const C: usize, T: Scalar = f32> where [(); R*C]: Sized {
+    m: &'a Matrix<R, C, T>,
+    x: &'a Vector<C, T>,
+    eager: Option<Vector<R, T>>,
+}
+
+pub struct Outer<'a, const R: usize, const C: usize, T: Scalar = f32>(&'a Vector<R, T>, &'a Vector<C, T>);
+
+pub struct Sum<A, B>(A, B);
+
+pub struct Scaled<E, T = f32>(T, E);
+
+pub struct Map<E, F>(E, F);
+
+impl<const D: usize, T: Scalar> VectorExpr<D, T> for &Vector<D, T> {
+    fn accumulate(&self, alpha: T, at: usize, out: &mut [T]) {
+        self.axpy_range_into(alpha, at, out);
+    }
+
+    fn write(&self, at: usize, out: &mut [T]) {
+        match self {
+            Vector::Dense(v) => out.copy_from_slice(&v.data[at..at + out.len()]),
+            _ => {
+                out.fill(T::zero());
+                self.axpy_range_into(T::one(), at, out);
+            },
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> VectorExpr<R, T> for Gemv<'_, R, C, T> where [(); R*C]: Sized {
+    fn accumulate(&self, alpha: T, at: usize, out: &mut [T]) {
+        match (&self.eager, self.m) {
+            (Some(v), _) => v.axpy_range_into(alpha, at, out),
+            (None, Matrix::Dense(m)) => m.gemv_into(alpha, self.x, at, out),
+            (None, _) => unreachable!(),
+        }
+    }
+
+    fn work(&self) -> usize {
+        R*C
+    }
+}
+
+impl<const D: usize, T: Scalar, A: VectorExpr<D, T>, B: VectorExpr<D, T>> VectorExpr<D, T> for Sum<A, B> {
+    fn accumulate(&self, alpha: T, at: usize, out: &mut [T]) {
+        self.0.accumulate(alpha, at, out);
+        self.1.accumulate(alpha, at, out);
+    }
+
+    fn work(&self) -> usize {
+        self.0.work() + self.1.work()
+    }
+
+    fn write(&self, at: usize, out: &mut [T]) {
+        self.0.write(at, out);
+        self.1.accumulate(T::one(), at, out);
+    }
+}
+
+impl<const D: usize, T: Scalar, E: VectorExpr<D, T>> VectorExpr<D, T> for Scaled<E, T> {
+    fn accumulate(&self, alpha: T, at: usize, out: &mut [T]) {
+        self.1.accumulate(alpha * self.0, at, out);
+    }
+
+    fn work(&self) -> usize {
+        self.1.work()
+    }
+
+    fn write(&self, at: usize, out: &mut [T]) {
+        self.1.write(at, out);
+        kernels::scale_assign(out, self.0);
+    }
+}
+
+impl<const D: usize, E: VectorExpr<D, T>, F: Fn(T) -> T + Sync, T: Scalar> VectorExpr<D, T> for Map<E, F> {
+    /// `f` has to see the finished entries before they are scaled and
+    /// added, so this is the one place that needs a scratch buffer.
+    fn accumulate(&self, alpha: T, at: usize, out: &mut [T]) {
+        let mut mapped = vec![T::zero(); out.len()];
+        self.write(at, &mut mapped);
+        kernels::axpy(out, alpha, &mapped);
+    }
+
+    fn work(&self) -> usize {
+        self.0.work()
+    }
+
+    fn write(&self, at: usize, out: &mut [T]) {
+        self.0.write(at, out);
+        kernels::map_assign(out, &self.1);
+    }
+}
+
+impl<E, F> Map<E, F> {
+    /// Like `assign_to(output)`, but also keeps the values `f` was applied
+    /// to in `input`, as a layer does for its backward pass.
+    pub fn assign_with_input<const D: usize, T: Scalar>(&self, input: &mut Vector<D, T>, output: &mut Vector<D, T>)
+        where E: VectorExpr<D, T>, F: Fn(T) -> T + Sync
+    {
+        self.0.assign_to(input);
+        let input = &input.make_dense().data;
+        kernels::map(input, &self.1, &mut output.make_dense().data);
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> MatrixExpr<R, C, T> for &Matrix<R, C, T> where [(); R*C]: Sized {
+    fn accumulate(&self, order: Order, alpha: T, i0: usize, out: &mut [T]) {
+        self.axpy_lines_into(order, alpha, i0, out);
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> MatrixExpr<R, C, T> for Outer<'_, R, C, T> where [(); R*C]: Sized {
+    /// Each line gets one axpy of `u` or `v`, so sparse factors skip
+    /// whole lines as well as entries.
+    fn accumulate(&self, order: Order, alpha: T, i0: usize, out: &mut [T]) {
+        let Outer(u, v) = *self;
+        match order {
+            Order::COLS => for (k, col) in out.chunks_exact_mut(R).enumerate() {
+                let y = v[i0 + k];
+                if y != T::zero() {
+                    u.axpy_into(alpha * y, col);
+                }
+            },
+            Order::ROWS => for (k, row) in out.chunks_exact_mut(C).enumerate() {
+                let x = u[i0 + k];
+                if x != T::zero() {
+                    v.axpy_into(alpha * x, row);
+                }
+            },
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar, A: MatrixExpr<R, C, T>, B: MatrixExpr<R, C, T>> MatrixExpr<R, C, T> for Sum<A, B>
+    where [(); R*C]: Sized
+{
+    fn accumulate(&self, order: Order, alpha: T, i0: usize, out: &mut [T]) {
+        self.0.accumulate(order, alpha, i0, out);
+        self.1.accumulate(order, alpha, i0, out);
+    }
+
+    fn work(&self) -> usize {
+        self.0.work() + self.1.work()
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar, E: MatrixExpr<R, C, T>> MatrixExpr<R, C, T> for Scaled<E, T> where [(); R*C]: Sized {
+    fn accumulate(&self, order: Order, alpha: T, i0: usize, out: &mut [T]) {
+        self.1.accumulate(order, alpha * self.0, i0, out);
+    }
+
+    fn work(&self) -> usize {
+        self.1.work()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::matrix::test_support::{assert_vectors_close, EPS};
+
+    fn weights() -> Matrix<3, 4> {
+        Matrix::from_cols(&[
+            [1., -2., 0.5],
+            [0., 3., -1.],
+            [2., 1., 4.],
+            [-0.5, 0., 1.5],
+        ])
+    }
+
+    fn relu(x: f32) -> f32 {
+        x.max(0f32)
+    }
+
+    #[test]
+    fn fused_layer_matches_eager() {
+        let (b, x) = (Vector::from_arr([0.5, -1., 2.]), Vector::from_arr([1., -1., 0.5, 2.]));
+        for w in [weights(), weights().T().T(), Matrix::from_triplets([(1, 2, 3.)])] {
+            for x in [x.clone(), Vector::one_hot(2), Vector::from_pairs([(0, 2.), (3, -1.)])] {
+                let n = &(&w * &x) + &b;
+                let expected = n.map(relu);
+                assert_vectors_close(&gemv(&w, &x).plus(&b).map(relu).eval(), &expected);
+
+                let (mut pre, mut post) = (Vector::zero(), Vector::zero());
+                gemv(&w, &x).plus(&b).map(relu).assign_with_input(&mut pre, &mut post);
+                assert_vectors_close(&pre, &n);
+                assert_vectors_close(&post, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn vector_combinators_match_eager() {
+        let (u, v) = (Vector::from_arr([1., 2., 3.]), Vector::from_pairs([(1, -4.)]));
+        assert_vectors_close(&(&u).minus(&v).scale(2.).eval(), &(2. * &(&u - &v)));
+
+        let mut acc = Vector::one_hot(0);
+        // `Vector::map` is eager, and takes precedence over the trait method.
+        VectorExpr::map(&u, |x| x * x).add_to(&mut acc);
+        assert_vectors_close(&acc, &Vector::from_arr([2., 4., 9.]));
+    }
+
+    #[test]
+    fn outer_update_matches_eager() {
+        let (s, a) = (Vector::from_arr([1., 0., -2.]), Vector::from_pairs([(1, 3.), (3, 0.5)]));
+        for w in [weights(), weights().T().T(), Matrix::I().hstack(&Matrix::<3, 1>::zero())] {
+            let expected = &w - &(&s.outer(&a) * 0.1);
+            assert_eq!((&w).minus(outer(&s, &a).scale(0.1)).eval(), expected);
+
+            let mut updated = w.clone();
+            outer(&s, &a).scale(-0.1).add_to(&mut updated);
+            for r in 0..3 {
+                for c in 0..4 {
+                    assert!((updated[(r, c)] - expected[(r, c)]).abs() < EPS, "{w:?} at ({r},{c})");
+                }
+            }
+        }
+    }
+}
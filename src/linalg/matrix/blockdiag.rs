@@ -0,0 +1,210 @@
+use std::marker::PhantomData;
+use std::ops::{Index, Mul};
+
+use crate::linalg::{kernels, Scalar};
+use crate::linalg::vector::Vector;
+
+use super::Order;
+use super::gemm::{gemm, Strided};
+
+/// Square blocks along the main diagonal and zeros everywhere else. Block
+/// `k` covers rows and columns `offsets[k]..offsets[k + 1]` and is stored
+/// column-major, so everything but the blocks is implicit.
+#[derive(Clone, Debug)]
+pub struct BlockDiagonalMatrix<const R: usize, const C: usize, T: Scalar = f32> where [(); R*C]: Sized {
+    pub(super) offsets: Box<[usize]>,
+    pub(super) blocks: Box<[Box<[T]>]>,
+    size_marker: PhantomData<[[T; R]; C]>,
+}
+
+impl<const R: usize, const C: usize, T: Scalar> BlockDiagonalMatrix<R, C, T> where [(); R*C]: Sized {
+    // constructor
+    /// Takes `(size, entries)` for each block, top left to bottom right.
+    pub(super) fn from_blocks(blocks: impl IntoIterator<Item = (usize, Box<[T]>)>) -> Self {
+        let mut offsets = vec![0];
+        let blocks = blocks.into_iter().map(|(n, block)| {
+            assert_eq!(block.len(), n*n);
+            offsets.push(offsets.last().unwrap() + n);
+            block
+        }).collect();
+        assert_eq!(*offsets.last().unwrap(), R);
+        assert_eq!(R, C, "block diagonal matrices are square");
+        BlockDiagonalMatrix {
+            offsets: offsets.into_boxed_slice(),
+            blocks,
+            size_marker: PhantomData,
+        }
+    }
+
+    /// `(offset, size, entries)` of each block.
+    pub(super) fn blocks(&self) -> impl Iterator<Item = (usize, usize, &[T])> + '_ {
+        self.offsets.windows(2).zip(self.blocks.iter()).map(|(at, block)| (at[0], at[1] - at[0], &block[..]))
+    }
+
+    /// The block covering row or column `i`.
+    fn block_of(&self, i: usize) -> usize {
+        self.offsets.partition_point(|&at| at <= i) - 1
+    }
+
+    /// Builds a matrix with the same partition, block by block.
+    fn map_blocks<const R2: usize, const C2: usize>(&self, f: impl Fn(usize, usize, &[T]) -> Box<[T]>) -> BlockDiagonalMatrix<R2, C2, T>
+        where [(); R2*C2]: Sized
+    {
+        BlockDiagonalMatrix {
+            offsets: self.offsets.clone(),
+            blocks: self.blocks().map(|(at, n, block)| f(at, n, block)).collect(),
+            size_marker: PhantomData,
+        }
+    }
+
+    /// Combines the blocks of `self` and `other`, or gives `None` if their
+    /// partitions differ.
+    pub(super) fn zip_blocks<const R2: usize, const C2: usize, const R3: usize, const C3: usize>(
+        &self,
+        other: &BlockDiagonalMatrix<R2, C2, T>,
+        f: impl Fn(usize, &[T], &[T]) -> Box<[T]>,
+    ) -> Option<BlockDiagonalMatrix<R3, C3, T>>
+        where
+            [(); R2*C2]: Sized,
+            [(); R3*C3]: Sized,
+    {
+        (self.offsets == other.offsets).then(|| BlockDiagonalMatrix {
+            offsets: self.offsets.clone(),
+            blocks: self.blocks().zip(other.blocks()).map(|((_, n, a), (_, _, b))| f(n, a, b)).collect(),
+            size_marker: PhantomData,
+        })
+    }
+
+    pub(super) fn T(&self) -> BlockDiagonalMatrix<C, R, T> where [(); C*R]: Sized {
+        self.map_blocks(|_, n, block| (0..n*n).map(|i| block[(i % n)*n + i / n]).collect())
+    }
+
+    pub(super) fn map(&self, f: impl Fn(T) -> T) -> Self {
+        self.map_blocks(|_, _, block| block.iter().map(|&x| f(x)).collect())
+    }
+
+    /// Like `map`, with the position of each entry.
+    pub(super) fn map_indexed(&self, f: impl Fn(usize, usize, T) -> T) -> Self {
+        self.map_blocks(|at, n, block| {
+            block.iter().enumerate().map(|(i, &x)| f(at + i % n, at + i / n, x)).collect()
+        })
+    }
+
+    /// `diag(rows) * self * diag(cols)`, block by block.
+    pub(super) fn scale<const R2: usize, const C2: usize>(&self, rows: impl Fn(usize) -> T, cols: impl Fn(usize) -> T) -> BlockDiagonalMatrix<R2, C2, T>
+        where [(); R2*C2]: Sized
+    {
+        self.map_blocks(|at, n, block| {
+            (0..n*n).map(|i| rows(at + i % n) * block[i] * cols(at + i / n)).collect()
+        })
+    }
+
+    /// `self + diag(f)`, which only touches the diagonal of each block.
+    pub(super) fn add_diagonal(&self, f: impl Fn(usize) -> T) -> Self {
+        self.map_blocks(|at, n, block| {
+            let mut block: Box<[T]> = block.into();
+            for i in 0..n {
+                block[i*n + i] += f(at + i);
+            }
+            block
+        })
+    }
+
+    /// Every stored `(row, col, value)`, block by block.
+    pub(super) fn stored(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        self.blocks().flat_map(|(at, n, block)| {
+            block.iter().enumerate().map(move |(i, &x)| (at + i % n, at + i / n, x))
+        })
+    }
+
+    /// The stored `(position, value)` entries of row `i` for `Order::ROWS`,
+    /// or of column `i` for `Order::COLS`.
+    pub(super) fn line(&self, order: Order, i: usize) -> Vec<(usize, T)> {
+        let k = self.block_of(i);
+        let (at, n, block) = (self.offsets[k], self.offsets[k + 1] - self.offsets[k], &self.blocks[k]);
+        (0..n).map(|j| (at + j, match order {
+            Order::COLS => block[(i - at)*n + j],
+            Order::ROWS => block[j*n + i - at],
+        })).collect()
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&Vector<C, T>> for &BlockDiagonalMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Vector<R, T>;
+
+    /// Every nonzero of `rhs` is an axpy with one column of one block.
+    fn mul(self, rhs: &Vector<C, T>) -> Self::Output {
+        let mut product = vec![T::zero(); R].into_boxed_slice();
+        for (c, x) in rhs.nonzeros() {
+            let k = self.block_of(c);
+            let (at, n) = (self.offsets[k], self.offsets[k + 1] - self.offsets[k]);
+            let col = c - at;
+            kernels::axpy(&mut product[at..at + n], x, &self.blocks[k][col*n..(col + 1)*n]);
+        }
+        Vector::from_boxed_slice(product)
+    }
+}
+
+/// The blockwise product, for block diagonal matrices with the same partition.
+pub(super) fn multiply_blocks<T: Scalar>(n: usize, a: &[T], b: &[T]) -> Box<[T]> {
+    let mut product = vec![T::zero(); n*n];
+    gemm(n, n, n, Strided::new(a, Order::COLS, n, n), Strided::new(b, Order::COLS, n, n), &mut product);
+    product.into_boxed_slice()
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for BlockDiagonalMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(r < R && c < C);
+        let k = self.block_of(r);
+        let (at, end) = (self.offsets[k], self.offsets[k + 1]);
+        if (at..end).contains(&c) {
+            &self.blocks[k][(c - at)*(end - at) + r - at]
+        } else {
+            T::ZERO_REF
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::linalg::{Matrix, Vector};
+    use super::super::test_support::block_diagonal;
+
+    #[test]
+    fn direct_sums_keep_the_tightest_flavor() {
+        let m = block_diagonal();
+        let Matrix::BlockDiagonal(blocks) = &m else { panic!("{m:?}") };
+        assert_eq!(&blocks.offsets[..], [0, 2, 3, 4]);
+        assert_eq!(m[(1, 0)], -1.);
+        assert_eq!(m[(0, 2)], 0.);
+        assert_eq!(m[(3, 3)], -2.);
+
+        let diag = Matrix::<2, 2>::I().direct_sum(&Matrix::<1, 1>::diag(Vector::from_arr([5.])));
+        assert!(matches!(diag, Matrix::Diagonal(_)));
+        assert!(matches!(Matrix::<2, 2>::I().direct_sum(&Matrix::<3, 3>::I()), Matrix::Identity(_)));
+        assert!(matches!(Matrix::<2, 2>::zero().direct_sum(&Matrix::<1, 1>::zero()), Matrix::Zero(_)));
+    }
+
+    #[test]
+    fn arithmetic_works_block_by_block() {
+        let m = block_diagonal();
+        let dense = m.clone().densify();
+        assert!(matches!(&m * &m, Matrix::BlockDiagonal(_)));
+        assert!(matches!(&m + &Matrix::I(), Matrix::BlockDiagonal(_)));
+        assert!(matches!(m.T(), Matrix::BlockDiagonal(_)));
+        assert_eq!(&m * &m, &dense * &dense);
+        assert_eq!(m.T(), dense.T());
+
+        // A different partition of the same size falls back to sparse.
+        let other = Matrix::<1, 1>::from_arr([1.]).direct_sum(&Matrix::<3, 3>::constant(1.));
+        assert!(matches!(&m * &other, Matrix::Sparse(_)));
+        assert_eq!(&m * &other, &dense * &other.clone().densify());
+
+        let x = Vector::from_arr([1., 2., -1., 0.5]);
+        assert_eq!(&m * &x, &dense * &x);
+        assert_eq!(m.row(1).to_vector(), Vector::from_pairs([(0, -1.), (1, 3.)]));
+        assert_eq!(m.col(2).to_vector(), Vector::from_pairs([(2, 4.)]));
+    }
+}
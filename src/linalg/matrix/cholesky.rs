@@ -0,0 +1,107 @@
+use crate::linalg::{kernels, LinalgError, Scalar};
+
+use super::dense::DenseMatrix;
+use super::diagonal::DiagonalMatrix;
+use super::triangular::TriangularMatrix;
+use super::wrapper::Matrix;
+
+impl<const D: usize, T: Scalar> Matrix<D, D, T> where [(); D*D]: Sized {
+    /// The lower triangular `L` with `LLᵀ = self`, for symmetric positive
+    /// definite `self`. Only the lower triangle of `self` is read.
+    ///
+    /// Columns are computed left to right. Each one starts as the matching
+    /// column of `self` and has every previous column of `L` subtracted off
+    /// with a contiguous axpy, before being divided by its diagonal.
+    ///
+    /// The factor comes back as `Matrix::LowerTriangular`, so solves against
+    /// it and its transpose are substitutions.
+    pub fn cholesky(&self) -> Result<Matrix<D, D, T>, LinalgError> {
+        use Matrix as M;
+        match self {
+            M::Diagonal(m) => match m.diagonal_data.iter().position(|&d| d.is_nan() || d <= T::zero()) {
+                Some(column) => Err(LinalgError::NotPositiveDefinite { column }),
+                None => Ok(M::Diagonal(DiagonalMatrix::from_fun(|i| m.diagonal_data[i].sqrt()))),
+            },
+            M::Identity(m) if m.1 == T::one() => Ok(self.clone()),
+            M::Zero(_) if D > 0 => Err(LinalgError::NotPositiveDefinite { column: 0 }),
+            _ => {
+                let mut l = vec![T::zero(); D*D].into_boxed_slice();
+                for j in 0..D {
+                    let (done, rest) = l.split_at_mut(j*D);
+                    let col = &mut rest[..D];
+                    for (i, x) in col.iter_mut().enumerate().skip(j) {
+                        *x = self.get(i, j);
+                    }
+                    for prev in done.chunks_exact(D) {
+                        let x = prev[j];
+                        if x != T::zero() {
+                            kernels::axpy(&mut col[j..], -x, &prev[j..]);
+                        }
+                    }
+
+                    let d = col[j];
+                    if d.is_nan() || d <= T::zero() {
+                        return Err(LinalgError::NotPositiveDefinite { column: j });
+                    }
+                    let root = d.sqrt();
+                    col[j] = root;
+                    kernels::scale_assign(&mut col[j + 1..], T::one() / root);
+                }
+                Ok(M::LowerTriangular(TriangularMatrix(DenseMatrix::from_boxed_slice(l))))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::Vector;
+
+    #[test]
+    fn factor_times_its_transpose_is_input() {
+        let b = Matrix::<3, 3>::from_cols(&[
+            [2., 0., 1.],
+            [-1., 3., 0.5],
+            [0., 1., 4.],
+        ]);
+        let a = &(&b * &b.T()) + &Matrix::I();
+        let l = a.cholesky().unwrap();
+        assert!(matches!(l, Matrix::LowerTriangular(_)), "{l:?}");
+        let llt = &l * &l.T();
+        for r in 0..3 {
+            for c in 0..3 {
+                assert!((llt[(r, c)] - a[(r, c)]).abs() < 1e-4, "({r},{c})");
+                if c > r {
+                    assert_eq!(l[(r, c)], 0.);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn factor_solves_by_substitution() {
+        let a = Matrix::<3, 3>::from_cols(&[[4., 2., 0.], [2., 5., 1.], [0., 1., 3.]]);
+        let x = Vector::from_arr([1., -1., 2.]);
+        let b = &a * &x;
+        let l = a.cholesky().unwrap();
+        let y = l.solve(&b).unwrap();
+        let solved = l.T().solve(&y).unwrap();
+        assert!((&solved - &x).norm_l2() < 1e-5, "{solved:?}");
+    }
+
+    #[test]
+    fn indefinite_matrices_are_rejected() {
+        let indefinite = Matrix::<2, 2>::from_cols(&[[1., 2.], [2., 1.]]);
+        assert_eq!(indefinite.cholesky().unwrap_err(), LinalgError::NotPositiveDefinite { column: 1 });
+        assert!(Matrix::<2, 2>::zero().cholesky().is_err());
+        assert!(Matrix::diag(Vector::<2>::from_arr([4., -1.])).cholesky().is_err());
+    }
+
+    #[test]
+    fn diagonal_factor_stays_diagonal() {
+        let l = Matrix::diag(Vector::<2>::from_arr([4., 9.])).cholesky().unwrap();
+        assert!(matches!(l, Matrix::Diagonal(_)));
+        assert_eq!((l[(0, 0)], l[(1, 1)]), (2., 3.));
+    }
+}
@@ -1,5 +1,8 @@
 use std::fmt::Display;
-use std::ops::{Add, Mul, Neg, Sub};
+use std::ops::{Add, Index, Mul, Neg, Sub};
+
+use crate::linalg::{kernels, Scalar};
+use crate::linalg::vector::{ConstantVector, DenseVector, OneHotVector, SparseVector, ZeroVector};
 
 use super::dense::DenseMatrix;
 use super::diagonal::DiagonalMatrix;
@@ -8,294 +11,347 @@ use super::sparse::SparseMatrix;
 use super::zero::ZeroMatrix;
 
 #[derive(Clone, Debug)]
-pub struct ConstantMatrix<const R: usize, const C: usize>(
-    pub(super) f32,
+pub struct ConstantMatrix<const R: usize, const C: usize, T: Scalar = f32>(
+    pub(crate) T,
 );
 
-impl<const R: usize, const C: usize> ConstantMatrix<R, C> {
-    pub(super) fn T(&self) -> ConstantMatrix<C, R> {
+impl<const R: usize, const C: usize, T: Scalar> ConstantMatrix<R, C, T> {
+    pub(super) fn T(&self) -> ConstantMatrix<C, R, T> {
         ConstantMatrix(self.0)
     }
 }
 
-impl<const R: usize, const C: usize> PartialEq for ConstantMatrix<R, C> where [(); R*C]: Sized {
+impl<const R: usize, const C: usize, T: Scalar> PartialEq for ConstantMatrix<R, C, T> where [(); R*C]: Sized {
     fn eq(&self, other: &Self) -> bool {
         self.0 == other.0
     }
 }
 
 /////////////////////////////////
-/// CONSTANT MATRIX ADD IMPLS ///
+//  CONSTANT MATRIX ADD IMPLS  //
 /////////////////////////////////
 
-impl<const R: usize, const C: usize> Add<&ConstantMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ConstantMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ConstantMatrix<R, C>;
+    type Output = ConstantMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        ConstantMatrix(self.0 + rhs.0)
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DenseMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DenseMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        rhs + self.0
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DiagonalMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DiagonalMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ConstantMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        rhs + self.0
     }
 }
 
-impl<const R: usize, const C: usize> Add<&IdentityMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&IdentityMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ConstantMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        rhs + self.0
     }
 }
 
-impl<const R: usize, const C: usize> Add<&SparseMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&SparseMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        rhs + self.0
     }
 }
 
-impl<const R: usize, const C: usize> Add<&ZeroMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ZeroMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ZeroMatrix<R, C>;
+    type Output = ConstantMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 /////////////////////////////////
-/// CONSTANT MATRIX SUB IMPLS ///
+//  CONSTANT MATRIX SUB IMPLS  //
 /////////////////////////////////
 
-impl<const R: usize, const C: usize> Sub<&ConstantMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ConstantMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ConstantMatrix<R, C>;
+    type Output = ConstantMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        ConstantMatrix(self.0 - rhs.0)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DenseMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DenseMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        rhs.map(|x| self.0 - x)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DiagonalMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DiagonalMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ConstantMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        &(-rhs) + self.0
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&IdentityMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&IdentityMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ConstantMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        &(-rhs) + self.0
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&SparseMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&SparseMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        &(-rhs) + self.0
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&ZeroMatrix<R, C>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ZeroMatrix<R, C, T>> for &ConstantMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ZeroMatrix<R, C>;
+    type Output = ConstantMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 /////////////////////////////////
-/// CONSTANT MATRIX MUL IMPLS ///
+//  CONSTANT MATRIX MUL IMPLS  //
 /////////////////////////////////
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ConstantMatrix<C, C2>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ConstantMatrix<C, C2, T>> for &ConstantMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
 {
-    type Output = ConstantMatrix<R, C2>;
+    type Output = ConstantMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &ConstantMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &ConstantMatrix<C, C2, T>) -> Self::Output {
+        ConstantMatrix(self.0 * rhs.0 * T::from_usize(C))
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DenseMatrix<C, C2>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DenseMatrix<C, C2, T>> for &ConstantMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DenseMatrix<R, C2>;
+    type Output = DenseMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &DenseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &DenseMatrix<C, C2, T>) -> Self::Output {
+        // Every row of the product is the column sums of `rhs`, scaled.
+        let col_sums = rhs.col_sums();
+        DenseMatrix::from_fun(|_, c| self.0 * col_sums[c])
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DiagonalMatrix<C, C2>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DiagonalMatrix<C, C2, T>> for &ConstantMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
-        [(); R*C]: Sized,
+        [(); R*C2]: Sized,
 {
-    type Output = ConstantMatrix<R, C2>;
+    type Output = DenseMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &DiagonalMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &DiagonalMatrix<C, C2, T>) -> Self::Output {
+        DenseMatrix::from_fun(|_, c| {
+            if c < C.min(C2) { self.0 * rhs.diagonal_data[c] } else { T::zero() }
+        })
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&IdentityMatrix<C, C2>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&IdentityMatrix<C, C2, T>> for &ConstantMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = ConstantMatrix<R, C2>;
+    type Output = ConstantMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &IdentityMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &IdentityMatrix<C, C2, T>) -> Self::Output {
+        ConstantMatrix(self.0)
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&SparseMatrix<C, C2>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&SparseMatrix<C, C2, T>> for &ConstantMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
+        [(); R*C2]: Sized,
 {
-    type Output = SparseMatrix<R, C2>;
-
-    fn mul(self, _rhs: &SparseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    type Output = DenseMatrix<R, C2, T>;
+
+    #[allow(clippy::suspicious_arithmetic_impl)] // a product is a sum of products
+    fn mul(self, rhs: &SparseMatrix<C, C2, T>) -> Self::Output {
+        let mut col_sums = [T::zero(); C2];
+        for (_, c, x) in rhs.nonzeros() {
+            col_sums[c] += x;
+        }
+        DenseMatrix::from_fun(|_, c| self.0 * col_sums[c])
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ZeroMatrix<C, C2>> for &ConstantMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ZeroMatrix<C, C2, T>> for &ConstantMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &ZeroMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &ZeroMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-//////////////////////////////////
-/// CONSTANT MATRIX MATH IMPLS ///
-//////////////////////////////////
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ConstantVector<C, T>> for &ConstantMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ConstantVector<R, T>;
 
-impl<const R: usize, const C: usize> Add<&ConstantMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = ConstantMatrix<R, C>;
+    fn mul(self, rhs: &ConstantVector<C, T>) -> Self::Output {
+        ConstantVector(self.0 * rhs.0 * T::from_usize(C))
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&DenseVector<C, T>> for &ConstantMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ConstantVector<R, T>;
 
-    fn add(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &DenseVector<C, T>) -> Self::Output {
+        ConstantVector(self.0 * kernels::sum(&rhs.data))
     }
 }
 
-impl<const R: usize, const C: usize> Add<f32> for &ConstantMatrix<R, C> where [(); R*C]: Sized {
-    type Output = ConstantMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<&OneHotVector<C, T>> for &ConstantMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ConstantVector<R, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &OneHotVector<C, T>) -> Self::Output {
+        ConstantVector(self.0 * rhs.one)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<f32> for &ConstantMatrix<R, C> where [(); R*C]: Sized {
-    type Output = ConstantMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<&SparseVector<C, T>> for &ConstantMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ConstantVector<R, T>;
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &SparseVector<C, T>) -> Self::Output {
+        ConstantVector(self.0 * rhs.nonzeros().map(|(_, x)| x).sum::<T>())
     }
 }
 
-impl<const R: usize, const C: usize> Mul<&ConstantMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = ConstantMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ZeroVector<C, T>> for &ConstantMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ZeroVector<R, T>;
+
+    fn mul(self, _rhs: &ZeroVector<C, T>) -> Self::Output {
+        ZeroVector(T::zero())
+    }
+}
+
+//////////////////////////////////
+//  CONSTANT MATRIX MATH IMPLS  //
+//////////////////////////////////
+
+impl<const R: usize, const C: usize, T: Scalar> Add<T> for &ConstantMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = ConstantMatrix<R, C, T>;
 
-    fn mul(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: T) -> Self::Output {
+        ConstantMatrix(self.0 + rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Mul<f32> for &ConstantMatrix<R, C> where [(); R*C]: Sized {
-    type Output = ConstantMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Sub<T> for &ConstantMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = ConstantMatrix<R, C, T>;
 
-    fn mul(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        ConstantMatrix(self.0 - rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Neg for &ConstantMatrix<R, C> where [(); R*C]: Sized {
-    type Output = ConstantMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<T> for &ConstantMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = ConstantMatrix<R, C, T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        ConstantMatrix(self.0 * rhs)
+    }
+}
+
+scalar_lhs_ops!(ConstantMatrix, add: ConstantMatrix, mul: ConstantMatrix);
+
+impl<const R: usize, const C: usize, T: Scalar> Neg for &ConstantMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = ConstantMatrix<R, C, T>;
 
     fn neg(self) -> Self::Output {
-        todo!()
+        ConstantMatrix(-self.0)
     }
 }
 
 /////////////////////////////////////
-/// CONSTANT MATRIX UTILITY IMPLS ///
+//  CONSTANT MATRIX UTILITY IMPLS  //
 /////////////////////////////////////
 
-impl<const R: usize, const C: usize> Display for ConstantMatrix<R, C> where [(); R*C]: Sized {
+impl<const R: usize, const C: usize, T: Scalar> Display for ConstantMatrix<R, C, T> where [(); R*C]: Sized {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}]", (0..R).into_iter()
-            .map(|_| std::iter::repeat(&self.0)
-                .map(<f32>::to_string)
-                .take(C)
+        write!(f, "[{}]", (0..R)
+            .map(|_| std::iter::repeat_n(&self.0, C)
+                .map(<T>::to_string)
                 .collect::<Vec<_>>()
                 .join(","))
             .collect::<Vec<_>>()
             .join("],["))
     }
 }
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for ConstantMatrix<R, C, T> {
+    type Output = T;
+
+    fn index(&self, _index: (usize, usize)) -> &Self::Output {
+        &self.0
+    }
+}
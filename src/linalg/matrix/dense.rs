@@ -1,10 +1,14 @@
 use std::fmt::Display;
 use std::marker::PhantomData;
-use std::ops::{Add, Mul, Neg, Sub};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Range, Sub};
 
-use crate::linalg::vector::DenseVector;
+use crate::linalg::{kernels, parallel, Scalar};
+use crate::linalg::vector::{ConstantVector, DenseVector, OneHotVector, SparseVector, Vector, ZeroVector};
+use crate::linalg::vector::traits::{CanStackHorizontally, CanStackVertically};
 
 use super::Order;
+use super::gemm::{gemm, Strided};
+use super::view::DenseMatrixView;
 
 use super::constant::ConstantMatrix;
 use super::diagonal::DiagonalMatrix;
@@ -13,17 +17,17 @@ use super::sparse::SparseMatrix;
 use super::zero::ZeroMatrix;
 
 #[derive(Clone, Debug)]
-pub struct DenseMatrix<const R: usize, const C: usize> where [(); R*C]: Sized {
-    pub(super) data: Box<[f32]>,
+pub struct DenseMatrix<const R: usize, const C: usize, T: Scalar = f32> where [(); R*C]: Sized {
+    pub(super) data: Box<[T]>,
     pub(super) order: Order,
-    pub(super) size_marker: PhantomData<[[f32; R]; C]>,
+    pub(super) size_marker: PhantomData<[[T; R]; C]>,
 }
 
-impl<const R: usize, const C: usize> DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
     // constructor
-    pub(crate) fn from_arr(arr: [f32; R*C]) -> Self {
+    pub(crate) fn from_arr(arr: [T; R*C]) -> Self {
         DenseMatrix {
             data: Box::new(arr),
             order: Order::COLS,
@@ -32,7 +36,7 @@ impl<const R: usize, const C: usize> DenseMatrix<R, C>
     }
 
     // constructor
-    pub(crate) fn from_boxed_slice(slice: Box<[f32]>) -> Self {
+    pub(crate) fn from_boxed_slice(slice: Box<[T]>) -> Self {
         assert_eq!(slice.len(), R*C);
         DenseMatrix {
             data: slice,
@@ -42,12 +46,11 @@ impl<const R: usize, const C: usize> DenseMatrix<R, C>
     }
 
     // constructor
-    pub(super) fn from_cols(cols: &[[f32; R]; C]) -> Self {
-        let mut arr = [0f32; R*C];
-        for c_ind in 0..C {
+    pub(super) fn from_cols(cols: &[[T; R]; C]) -> Self {
+        let mut arr = [T::zero(); R*C];
+        for (c_ind, col) in cols.iter().enumerate() {
             let from = c_ind * R;
             let to = from + R;
-            let col = &cols[c_ind];
             arr[from..to].copy_from_slice(col);
         }
         DenseMatrix {
@@ -57,385 +60,747 @@ impl<const R: usize, const C: usize> DenseMatrix<R, C>
         }
     }
     
-    pub(super) fn T(&self) -> DenseMatrix<C, R> where [(); C*R]: Sized {
+    // constructor
+    pub(super) fn from_fun(f: impl Fn(usize, usize) -> T) -> Self {
+        let mut container = Vec::with_capacity(R*C);
+        for c in 0..C {
+            container.extend((0..R).map(|r| f(r, c)));
+        }
+        DenseMatrix {
+            data: container.into_boxed_slice(),
+            order: Order::COLS,
+            size_marker: PhantomData,
+        }
+    }
+
+    pub(super) fn T(&self) -> DenseMatrix<C, R, T> where [(); C*R]: Sized {
         DenseMatrix {
             data: self.data.clone(),
-            order: Order::ROWS,
+            order: -self.order,
             size_marker: PhantomData,
         }
     }
+
+    /// Allocates storage in the given order and lets `kernel` fill it in.
+    fn from_kernel(order: Order, kernel: impl FnOnce(&mut [T])) -> Self {
+        let mut data = vec![T::zero(); R*C].into_boxed_slice();
+        kernel(&mut data);
+        DenseMatrix { data, order, size_marker: PhantomData }
+    }
+
+    /// Like `from_kernel` for elementwise kernels, which get handed the
+    /// range of storage each output chunk corresponds to, so that large
+    /// matrices can be split across threads.
+    fn from_elementwise(order: Order, kernel: impl Fn(Range<usize>, &mut [T]) + Sync) -> Self {
+        DenseMatrix::from_kernel(order, |out| {
+            parallel::for_each_chunk(out, 1, R*C, |at, out| kernel(at..at + out.len(), out))
+        })
+    }
+
+    /// Applies `f` elementwise. The result keeps the storage order of `self`.
+    pub(super) fn map(&self, f: impl Fn(T) -> T) -> Self {
+        DenseMatrix::from_kernel(self.order, |out| kernels::map(&self.data, f, out))
+    }
+
+    pub(super) fn map_in_place(&mut self, f: impl Fn(T) -> T) {
+        kernels::map_assign(&mut self.data, f);
+    }
+
+    /// `self += alpha * x`, streaming through storage when the orders agree.
+    pub(super) fn axpy(&mut self, alpha: T, x: &DenseMatrix<R, C, T>) {
+        if self.order == x.order {
+            kernels::axpy(&mut self.data, alpha, &x.data);
+        } else {
+            for c in 0..C {
+                for r in 0..R {
+                    self[(r, c)] += alpha * x[(r, c)];
+                }
+            }
+        }
+    }
+
+    /// `self += alpha * u.outer(v)` without building the outer product.
+    /// Each major line of storage gets one axpy, and lines whose scale
+    /// vanishes, e.g. for sparse `u` or `v`, are skipped outright.
+    pub(super) fn add_outer(&mut self, alpha: T, u: &Vector<R, T>, v: &Vector<C, T>) {
+        match self.order {
+            Order::COLS => parallel::for_each_chunk(&mut self.data, R, R*C, |c0, cols| {
+                for (c, col) in cols.chunks_exact_mut(R).enumerate() {
+                    let y = v[c0 + c];
+                    if y != T::zero() {
+                        u.axpy_into(alpha * y, col);
+                    }
+                }
+            }),
+            Order::ROWS => parallel::for_each_chunk(&mut self.data, C, R*C, |r0, rows| {
+                for (r, row) in rows.chunks_exact_mut(C).enumerate() {
+                    let x = u[r0 + r];
+                    if x != T::zero() {
+                        v.axpy_into(alpha * x, row);
+                    }
+                }
+            }),
+        }
+    }
+
+    /// Combines `self` and `other` elementwise. Storage is zipped directly
+    /// when both matrices agree on order, otherwise `other` is read by index.
+    pub(super) fn zip_map(&self, other: &DenseMatrix<R, C, T>, f: impl Fn(T, T) -> T) -> Self {
+        if self.order == other.order {
+            let us = self.data.iter().copied();
+            let them = other.data.iter().copied();
+            DenseMatrix {
+                data: us.zip(them).map(|(a, b)| f(a, b)).collect(),
+                order: self.order,
+                size_marker: PhantomData,
+            }
+        } else {
+            DenseMatrix::from_fun(|r, c| f(self[(r, c)], other[(r, c)]))
+        }
+    }
+
+    /// `out += alpha * (self * x)[r0..r0 + out.len()]`, i.e. one run of
+    /// output rows of a matrix-vector product, with the same per-entry
+    /// arithmetic as `self * x`. Sparse flavors of `x` only cost their nonzeros.
+    pub(in crate::linalg) fn gemv_into(&self, alpha: T, x: &Vector<C, T>, r0: usize, out: &mut [T]) {
+        let rows = r0..r0 + out.len();
+        match (self.order, x) {
+            (Order::COLS, Vector::Dense(x)) => for (col, &y) in self.data.chunks_exact(R).zip(x.data.iter()) {
+                kernels::axpy(out, alpha * y, &col[rows.clone()]);
+            },
+            (Order::ROWS, Vector::Dense(x)) => for (acc, row) in out.iter_mut().zip(self.data.chunks_exact(C).skip(r0)) {
+                *acc += alpha * kernels::dot(row, &x.data);
+            },
+            (Order::COLS, _) => for (c, y) in x.nonzeros() {
+                kernels::axpy(out, alpha * y, &self.data[c*R + r0..c*R + rows.end]);
+            },
+            (Order::ROWS, _) => for (acc, row) in out.iter_mut().zip(self.data.chunks_exact(C).skip(r0)) {
+                *acc += alpha * x.nonzeros().map(|(c, y)| row[c] * y).sum::<T>();
+            },
+        }
+    }
+
+    /// Entrywise product, in the storage order of `self`.
+    pub(super) fn hadamard(&self, other: &DenseMatrix<R, C, T>) -> Self {
+        if self.order == other.order {
+            DenseMatrix::from_elementwise(self.order, |at, out| {
+                kernels::mul(&self.data[at.clone()], &other.data[at], out)
+            })
+        } else {
+            self.zip_map(other, |a, b| a * b)
+        }
+    }
+
+    /// Column-major Kronecker product. Each column of the result is a stack
+    /// of scaled copies of one column of `other`, so it is filled by `scale`.
+    pub(super) fn kron<const R2: usize, const C2: usize>(&self, other: &DenseMatrix<R2, C2, T>) -> DenseMatrix<{R*R2}, {C*C2}, T>
+        where
+            [(); R2*C2]: Sized,
+            [(); (R*R2)*(C*C2)]: Sized,
+    {
+        let other_cols: Vec<Box<[T]>> = (0..C2).map(|l| other.line(Order::COLS, l)).collect();
+        DenseMatrix::from_kernel(Order::COLS, |out| {
+            for (jl, col) in out.chunks_exact_mut(R*R2).enumerate() {
+                let (j, l) = (jl / C2, jl % C2);
+                for (i, block) in col.chunks_exact_mut(R2).enumerate() {
+                    kernels::scale(&other_cols[l], self[(i, j)], block);
+                }
+            }
+        })
+    }
+
+    /// Borrows the `RN × CN` block at `(R0, C0)` in place, in the order of `self`.
+    pub(super) fn view<const R0: usize, const C0: usize, const RN: usize, const CN: usize>(&self) -> DenseMatrixView<'_, RN, CN, T>
+        where [(); RN*CN]: Sized
+    {
+        match self.order {
+            Order::COLS => DenseMatrixView::new(&self.data, C0*R + R0, R, Order::COLS),
+            Order::ROWS => DenseMatrixView::new(&self.data, R0*C + C0, C, Order::ROWS),
+        }
+    }
+
+    /// Copies out row `i` for `Order::ROWS`, or column `i` for `Order::COLS`.
+    /// Free of gathers when `order` matches the storage.
+    pub(super) fn line(&self, order: Order, i: usize) -> Box<[T]> {
+        match (order, self.order) {
+            (Order::COLS, Order::COLS) => self.data[i*R..(i + 1)*R].into(),
+            (Order::ROWS, Order::ROWS) => self.data[i*C..(i + 1)*C].into(),
+            (Order::COLS, Order::ROWS) => (0..R).map(|r| self.data[r*C + i]).collect(),
+            (Order::ROWS, Order::COLS) => (0..C).map(|c| self.data[c*R + i]).collect(),
+        }
+    }
+
+    /// Reads the storage in place, whatever its order.
+    pub(super) fn strided(&self) -> Strided<'_, T> {
+        Strided::new(&self.data, self.order, R, C)
+    }
+
+    pub(super) fn row_sums(&self) -> Vec<T> {
+        self.axis_sums(Order::ROWS)
+    }
+
+    pub(super) fn col_sums(&self) -> Vec<T> {
+        self.axis_sums(Order::COLS)
+    }
+
+    /// Sums of every row (`Order::ROWS`) or column (`Order::COLS`). Along
+    /// the storage order each line is one `sum`, across it the lines of
+    /// storage are added up with `add_assign`.
+    fn axis_sums(&self, order: Order) -> Vec<T> {
+        let stride = match self.order {
+            Order::COLS => R,
+            Order::ROWS => C,
+        };
+        if order == self.order {
+            return self.data.chunks_exact(stride).map(kernels::sum).collect();
+        }
+        let mut sums = vec![T::zero(); stride];
+        for line in self.data.chunks_exact(stride) {
+            kernels::add_assign(&mut sums, line);
+        }
+        sums
+    }
 }
 
-impl<const R: usize, const C: usize> PartialEq for DenseMatrix<R, C> where [(); R*C]: Sized {
+impl<const R: usize, const C: usize, T: Scalar> PartialEq for DenseMatrix<R, C, T> where [(); R*C]: Sized {
     fn eq(&self, other: &Self) -> bool {
-        self.data == other.data && self.order == other.order // TODO: handle transposed args.
+        if self.order == other.order {
+            self.data == other.data
+        } else {
+            (0..R).all(|r| (0..C).all(|c| self[(r, c)] == other[(r, c)]))
+        }
     }
 }
 
 //////////////////////////////
-/// DENSE MATRIX ADD IMPLS ///
+//  DENSE MATRIX ADD IMPLS  //
 //////////////////////////////
 
-impl<const R: usize, const C: usize> Add<&ConstantMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ConstantMatrix<R, C, T>> for &DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        self + rhs.0
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DenseMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DenseMatrix<R, C, T>> for &DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
+
+    fn add(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        if self.order == rhs.order {
+            DenseMatrix::from_elementwise(self.order, |at, out| {
+                kernels::add(&self.data[at.clone()], &rhs.data[at], out)
+            })
+        } else {
+            self.zip_map(rhs, |a, b| a + b)
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Add<&DiagonalMatrix<R, C, T>> for &DenseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        let mut sum = self.clone();
+        sum += rhs;
+        sum
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DiagonalMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&IdentityMatrix<R, C, T>> for &DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        let mut sum = self.clone();
+        sum += rhs;
+        sum
     }
 }
 
-impl<const R: usize, const C: usize> Add<&IdentityMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&SparseMatrix<R, C, T>> for &DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        let mut sum = self.clone();
+        sum += rhs;
+        sum
     }
 }
 
-impl<const R: usize, const C: usize> Add<&SparseMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ZeroMatrix<R, C, T>> for &DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        self.clone()
     }
 }
 
-impl<const R: usize, const C: usize> Add<&ZeroMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> AddAssign<&ConstantMatrix<R, C, T>> for DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    fn add_assign(&mut self, rhs: &ConstantMatrix<R, C, T>) {
+        *self += rhs.0;
+    }
+}
 
-    fn add(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+impl<const R: usize, const C: usize, T: Scalar> AddAssign<&DenseMatrix<R, C, T>> for DenseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    fn add_assign(&mut self, rhs: &DenseMatrix<R, C, T>) {
+        if self.order == rhs.order {
+            kernels::add_assign(&mut self.data, &rhs.data);
+        } else {
+            for c in 0..C {
+                for r in 0..R {
+                    self[(r, c)] += rhs[(r, c)];
+                }
+            }
+        }
     }
 }
 
-//////////////////////////////
-/// DENSE MATRIX SUB IMPLS ///
-//////////////////////////////
+impl<const R: usize, const C: usize, T: Scalar> AddAssign<&DiagonalMatrix<R, C, T>> for DenseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    fn add_assign(&mut self, rhs: &DiagonalMatrix<R, C, T>) {
+        for (i, d) in rhs.diagonal_data.iter().enumerate().take(R.min(C)) {
+            self[(i, i)] += *d;
+        }
+    }
+}
 
-impl<const R: usize, const C: usize> Sub<&ConstantMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> AddAssign<&IdentityMatrix<R, C, T>> for DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    fn add_assign(&mut self, rhs: &IdentityMatrix<R, C, T>) {
+        for i in 0..R.min(C) {
+            self[(i, i)] += rhs.1;
+        }
+    }
+}
 
-    fn sub(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+impl<const R: usize, const C: usize, T: Scalar> AddAssign<&SparseMatrix<R, C, T>> for DenseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    fn add_assign(&mut self, rhs: &SparseMatrix<R, C, T>) {
+        for (r, c, x) in rhs.nonzeros() {
+            self[(r, c)] += x;
+        }
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DenseMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> AddAssign<T> for DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    fn add_assign(&mut self, rhs: T) {
+        kernels::offset_assign(&mut self.data, rhs);
+    }
+}
 
-    fn sub(self, rhs: &DenseMatrix<R, C>) -> Self::Output {
-        let f = |(a, b)| a - b;
-        let us = self.data.iter().copied();
-        let them = rhs.data.iter().copied();
+//////////////////////////////
+//  DENSE MATRIX SUB IMPLS  //
+//////////////////////////////
 
-        let mut container = Vec::with_capacity(R*C);
-        container.extend(us.zip(them).map(f));
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ConstantMatrix<R, C, T>> for &DenseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = DenseMatrix<R, C, T>;
 
-        DenseMatrix {
-            data: container.into_boxed_slice(),
-            order: self.order,
-            size_marker: PhantomData,
+    fn sub(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        self - rhs.0
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DenseMatrix<R, C, T>> for &DenseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = DenseMatrix<R, C, T>;
+
+    fn sub(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        if self.order == rhs.order {
+            DenseMatrix::from_elementwise(self.order, |at, out| {
+                kernels::sub(&self.data[at.clone()], &rhs.data[at], out)
+            })
+        } else {
+            self.zip_map(rhs, |a, b| a - b)
         }
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DiagonalMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DiagonalMatrix<R, C, T>> for &DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        self + &(-rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&IdentityMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&IdentityMatrix<R, C, T>> for &DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        self + &(-rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&SparseMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&SparseMatrix<R, C, T>> for &DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        let mut difference = self.clone();
+        for (r, c, x) in rhs.nonzeros() {
+            difference[(r, c)] -= x;
+        }
+        difference
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&ZeroMatrix<R, C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ZeroMatrix<R, C, T>> for &DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 //////////////////////////////
-/// DENSE MATRIX MUL IMPLS ///
+//  DENSE MATRIX MUL IMPLS  //
 //////////////////////////////
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ConstantMatrix<C, C2>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ConstantMatrix<C, C2, T>> for &DenseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DenseMatrix<R, C2>;
+    type Output = DenseMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &ConstantMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &ConstantMatrix<C, C2, T>) -> Self::Output {
+        // Every column of the product is the row sums of `self`, scaled.
+        let row_sums = self.row_sums();
+        DenseMatrix::from_fun(|r, _| row_sums[r] * rhs.0)
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DenseMatrix<C, C2>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DenseMatrix<C, C2, T>> for &DenseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DenseMatrix<R, C2>;
-
-    fn mul(self, rhs: &DenseMatrix<C, C2>) -> Self::Output {
-        // TODO: handle transposed args.
-        let product = (0..R*C2).map(|i| {
-            let from = (i / R) * C;
-            let to = from + C;
-            rhs.data[from..to].into_iter()
-               .enumerate()
-               .map(|(j, x)| x * self.data[j * R + i % R])
-               .sum::<f32>()
-        });
-
-        let mut container = Vec::with_capacity(R*C2);
-        container.extend(product);
+    type Output = DenseMatrix<R, C2, T>;
 
-        DenseMatrix {
-            data: container.into_boxed_slice(),
-            order: self.order,
-            size_marker: PhantomData,
-        }
-
-        // let mut arr = [0f32; R*C2];
-        // for i in 0..(R * C2) {
-        //     let from = (i / R) * C;
-        //     let to = from + C;
-        //     let x: f32 = rhs.0[from..to]
-        //         .into_iter()
-        //         .enumerate()
-        //         .map(|(j, x)| x * self.0[j * R + i % R])
-        //         .sum();
-        //     arr[i] = x;
-        // }
-        // DenseMatrix(arr, self.1)
+    fn mul(self, rhs: &DenseMatrix<C, C2, T>) -> Self::Output {
+        let mut product = vec![T::zero(); R*C2];
+        gemm(R, C, C2, self.strided(), rhs.strided(), &mut product);
+        DenseMatrix::from_boxed_slice(product.into_boxed_slice())
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DiagonalMatrix<C, C2>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DiagonalMatrix<C, C2, T>> for &DenseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DenseMatrix<R, C2>;
+    type Output = DenseMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &DiagonalMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &DiagonalMatrix<C, C2, T>) -> Self::Output {
+        // Right-multiplying by a diagonal matrix scales the columns of `self`.
+        DenseMatrix::from_fun(|r, c| {
+            if c < C.min(C2) { self[(r, c)] * rhs.diagonal_data[c] } else { T::zero() }
+        })
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&IdentityMatrix<C, C2>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&IdentityMatrix<C, C2, T>> for &DenseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DenseMatrix<R, C2>;
+    type Output = DenseMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &IdentityMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &IdentityMatrix<C, C2, T>) -> Self::Output {
+        // Identity matrices are always square, so this is just a copy.
+        debug_assert_eq!(C, C2);
+        DenseMatrix {
+            data: self.data.clone(),
+            order: self.order,
+            size_marker: PhantomData,
+        }
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&SparseMatrix<C, C2>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&SparseMatrix<C, C2, T>> for &DenseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DenseMatrix<R, C2>;
-
-    fn mul(self, _rhs: &SparseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    type Output = DenseMatrix<R, C2, T>;
+
+    fn mul(self, rhs: &SparseMatrix<C, C2, T>) -> Self::Output {
+        // Each nonzero (k, c) of `rhs` adds a scaled column k of `self` to column c.
+        let mut data = vec![T::zero(); R*C2];
+        for (k, c, x) in rhs.nonzeros() {
+            let col = &mut data[c*R..(c+1)*R];
+            match self.order {
+                Order::COLS => for (acc, &m) in col.iter_mut().zip(&self.data[k*R..(k+1)*R]) {
+                    *acc += m * x;
+                },
+                Order::ROWS => for (r, acc) in col.iter_mut().enumerate() {
+                    *acc += self.data[r*C + k] * x;
+                },
+            }
+        }
+        DenseMatrix {
+            data: data.into_boxed_slice(),
+            order: Order::COLS,
+            size_marker: PhantomData,
+        }
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ZeroMatrix<C, C2>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ZeroMatrix<C, C2, T>> for &DenseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &ZeroMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &ZeroMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize> Mul<&DenseVector<C>> for &DenseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ConstantVector<C, T>> for &DenseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseVector<R>;
+    type Output = DenseVector<R, T>;
 
-    fn mul(self, rhs: &DenseVector<C>) -> Self::Output {
-        let product = (0..R).map(|i| {
-            rhs.data.into_iter()
-               .enumerate()
-               .map(|(j, x)| x * self.data[j * R + i])
-               .sum::<f32>()
+    fn mul(self, rhs: &ConstantVector<C, T>) -> Self::Output {
+        let row_sums = self.row_sums();
+        DenseVector::from_fun(|r| row_sums[r] * rhs.0)
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&DenseVector<C, T>> for &DenseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = DenseVector<R, T>;
+
+    #[allow(clippy::suspicious_arithmetic_impl)] // a product is a sum of products
+    fn mul(self, rhs: &DenseVector<C, T>) -> Self::Output {
+        let mut container = vec![T::zero(); R];
+        // Threads get disjoint runs of output rows.
+        parallel::for_each_chunk(&mut container, 1, R*C, |r0, out| match self.order {
+            // Accumulate scaled columns, which are contiguous in storage.
+            Order::COLS => for (col, &x) in self.data.chunks_exact(R).zip(rhs.data.iter()) {
+                kernels::axpy(out, x, &col[r0..r0 + out.len()]);
+            },
+            // Rows are contiguous, so every entry is a single dot product.
+            Order::ROWS => for (acc, row) in out.iter_mut().zip(self.data.chunks_exact(C).skip(r0)) {
+                *acc = kernels::dot(row, &rhs.data);
+            },
         });
+        DenseVector::from_boxed_slice(container.into_boxed_slice())
+    }
+}
 
-        let mut container = Vec::with_capacity(R);
-        container.extend(product);
+impl<const R: usize, const C: usize, T: Scalar> Mul<&OneHotVector<C, T>> for &DenseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = DenseVector<R, T>;
+
+    fn mul(self, rhs: &OneHotVector<C, T>) -> Self::Output {
+        // Picking out a single column, no arithmetic needed unless `one` was scaled.
+        let c = rhs.index;
+        match self.order {
+            Order::COLS => DenseVector::from_boxed_slice(self.data[c*R..(c+1)*R].iter()
+                .map(|&m| m * rhs.one)
+                .collect()),
+            Order::ROWS => DenseVector::from_fun(|r| self.data[r*C + c] * rhs.one),
+        }
+    }
+}
 
-        DenseVector {
-            data: container.into_boxed_slice(),
-            size_marker: PhantomData,
+impl<const R: usize, const C: usize, T: Scalar> Mul<&SparseVector<C, T>> for &DenseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = DenseVector<R, T>;
+
+    fn mul(self, rhs: &SparseVector<C, T>) -> Self::Output {
+        let mut container = vec![T::zero(); R];
+        for (c, x) in rhs.nonzeros() {
+            for (r, acc) in container.iter_mut().enumerate() {
+                *acc += self[(r, c)] * x;
+            }
         }
+        DenseVector::from_boxed_slice(container.into_boxed_slice())
     }
 }
 
-///////////////////////////////
-/// DENSE MATRIX MATH IMPLS ///
-///////////////////////////////
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ZeroVector<C, T>> for &DenseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ZeroVector<R, T>;
 
-impl<const R: usize, const C: usize> Add<&DenseMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+    fn mul(self, _rhs: &ZeroVector<C, T>) -> Self::Output {
+        ZeroVector(T::zero())
+    }
+}
 
-    fn add(self, rhs: &DenseMatrix<R, C>) -> Self::Output {
-        let f = |x| x + self;
-        let data = rhs.data.iter().map(f);
+///////////////////////////////
+//  DENSE MATRIX MATH IMPLS  //
+///////////////////////////////
 
-        let mut container = Vec::with_capacity(R*C);
-        container.extend(data);
+impl<const R: usize, const C: usize, T: Scalar> Add<T> for &DenseMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DenseMatrix<R, C, T>;
 
-        DenseMatrix {
-            data: container.into_boxed_slice(),
-            order: rhs.order,
-            size_marker: PhantomData,
-        }
+    fn add(self, rhs: T) -> Self::Output {
+        DenseMatrix::from_elementwise(self.order, |at, out| kernels::offset(&self.data[at], rhs, out))
     }
 }
 
-impl<const R: usize, const C: usize> Add<f32> for &DenseMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Sub<T> for &DenseMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        self + (-rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<f32> for &DenseMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<T> for &DenseMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: T) -> Self::Output {
+        DenseMatrix::from_elementwise(self.order, |at, out| kernels::scale(&self.data[at], rhs, out))
     }
 }
 
-impl<const R: usize, const C: usize> Mul<&DenseMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+scalar_lhs_ops!(DenseMatrix, add: DenseMatrix, mul: DenseMatrix);
 
-    fn mul(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+impl<const R: usize, const C: usize, T: Scalar> MulAssign<T> for DenseMatrix<R, C, T> where [(); R*C]: Sized {
+    fn mul_assign(&mut self, rhs: T) {
+        kernels::scale_assign(&mut self.data, rhs);
     }
 }
 
-impl<const R: usize, const C: usize> Mul<f32> for &DenseMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Neg for &DenseMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DenseMatrix<R, C, T>;
 
-    fn mul(self, rhs: f32) -> Self::Output {
-        let f = |x| x * rhs;
-        let data = self.data.iter().map(f);
+    fn neg(self) -> Self::Output {
+        self * -T::one()
+    }
+}
 
-        let mut container = Vec::with_capacity(R*C);
-        container.extend(data);
+////////////////////////////////
+//  DENSE MATRIX STACK IMPLS  //
+////////////////////////////////
 
-        DenseMatrix {
-            data: container.into_boxed_slice(),
-            order: self.order,
-            size_marker: PhantomData,
-        }
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> CanStackHorizontally<&DenseMatrix<R, C2, T>> for DenseMatrix<R, C, T>
+    where
+        [(); R*C]: Sized,
+        [(); R*C2]: Sized,
+        [(); R*(C+C2)]: Sized,
+{
+    type Output = DenseMatrix<R, {C+C2}, T>;
+
+    /// Column-major storage is appended to as is, row-major storage gets
+    /// its rows extended one by one.
+    fn hstack(&self, other: &DenseMatrix<R, C2, T>) -> Self::Output {
+        let data: Box<[T]> = match (self.order, other.order) {
+            (Order::COLS, Order::COLS) => self.data.iter().chain(other.data.iter()).copied().collect(),
+            (Order::ROWS, Order::ROWS) => (0..R)
+                .flat_map(|r| self.data[r*C..(r + 1)*C].iter().chain(&other.data[r*C2..(r + 1)*C2]))
+                .copied()
+                .collect(),
+            _ => return DenseMatrix::from_fun(|r, c| if c < C { self[(r, c)] } else { other[(r, c - C)] }),
+        };
+        DenseMatrix { data, order: self.order, size_marker: PhantomData }
     }
 }
 
-impl<const R: usize, const C: usize> Neg for &DenseMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
-
-    fn neg(self) -> Self::Output {
-        todo!()
+impl<const R: usize, const C: usize, const R2: usize, T: Scalar> CanStackVertically<&DenseMatrix<R2, C, T>> for DenseMatrix<R, C, T>
+    where
+        [(); R*C]: Sized,
+        [(); R2*C]: Sized,
+        [(); (R+R2)*C]: Sized,
+{
+    type Output = DenseMatrix<{R+R2}, C, T>;
+
+    /// The transpose of `hstack`: row-major storage is appended to as is.
+    fn vstack(&self, other: &DenseMatrix<R2, C, T>) -> Self::Output {
+        let data: Box<[T]> = match (self.order, other.order) {
+            (Order::ROWS, Order::ROWS) => self.data.iter().chain(other.data.iter()).copied().collect(),
+            (Order::COLS, Order::COLS) => (0..C)
+                .flat_map(|c| self.data[c*R..(c + 1)*R].iter().chain(&other.data[c*R2..(c + 1)*R2]))
+                .copied()
+                .collect(),
+            _ => return DenseMatrix::from_fun(|r, c| if r < R { self[(r, c)] } else { other[(r - R, c)] }),
+        };
+        DenseMatrix { data, order: self.order, size_marker: PhantomData }
     }
 }
 
 //////////////////////////////////
-/// DENSE MATRIX UTILITY IMPLS ///
+//  DENSE MATRIX UTILITY IMPLS  //
 //////////////////////////////////
 
-impl<const R: usize, const C: usize> Display for DenseMatrix<R, C> where [(); R*C]: Sized {
+impl<const R: usize, const C: usize, T: Scalar> Display for DenseMatrix<R, C, T> where [(); R*C]: Sized {
     /// Displays the rows of a dense matrix.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[")?;
         for r in 0..R {
-            let mut arr = [0f32; C];
-            for c in 0..C {
-                arr[c] = self.data[c*R+r];
-            }
-            write!(f, "[{}]", arr.map(|n| n.to_string()).join(","))?;
+            let row = (0..C).map(|c| self[(r, c)].to_string());
+            write!(f, "[{}]", row.collect::<Vec<_>>().join(","))?;
         }
         write!(f, "]")
     }
 }
 
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for DenseMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        match self.order {
+            Order::COLS => &self.data[c * R + r],
+            Order::ROWS => &self.data[r * C + c],
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> IndexMut<(usize, usize)> for DenseMatrix<R, C, T> where [(); R*C]: Sized {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut Self::Output {
+        match self.order {
+            Order::COLS => &mut self.data[c * R + r],
+            Order::ROWS => &mut self.data[r * C + c],
+        }
+    }
+}
+
 mod tests {
     #[test]
     fn dense_matrix_multiply() {
@@ -460,6 +825,32 @@ mod tests {
         assert_eq!(&m1 * &m2, expected);
     }
 
+    #[test]
+    fn dense_matrix_blocked_multiply_matches_naive() {
+        use super::DenseMatrix;
+
+        // Big enough to span several tiles, with ragged edges everywhere.
+        let a = DenseMatrix::<67, 261>::from_fun(|r, c| ((r * 7 + c * 3) % 11) as f32 - 5.25);
+        let b = DenseMatrix::<261, 6>::from_fun(|r, c| ((r * 5 + c * 13) % 17) as f32 * 0.125);
+        let at = DenseMatrix::<261, 67>::from_fun(|r, c| a[(c, r)]).T();
+        let bt = DenseMatrix::<6, 261>::from_fun(|r, c| b[(c, r)]).T();
+
+        let naive = DenseMatrix::<67, 6>::from_fun(|r, c| {
+            let mut acc = 0f32;
+            for k in 0..261 {
+                acc += a[(r, k)] * b[(k, c)];
+            }
+            acc
+        });
+
+        // Same summation order as the naive loop, so agreement is exact
+        // for every combination of storage orders.
+        assert_eq!(&a * &b, naive);
+        assert_eq!(&at * &b, naive);
+        assert_eq!(&a * &bt, naive);
+        assert_eq!(&at * &bt, naive);
+    }
+
     #[test]
     fn dense_matrix_scalar_add_multiply() {
         use super::DenseMatrix;
@@ -478,4 +869,24 @@ mod tests {
 
         assert_eq!(&(5. + &m) * 2., expected);
     }
+
+    #[test]
+    fn dense_matrix_transpose_is_reinterpretation() {
+        use super::DenseMatrix;
+
+        let m = DenseMatrix::from_cols(&[
+            [1., 2.],
+            [3., 4.],
+            [5., 6.],
+        ]);
+
+        let expected = DenseMatrix::from_cols(&[
+            [1., 3., 5.],
+            [2., 4., 6.],
+        ]);
+
+        assert_eq!(m.T(), expected);
+        assert_eq!(m.T().T(), m);
+        assert_eq!(&m.T() * &m, &expected * &m);
+    }
 }
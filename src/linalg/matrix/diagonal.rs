@@ -1,9 +1,9 @@
 use std::fmt::Display;
 use std::marker::PhantomData;
-use std::ops::{Add, Mul, Neg, Sub};
+use std::ops::{Add, Index, Mul, Neg, Sub};
 
-use crate::linalg::vector::DenseVector;
-use crate::linalg::Vector;
+use crate::linalg::{Scalar, Vector};
+use crate::linalg::vector::{ConstantVector, DenseVector, OneHotVector, SparseVector, ZeroVector};
 
 use super::constant::ConstantMatrix;
 use super::dense::DenseMatrix;
@@ -12,25 +12,64 @@ use super::sparse::SparseMatrix;
 use super::zero::ZeroMatrix;
 
 #[derive(Clone, Debug)]
-pub struct DiagonalMatrix<const R: usize, const C: usize> where [(); R*C]: Sized {
-    pub(crate) diagonal_data: Box<[f32]>,
-    pub(crate) size_marker: PhantomData<[[f32; R]; C]>
+pub struct DiagonalMatrix<const R: usize, const C: usize, T: Scalar = f32> where [(); R*C]: Sized {
+    pub(crate) diagonal_data: Box<[T]>,
+    pub(crate) size_marker: PhantomData<[[T; R]; C]>
+}
+
+impl<const R: usize, const C: usize, T: Scalar> DiagonalMatrix<R, C, T> where [(); R*C]: Sized {
+    // constructor
+    pub(super) fn from_fun(f: impl Fn(usize) -> T) -> Self {
+        DiagonalMatrix {
+            diagonal_data: (0..R.min(C)).map(f).collect(),
+            size_marker: PhantomData,
+        }
+    }
+
+    /// Applies `f` to the main diagonal only.
+    pub(super) fn map(&self, f: impl Fn(T) -> T) -> Self {
+        DiagonalMatrix {
+            diagonal_data: self.diagonal_data.iter().copied().map(f).collect(),
+            size_marker: PhantomData,
+        }
+    }
+
+    /// Combines the main diagonals of `self` and `other` elementwise.
+    pub(super) fn zip_map<const R2: usize, const C2: usize>(
+        &self,
+        other: &DiagonalMatrix<R2, C2, T>,
+        f: impl Fn(T, T) -> T,
+    ) -> DiagonalMatrix<R, C2, T>
+        where
+            [(); R2*C2]: Sized,
+            [(); R*C2]: Sized,
+    {
+        let us = self.diagonal_data.iter().copied();
+        let them = other.diagonal_data.iter().copied();
+        DiagonalMatrix {
+            diagonal_data: us.zip(them).map(|(a, b)| f(a, b))
+                .chain(std::iter::repeat(T::zero()))
+                .take(R.min(C2))
+                .collect(),
+            size_marker: PhantomData,
+        }
+    }
 }
 
 // Impl is provided for possibly unequal R and C,
 // even though only square diagonal matrices can be instantiated.
 // This impl clones the underlying array and, if needed,
-// extends it with 0f32 or truncates it to the needed size.
+// extends it with zeros or truncates it to the needed size.
 // If R == C, as it ought to be, this is just a clone.
 // Wishing Rust had impl specialization...
-impl<const R: usize, const C: usize> DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> DiagonalMatrix<R, C, T>
     where
         [(); C*R]: Sized,
         [(); R*C]: Sized,
 {
-    pub(super) fn T(&self) -> DiagonalMatrix<C, R> {
+    pub(super) fn T(&self) -> DiagonalMatrix<C, R, T> {
         let copy = self.diagonal_data.iter().copied()
-            .chain(std::iter::repeat(0f32))
+            .chain(std::iter::repeat(T::zero()))
             .take(C);
 
         let mut container = Vec::with_capacity(C*R);
@@ -43,301 +82,333 @@ impl<const R: usize, const C: usize> DiagonalMatrix<R, C>
     }
 }
 
-impl<const R: usize, const C: usize> PartialEq for DiagonalMatrix<R, C> where [(); R*C]: Sized {
+impl<const R: usize, const C: usize, T: Scalar> PartialEq for DiagonalMatrix<R, C, T> where [(); R*C]: Sized {
     fn eq(&self, other: &Self) -> bool {
         self.diagonal_data == other.diagonal_data
     }
 }
 
 /////////////////////////////////
-/// DIAGONAL MATRIX ADD IMPLS ///
+//  DIAGONAL MATRIX ADD IMPLS  //
 /////////////////////////////////
 
-impl<const R: usize, const C: usize> Add<&ConstantMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ConstantMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        self + rhs.0
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DenseMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DenseMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DiagonalMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DiagonalMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        self.zip_map(rhs, |a, b| a + b)
     }
 }
 
-impl<const R: usize, const C: usize> Add<&IdentityMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&IdentityMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn add(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        self.map(|x| x + rhs.1)
     }
 }
 
-impl<const R: usize, const C: usize> Add<&SparseMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&SparseMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const R: usize, const C: usize> Add<&ZeroMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ZeroMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 /////////////////////////////////
-/// DIAGONAL MATRIX SUB IMPLS ///
+//  DIAGONAL MATRIX SUB IMPLS  //
 /////////////////////////////////
 
-impl<const R: usize, const C: usize> Sub<&ConstantMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ConstantMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        self - rhs.0
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DenseMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DenseMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        &(-rhs) + self
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DiagonalMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DiagonalMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        self.zip_map(rhs, |a, b| a - b)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&IdentityMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&IdentityMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        self.map(|x| x - rhs.1)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&SparseMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&SparseMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        &(-rhs) + self
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&ZeroMatrix<R, C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ZeroMatrix<R, C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 /////////////////////////////////
-/// DIAGONAL MATRIX MUL IMPLS ///
+//  DIAGONAL MATRIX MUL IMPLS  //
 /////////////////////////////////
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ConstantMatrix<C, C2>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ConstantMatrix<C, C2, T>> for &DiagonalMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = ConstantMatrix<R, C2>;
+    type Output = DenseMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &ConstantMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &ConstantMatrix<C, C2, T>) -> Self::Output {
+        DenseMatrix::from_fun(|r, _| {
+            if r < R.min(C) { self.diagonal_data[r] * rhs.0 } else { T::zero() }
+        })
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DenseMatrix<C, C2>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DenseMatrix<C, C2, T>> for &DiagonalMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DenseMatrix<R, C2>;
+    type Output = DenseMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &DenseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &DenseMatrix<C, C2, T>) -> Self::Output {
+        // Left-multiplying by a diagonal matrix scales the rows of `rhs`.
+        DenseMatrix::from_fun(|r, c| {
+            if r < R.min(C) { self.diagonal_data[r] * rhs[(r, c)] } else { T::zero() }
+        })
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DiagonalMatrix<C, C2>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DiagonalMatrix<C, C2, T>> for &DiagonalMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DiagonalMatrix<R, C2>;
+    type Output = DiagonalMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &DiagonalMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &DiagonalMatrix<C, C2, T>) -> Self::Output {
+        self.zip_map(rhs, |a, b| a * b)
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&IdentityMatrix<C, C2>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&IdentityMatrix<C, C2, T>> for &DiagonalMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DiagonalMatrix<R, C2>;
+    type Output = DiagonalMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &IdentityMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &IdentityMatrix<C, C2, T>) -> Self::Output {
+        DiagonalMatrix {
+            diagonal_data: self.diagonal_data.clone(),
+            size_marker: PhantomData,
+        }
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&SparseMatrix<C, C2>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&SparseMatrix<C, C2, T>> for &DiagonalMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = SparseMatrix<R, C2>;
+    type Output = SparseMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &SparseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &SparseMatrix<C, C2, T>) -> Self::Output {
+        let d = &self.diagonal_data;
+        SparseMatrix::from_triplets(rhs.nonzeros()
+            .filter(|&(r, _, _)| r < d.len())
+            .map(|(r, c, x)| (r, c, d[r] * x)))
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ZeroMatrix<C, C2>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ZeroMatrix<C, C2, T>> for &DiagonalMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &ZeroMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &ZeroMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize> Mul<&DenseVector<C>> for &DiagonalMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ConstantVector<C, T>> for &DiagonalMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseVector<R>;
+    type Output = DenseVector<R, T>;
 
-    fn mul(self, rhs: &DenseVector<C>) -> Self::Output {
-        let f = |(x, y)| x * y;
-        let us = self.diagonal_data.iter();
-        let them = rhs.data.iter();
+    fn mul(self, rhs: &ConstantVector<C, T>) -> Self::Output {
+        let d = &self.diagonal_data;
+        DenseVector::from_fun(|r| if r < d.len() { d[r] * rhs.0 } else { T::zero() })
+    }
+}
 
-        let mut container = Vec::with_capacity(R);
-        let mapped = us.zip(them).take(R).map(f);
-        container.extend(mapped);
+impl<const R: usize, const C: usize, T: Scalar> Mul<&DenseVector<C, T>> for &DiagonalMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = DenseVector<R, T>;
 
-        DenseVector {
-            data: container.into_boxed_slice(),
-            size_marker: PhantomData,
-        }
+    fn mul(self, rhs: &DenseVector<C, T>) -> Self::Output {
+        let d = &self.diagonal_data;
+        DenseVector::from_fun(|r| if r < d.len() { d[r] * rhs.data[r] } else { T::zero() })
     }
 }
 
-//////////////////////////////////
-/// DIAGONAL MATRIX MATH IMPLS ///
-//////////////////////////////////
+impl<const R: usize, const C: usize, T: Scalar> Mul<&OneHotVector<C, T>> for &DiagonalMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = SparseVector<R, T>;
 
-impl<const R: usize, const C: usize> Add<&DiagonalMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+    fn mul(self, rhs: &OneHotVector<C, T>) -> Self::Output {
+        let d = &self.diagonal_data;
+        SparseVector::from_pairs((rhs.index < d.len()).then(|| (rhs.index, d[rhs.index] * rhs.one)))
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&SparseVector<C, T>> for &DiagonalMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = SparseVector<R, T>;
 
-    fn add(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &SparseVector<C, T>) -> Self::Output {
+        let d = &self.diagonal_data;
+        SparseVector::from_pairs(rhs.nonzeros()
+            .filter(|&(i, _)| i < d.len())
+            .map(|(i, x)| (i, d[i] * x)))
     }
 }
 
-impl<const R: usize, const C: usize> Add<f32> for &DiagonalMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ZeroVector<C, T>> for &DiagonalMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ZeroVector<R, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &ZeroVector<C, T>) -> Self::Output {
+        ZeroVector(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize> Sub<f32> for &DiagonalMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+//////////////////////////////////
+//  DIAGONAL MATRIX MATH IMPLS  //
+//////////////////////////////////
+
+impl<const R: usize, const C: usize, T: Scalar> Add<T> for &DiagonalMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn add(self, rhs: T) -> Self::Output {
+        DenseMatrix::from_fun(|r, c| self[(r, c)] + rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Mul<&DiagonalMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = DiagonalMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Sub<T> for &DiagonalMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DenseMatrix<R, C, T>;
 
-    fn mul(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        DenseMatrix::from_fun(|r, c| self[(r, c)] - rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Mul<f32> for &DiagonalMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DiagonalMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<T> for &DiagonalMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn mul(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: T) -> Self::Output {
+        self.map(|x| x * rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Neg for &DiagonalMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DiagonalMatrix<R, C>;
+scalar_lhs_ops!(DiagonalMatrix, add: DenseMatrix, mul: DiagonalMatrix);
+
+impl<const R: usize, const C: usize, T: Scalar> Neg for &DiagonalMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DiagonalMatrix<R, C, T>;
 
     fn neg(self) -> Self::Output {
-        todo!()
+        self.map(|x| -x)
     }
 }
 
 /////////////////////////////////////
-/// DIAGONAL MATRIX UTILITY IMPLS ///
+//  DIAGONAL MATRIX UTILITY IMPLS  //
 /////////////////////////////////////
 
-impl<const D: usize> From<DenseVector<D>> for DiagonalMatrix<D, D> where [(); D*D]: Sized {
-    fn from(vector: DenseVector<D>) -> Self {
+impl<const D: usize, T: Scalar> From<DenseVector<D, T>> for DiagonalMatrix<D, D, T> where [(); D*D]: Sized {
+    fn from(vector: DenseVector<D, T>) -> Self {
         Self {
             diagonal_data: vector.data,
             size_marker: PhantomData,
@@ -345,21 +416,30 @@ impl<const D: usize> From<DenseVector<D>> for DiagonalMatrix<D, D> where [(); D*
     }
 }
 
-impl<const D: usize> From<Vector<D>> for DiagonalMatrix<D, D> where [(); D*D]: Sized {
-    fn from(vector: Vector<D>) -> Self {
-        vector.to_dense().into()
+impl<const D: usize, T: Scalar> From<Vector<D, T>> for DiagonalMatrix<D, D, T> where [(); D*D]: Sized {
+    fn from(vector: Vector<D, T>) -> Self {
+        vector.into_dense().into()
     }
 }
 
-impl<const D: usize> Display for DiagonalMatrix<D, D> where [(); D*D]: Sized {
+impl<const D: usize, T: Scalar> Display for DiagonalMatrix<D, D, T> where [(); D*D]: Sized {
     /// Displays the rows of a diagonal matrix.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}]", (0..D).into_iter()
+        write!(f, "[{}]", (0..D)
             .map(|r| {
-                let before = (0..r).into_iter().map(|_| 0f32.to_string());
+                let before = (0..r).map(|_| T::zero().to_string());
                 let val = std::iter::once(self.diagonal_data[r].to_string());
-                let after = ((r+1)..D).into_iter().map(|_| 0f32.to_string());
+                let after = ((r+1)..D).map(|_| T::zero().to_string());
                 before.chain(val).chain(after).collect::<Vec<_>>().join(",")
             }).collect::<Vec<_>>().join("],["))
     }
 }
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for DiagonalMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(r < R && c < C);
+        if r == c { &self.diagonal_data[r] } else { T::ZERO_REF }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::linalg::{parallel, Scalar};
+
+use super::Order;
+
+/// Rows of `A` packed per tile. A packed tile column stays within a few cache lines.
+const MC: usize = 64;
+/// Depth of a tile, so that an `MC × KC` packed panel of `A` fits in L2.
+const KC: usize = 256;
+/// Output columns updated together, so every load from the panel is reused.
+const NR: usize = 4;
+
+/// A read-only view of matrix storage, addressed through strides so that
+/// both storage orders are read in place.
+#[derive(Clone, Copy)]
+pub(super) struct Strided<'a, T> {
+    data: &'a [T],
+    row_stride: usize,
+    col_stride: usize,
+}
+
+impl<'a, T: Scalar> Strided<'a, T> {
+    pub(super) fn new(data: &'a [T], order: Order, rows: usize, cols: usize) -> Self {
+        debug_assert_eq!(data.len(), rows * cols);
+        let (row_stride, col_stride) = match order {
+            Order::COLS => (1, rows),
+            Order::ROWS => (cols, 1),
+        };
+        Strided { data, row_stride, col_stride }
+    }
+
+    /// Drops the first `j` columns.
+    fn skip_cols(self, j: usize) -> Self {
+        Strided { data: &self.data[j * self.col_stride..], ..self }
+    }
+
+    #[inline(always)]
+    fn at(&self, r: usize, c: usize) -> T {
+        self.data[r * self.row_stride + c * self.col_stride]
+    }
+}
+
+/// Accumulates `a * b` into the column-major `m × n` buffer `out`, where `a`
+/// is `m × k` and `b` is `k × n`.
+///
+/// `a` is copied tile by tile into a column-major panel, so the innermost
+/// loop is contiguous whatever the order of `a`. `b` is only read one scalar
+/// per panel column and is never repacked. Every output entry sums over `k`
+/// in increasing order, the same as the textbook triple loop.
+///
+/// Large products are split across threads by output columns, which leaves
+/// the arithmetic behind every entry unchanged.
+pub(super) fn gemm<T: Scalar>(m: usize, k: usize, n: usize, a: Strided<T>, b: Strided<T>, out: &mut [T]) {
+    assert_eq!(out.len(), m * n);
+    if m == 0 || n == 0 {
+        return;
+    }
+    parallel::for_each_chunk(out, m, m * k * n, |j, out| {
+        gemm_block(m, k, out.len() / m, a, b.skip_cols(j), out)
+    });
+}
+
+fn gemm_block<T: Scalar>(m: usize, k: usize, n: usize, a: Strided<T>, b: Strided<T>, out: &mut [T]) {
+    let mut panel = vec![T::zero(); MC.min(m) * KC.min(k)];
+
+    for kb in (0..k).step_by(KC) {
+        let kl = KC.min(k - kb);
+        for ib in (0..m).step_by(MC) {
+            let il = MC.min(m - ib);
+            let panel = &mut panel[..il * kl];
+            for p in 0..kl {
+                for i in 0..il {
+                    panel[p * il + i] = a.at(ib + i, kb + p);
+                }
+            }
+
+            let mut cols = out.chunks_exact_mut(m);
+            let mut j = 0;
+            while j + NR <= n {
+                let [c0, c1, c2, c3] = std::array::from_fn(|_| {
+                    &mut cols.next().unwrap()[ib..ib + il]
+                });
+                for p in 0..kl {
+                    let x = [b.at(kb + p, j), b.at(kb + p, j + 1), b.at(kb + p, j + 2), b.at(kb + p, j + 3)];
+                    let a_col = &panel[p * il..(p + 1) * il];
+                    for i in 0..il {
+                        let y = a_col[i];
+                        c0[i] += y * x[0];
+                        c1[i] += y * x[1];
+                        c2[i] += y * x[2];
+                        c3[i] += y * x[3];
+                    }
+                }
+                j += NR;
+            }
+            for (jr, col) in cols.enumerate() {
+                let col = &mut col[ib..ib + il];
+                for p in 0..kl {
+                    let x = b.at(kb + p, j + jr);
+                    let a_col = &panel[p * il..(p + 1) * il];
+                    for (o, y) in col.iter_mut().zip(a_col) {
+                        *o += *y * x;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use std::fmt::Display;
-use std::ops::{Add, Mul, Neg, Sub};
+use std::marker::PhantomData;
+use std::ops::{Add, Index, Mul, Neg, Sub};
+
+use crate::linalg::Scalar;
+use crate::linalg::vector::{ConstantVector, DenseVector, OneHotVector, SparseVector, ZeroVector};
 
 use super::constant::ConstantMatrix;
 use super::dense::DenseMatrix;
@@ -8,300 +12,364 @@ use super::sparse::SparseMatrix;
 use super::zero::ZeroMatrix;
 
 #[derive(Clone, Debug)]
-pub struct IdentityMatrix<const R: usize, const C: usize>(
-    pub(super) f32,
-    pub(super) f32,
+pub struct IdentityMatrix<const R: usize, const C: usize, T: Scalar = f32>(
+    pub(super) T,
+    pub(super) T,
 );
 
 // Impl is provided for possibly unequal R and C,
 // even though only square diagonal matrices can be instantiated.
-impl<const R: usize, const C: usize> IdentityMatrix<R, C> {
-    pub(super) fn T(&self) -> IdentityMatrix<C, R> {
+impl<const R: usize, const C: usize, T: Scalar> IdentityMatrix<R, C, T> {
+    pub(super) fn T(&self) -> IdentityMatrix<C, R, T> {
         IdentityMatrix(self.0, self.1)
     }
 }
 
-impl<const R: usize, const C: usize> PartialEq for IdentityMatrix<R, C> where [(); R*C]: Sized {
+impl<const R: usize, const C: usize, T: Scalar> PartialEq for IdentityMatrix<R, C, T> where [(); R*C]: Sized {
     fn eq(&self, _rhs: &Self) -> bool {
         true
     }
 }
 
 /////////////////////////////////
-/// IDENTITY MATRIX ADD IMPLS ///
+//  IDENTITY MATRIX ADD IMPLS  //
 /////////////////////////////////
 
-impl<const R: usize, const C: usize> Add<&ConstantMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ConstantMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        self + rhs.0
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DenseMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DenseMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DiagonalMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DiagonalMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const R: usize, const C: usize> Add<&IdentityMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&IdentityMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn add(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        DiagonalMatrix::from_fun(|_| self.1 + rhs.1)
     }
 }
 
-impl<const R: usize, const C: usize> Add<&SparseMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&SparseMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const R: usize, const C: usize> Add<&ZeroMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ZeroMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = IdentityMatrix<R, C>;
+    type Output = IdentityMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 /////////////////////////////////
-/// IDENTITY MATRIX SUB IMPLS ///
+//  IDENTITY MATRIX SUB IMPLS  //
 /////////////////////////////////
 
-impl<const R: usize, const C: usize> Sub<&ConstantMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ConstantMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        self - rhs.0
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DenseMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DenseMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        &(-rhs) + self
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DiagonalMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DiagonalMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        rhs.map(|x| self.1 - x)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&IdentityMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&IdentityMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ZeroMatrix<R, C>;
+    type Output = ZeroMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&SparseMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&SparseMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        &(-rhs) + self
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&ZeroMatrix<R, C>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ZeroMatrix<R, C, T>> for &IdentityMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = IdentityMatrix<R, C>;
+    type Output = IdentityMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 /////////////////////////////////
-/// IDENTITY MATRIX MUL IMPLS ///
+//  IDENTITY MATRIX MUL IMPLS  //
 /////////////////////////////////
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ConstantMatrix<C, C2>> for &IdentityMatrix<R, C>
+// Identity matrices are always square, so all of the products below
+// hand back a copy of the other operand without computing anything.
+
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ConstantMatrix<C, C2, T>> for &IdentityMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = ConstantMatrix<R, C2>;
+    type Output = ConstantMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &ConstantMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &ConstantMatrix<C, C2, T>) -> Self::Output {
+        ConstantMatrix(rhs.0)
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DenseMatrix<C, C2>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DenseMatrix<C, C2, T>> for &IdentityMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DenseMatrix<R, C2>;
-
-    fn mul(self, _rhs: &DenseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    type Output = DenseMatrix<R, C2, T>;
+
+    fn mul(self, rhs: &DenseMatrix<C, C2, T>) -> Self::Output {
+        debug_assert_eq!(R, C);
+        DenseMatrix {
+            data: rhs.data.clone(),
+            order: rhs.order,
+            size_marker: PhantomData,
+        }
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DiagonalMatrix<C, C2>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DiagonalMatrix<C, C2, T>> for &IdentityMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DiagonalMatrix<R, C2>;
+    type Output = DiagonalMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &DiagonalMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &DiagonalMatrix<C, C2, T>) -> Self::Output {
+        DiagonalMatrix {
+            diagonal_data: rhs.diagonal_data.clone(),
+            size_marker: PhantomData,
+        }
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&IdentityMatrix<C, C2>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&IdentityMatrix<C, C2, T>> for &IdentityMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = IdentityMatrix<R, C2>;
+    type Output = IdentityMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &IdentityMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &IdentityMatrix<C, C2, T>) -> Self::Output {
+        IdentityMatrix(self.0, self.1)
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&SparseMatrix<C, C2>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&SparseMatrix<C, C2, T>> for &IdentityMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = SparseMatrix<R, C2>;
-
-    fn mul(self, _rhs: &SparseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    type Output = SparseMatrix<R, C2, T>;
+
+    fn mul(self, rhs: &SparseMatrix<C, C2, T>) -> Self::Output {
+        debug_assert_eq!(R, C);
+        SparseMatrix {
+            offsets: rhs.offsets.clone(),
+            indices: rhs.indices.clone(),
+            values: rhs.values.clone(),
+            order: rhs.order,
+        }
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ZeroMatrix<C, C2>> for &IdentityMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ZeroMatrix<C, C2, T>> for &IdentityMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &ZeroMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &ZeroMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-//////////////////////////////////
-/// DIAGONAL MATRIX MATH IMPLS ///
-//////////////////////////////////
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ConstantVector<C, T>> for &IdentityMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ConstantVector<R, T>;
+
+    fn mul(self, rhs: &ConstantVector<C, T>) -> Self::Output {
+        ConstantVector(rhs.0)
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&DenseVector<C, T>> for &IdentityMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = DenseVector<R, T>;
+
+    fn mul(self, rhs: &DenseVector<C, T>) -> Self::Output {
+        debug_assert_eq!(R, C);
+        DenseVector::from_boxed_slice(rhs.data.clone())
+    }
+}
 
-impl<const R: usize, const C: usize> Add<&IdentityMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<&OneHotVector<C, T>> for &IdentityMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = OneHotVector<R, T>;
 
-    fn add(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: &OneHotVector<C, T>) -> Self::Output {
+        debug_assert_eq!(R, C);
+        OneHotVector { zero: rhs.zero, one: rhs.one, index: rhs.index }
     }
 }
 
-impl<const R: usize, const C: usize> Add<f32> for &IdentityMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<&SparseVector<C, T>> for &IdentityMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = SparseVector<R, T>;
+
+    fn mul(self, rhs: &SparseVector<C, T>) -> Self::Output {
+        debug_assert_eq!(R, C);
+        SparseVector { elems: rhs.elems.clone(), zero: rhs.zero }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ZeroVector<C, T>> for &IdentityMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ZeroVector<R, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &ZeroVector<C, T>) -> Self::Output {
+        ZeroVector(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize> Sub<f32> for &IdentityMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+//////////////////////////////////
+//  IDENTITY MATRIX MATH IMPLS  //
+//////////////////////////////////
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+impl<const R: usize, const C: usize, T: Scalar> Add<T> for &IdentityMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DenseMatrix<R, C, T>;
+
+    fn add(self, rhs: T) -> Self::Output {
+        DenseMatrix::from_fun(|r, c| if r == c { self.1 + rhs } else { rhs })
     }
 }
 
-impl<const R: usize, const C: usize> Mul<&IdentityMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = DiagonalMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Sub<T> for &IdentityMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DenseMatrix<R, C, T>;
 
-    fn mul(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        self + (-rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Mul<f32> for &IdentityMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DiagonalMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<T> for &IdentityMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn mul(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: T) -> Self::Output {
+        DiagonalMatrix::from_fun(|_| self.1 * rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Neg for &IdentityMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DiagonalMatrix<R, C>;
+scalar_lhs_ops!(IdentityMatrix, add: DenseMatrix, mul: DiagonalMatrix);
+
+impl<const R: usize, const C: usize, T: Scalar> Neg for &IdentityMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DiagonalMatrix<R, C, T>;
 
     fn neg(self) -> Self::Output {
-        todo!()
+        DiagonalMatrix::from_fun(|_| -self.1)
     }
 }
 
 /////////////////////////////////////
-/// IDENTITY MATRIX UTILITY IMPLS ///
+//  IDENTITY MATRIX UTILITY IMPLS  //
 /////////////////////////////////////
 
-impl<const D: usize> Display for IdentityMatrix<D, D> where [(); D*D]: Sized {
+impl<const D: usize, T: Scalar> Display for IdentityMatrix<D, D, T> where [(); D*D]: Sized {
     /// Displays the rows of an identity matrix.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}]", (0..D).into_iter()
+        write!(f, "[{}]", (0..D)
             .map(|r| {
-                let before = (0..r).into_iter().map(|_| 0f32.to_string());
-                let val = std::iter::once(self.0.to_string());
-                let after = ((r+1)..D).into_iter().map(|_| 0f32.to_string());
+                let before = (0..r).map(|_| self.0.to_string());
+                let val = std::iter::once(self.1.to_string());
+                let after = ((r+1)..D).map(|_| self.0.to_string());
                 before.chain(val).chain(after).collect::<Vec<_>>().join(",")
             }).collect::<Vec<_>>().join("],["))
     }
 }
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for IdentityMatrix<R, C, T> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        if r == c { &self.1 } else { &self.0 }
+    }
+}
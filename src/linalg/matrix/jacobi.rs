@@ -0,0 +1,222 @@
+//! Spectral decompositions by Jacobi rotations. They converge more slowly
+//! than tridiagonal QR iteration, but every step is a plane rotation of two
+//! columns, which keeps the code short and the results accurate in the entry type.
+
+use std::cmp::Ordering;
+
+use crate::linalg::{kernels, Scalar};
+use crate::linalg::vector::Vector;
+
+use super::wrapper::Matrix;
+
+/// Upper bound on sweeps over all column pairs. Convergence is quadratic,
+/// so well-conditioned inputs settle in well under ten.
+const MAX_SWEEPS: usize = 64;
+
+/// `A = VΛVᵀ` for symmetric `A`, with eigenvalues in decreasing order and
+/// the matching orthonormal eigenvectors as the columns of `vectors`.
+#[derive(Clone, Debug)]
+pub struct SymmetricEigen<const D: usize, T: Scalar = f32> where [(); D*D]: Sized {
+    pub values: Vector<D, T>,
+    pub vectors: Matrix<D, D, T>,
+}
+
+/// `A = U diag(S) Vᵀ`, with singular values in decreasing order.
+///
+/// `V` is orthogonal. When `C > R`, the trailing `C - R` singular values are
+/// zero and so are the matching columns of `U`; all other columns of `U` are
+/// orthonormal.
+#[derive(Clone, Debug)]
+pub struct Svd<const R: usize, const C: usize, T: Scalar = f32>
+    where
+        [(); R*C]: Sized,
+        [(); C*C]: Sized,
+{
+    pub U: Matrix<R, C, T>,
+    pub S: Vector<C, T>,
+    pub V: Matrix<C, C, T>,
+}
+
+impl<const D: usize, T: Scalar> Matrix<D, D, T> where [(); D*D]: Sized {
+    /// Cyclic Jacobi: each rotation zeroes one off-diagonal pair, and sweeps
+    /// repeat until every pair is negligible next to its diagonal entries.
+    /// Only the symmetric part of `self` is meaningful to the result.
+    pub fn symmetric_eigen(&self) -> SymmetricEigen<D, T> {
+        let mut a: Box<[T]> = (0..D*D).map(|i| self.get(i % D, i / D)).collect();
+        let mut v = identity(D);
+        // Pairs next to a vanishing diagonal are judged against the whole matrix.
+        let floor = T::epsilon() * T::epsilon() * kernels::sum_of_squares(&a).sqrt();
+
+        for _ in 0..MAX_SWEEPS {
+            let mut rotated = false;
+            for p in 0..D {
+                for q in p + 1..D {
+                    let (app, aqq, apq) = (a[p*D + p], a[q*D + q], a[q*D + p]);
+                    if apq.abs() <= T::epsilon() * (app * aqq).abs().sqrt() || apq.abs() <= floor {
+                        continue;
+                    }
+                    let (c, s) = rotation(app, aqq, apq);
+                    rotate_cols(&mut a, D, p, q, c, s);
+                    for col in a.chunks_exact_mut(D) {
+                        let (x, y) = (col[p], col[q]);
+                        col[p] = c * x - s * y;
+                        col[q] = s * x + c * y;
+                    }
+                    rotate_cols(&mut v, D, p, q, c, s);
+                    rotated = true;
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+
+        let order = decreasing(&(0..D).map(|i| a[i*D + i]).collect::<Vec<_>>());
+        SymmetricEigen {
+            values: Vector::from_fun(|i| a[order[i]*D + order[i]]),
+            vectors: Matrix::from_boxed_slice(permute_cols(&v, D, &order)),
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Matrix<R, C, T> where [(); R*C]: Sized {
+    /// One-sided Jacobi (Hestenes): columns of a working copy are rotated in
+    /// pairs until all are mutually orthogonal, with the rotations collected
+    /// in `V`. The column norms are then the singular values.
+    pub fn svd(&self) -> Svd<R, C, T> where [(); C*C]: Sized {
+        let mut b: Box<[T]> = (0..R*C).map(|i| self.get(i % R, i / R)).collect();
+        let mut v = identity(C);
+
+        for _ in 0..MAX_SWEEPS {
+            let mut rotated = false;
+            for p in 0..C {
+                for q in p + 1..C {
+                    let (bp, bq) = (&b[p*R..(p + 1)*R], &b[q*R..(q + 1)*R]);
+                    let (alpha, beta) = (kernels::sum_of_squares(bp), kernels::sum_of_squares(bq));
+                    let gamma = kernels::dot(bp, bq);
+                    if gamma == T::zero() || gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() {
+                        continue;
+                    }
+                    let (c, s) = rotation(alpha, beta, gamma);
+                    rotate_cols(&mut b, R, p, q, c, s);
+                    rotate_cols(&mut v, C, p, q, c, s);
+                    rotated = true;
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+
+        let norms: Vec<T> = b.chunks_exact(R).map(|col| kernels::sum_of_squares(col).sqrt()).collect();
+        for (col, &norm) in b.chunks_exact_mut(R).zip(&norms) {
+            if norm > T::zero() {
+                kernels::scale_assign(col, T::one() / norm);
+            }
+        }
+        let order = decreasing(&norms);
+        Svd {
+            U: Matrix::from_boxed_slice(permute_cols(&b, R, &order)),
+            S: Vector::from_fun(|i| norms[order[i]]),
+            V: Matrix::from_boxed_slice(permute_cols(&v, C, &order)),
+        }
+    }
+
+    /// The largest singular value, i.e. the most `self` can stretch a vector.
+    pub fn spectral_norm(&self) -> T where [(); C*C]: Sized {
+        if C == 0 { T::zero() } else { self.svd().S[0] }
+    }
+}
+
+/// The rotation `(c, s)` that zeroes the `pq` entry of the symmetric 2×2
+/// block `[[app, apq], [apq, aqq]]`, taking the smaller of the two angles.
+fn rotation<T: Scalar>(app: T, aqq: T, apq: T) -> (T, T) {
+    let theta = (aqq - app) / (T::from_f32(2.) * apq);
+    let t = theta.signum() / (theta.abs() + theta.hypot(T::one()));
+    let c = T::one() / t.hypot(T::one());
+    (c, c * t)
+}
+
+/// Replaces columns `p < q` of the column-major `data` by
+/// `c·col_p - s·col_q` and `s·col_p + c·col_q`.
+fn rotate_cols<T: Scalar>(data: &mut [T], rows: usize, p: usize, q: usize, c: T, s: T) {
+    let (left, right) = data.split_at_mut(q * rows);
+    let col_p = &mut left[p*rows..(p + 1)*rows];
+    let col_q = &mut right[..rows];
+    for (x, y) in col_p.iter_mut().zip(col_q) {
+        let (u, w) = (*x, *y);
+        *x = c * u - s * w;
+        *y = s * u + c * w;
+    }
+}
+
+fn identity<T: Scalar>(d: usize) -> Box<[T]> {
+    (0..d*d).map(|i| if i % d == i / d { T::one() } else { T::zero() }).collect()
+}
+
+/// Indices that sort `keys` from largest to smallest.
+fn decreasing<T: Scalar>(keys: &[T]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    order.sort_by(|&i, &j| keys[j].partial_cmp(&keys[i]).unwrap_or(Ordering::Equal));
+    order
+}
+
+fn permute_cols<T: Scalar>(data: &[T], rows: usize, order: &[usize]) -> Box<[T]> {
+    order.iter().flat_map(|&j| data[j*rows..(j + 1)*rows].iter().copied()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::test_support::{assert_close, tall, EPS};
+
+    #[test]
+    fn eigenvectors_diagonalize_symmetric_input() {
+        let b = tall();
+        let a = &b * &b.T();
+        let eigen = a.symmetric_eigen();
+        let (values, vectors) = (&eigen.values, &eigen.vectors);
+
+        assert_close(&(&vectors.T() * vectors), &Matrix::I());
+        assert_close(&(&a * vectors), &(vectors * &Matrix::diag(values.clone())));
+        assert!((0..3).all(|i| values[i] >= values[i + 1]));
+        // `a` has rank three, so its smallest eigenvalue vanishes.
+        assert!(values[3].abs() < EPS);
+    }
+
+    #[test]
+    fn eigenvalues_of_diagonal_come_out_sorted() {
+        let eigen = Matrix::diag(Vector::<3>::from_arr([1., -2., 5.])).symmetric_eigen();
+        assert_eq!([eigen.values[0], eigen.values[1], eigen.values[2]], [5., 1., -2.]);
+        assert_eq!(eigen.vectors[(2, 0)], 1.);
+    }
+
+    #[test]
+    fn svd_reconstructs_tall_and_wide_input() {
+        let tall = tall();
+        let svd = tall.svd();
+        assert_close(&(&(&svd.U * &Matrix::diag(svd.S.clone())) * &svd.V.T()), &tall);
+        assert_close(&(&svd.U.T() * &svd.U), &Matrix::I());
+        assert_close(&(&svd.V.T() * &svd.V), &Matrix::I());
+
+        let wide = tall.T();
+        let svd = wide.svd();
+        assert_close(&(&(&svd.U * &Matrix::diag(svd.S.clone())) * &svd.V.T()), &wide);
+        assert!(svd.S[3].abs() < EPS);
+        assert!((0..3).all(|i| svd.S[i] >= svd.S[i + 1]));
+    }
+
+    #[test]
+    fn singular_values_are_square_roots_of_gram_eigenvalues() {
+        let a = tall();
+        let svd = a.svd();
+        let eigen = (&a.T() * &a).symmetric_eigen();
+        assert!((0..3).all(|i| (svd.S[i] * svd.S[i] - eigen.values[i]).abs() < 1e-3));
+    }
+
+    #[test]
+    fn spectral_norm_is_largest_stretch() {
+        let m = Matrix::diag(Vector::<3>::from_arr([3., -5., 1.]));
+        assert!((m.spectral_norm() - 5.).abs() < EPS);
+    }
+}
@@ -0,0 +1,283 @@
+use std::marker::PhantomData;
+use std::ops::{Index, Mul, Range};
+use std::sync::OnceLock;
+
+use crate::linalg::{kernels, Scalar};
+use crate::linalg::vector::Vector;
+
+use super::Order;
+use super::dense::DenseMatrix;
+use super::gemm::{gemm, Strided};
+use super::wrapper::Matrix;
+
+/// `U Vᵀ`, with `U` of size `R × rank` and `V` of size `C × rank`, both
+/// column-major. A product with a vector costs `(R + C) * rank` instead of
+/// `R * C`, and a product with any other matrix only touches one factor, so
+/// it stays low-rank.
+#[derive(Clone, Debug)]
+pub struct LowRankMatrix<const R: usize, const C: usize, T: Scalar = f32> where [(); R*C]: Sized {
+    pub(super) rank: usize,
+    pub(super) u: Box<[T]>,
+    pub(super) v: Box<[T]>,
+    /// The entries, column-major, multiplied out the first time one is read
+    /// through `Index`, which has to hand out references into something.
+    /// Everything else reads entries from the factors through `get`.
+    entries: OnceLock<Box<[T]>>,
+    size_marker: PhantomData<[[T; R]; C]>,
+}
+
+impl<const R: usize, const C: usize, T: Scalar> LowRankMatrix<R, C, T> where [(); R*C]: Sized {
+    // constructor
+    pub(super) fn from_factors(rank: usize, u: Box<[T]>, v: Box<[T]>) -> Self {
+        assert_eq!(u.len(), R*rank);
+        assert_eq!(v.len(), C*rank);
+        LowRankMatrix {
+            rank,
+            u,
+            v,
+            entries: OnceLock::new(),
+            size_marker: PhantomData,
+        }
+    }
+
+    fn left(&self) -> Strided<'_, T> {
+        Strided::new(&self.u, Order::COLS, R, self.rank)
+    }
+
+    /// `Vᵀ`, read in place.
+    fn right_transposed(&self) -> Strided<'_, T> {
+        Strided::new(&self.v, Order::ROWS, self.rank, C)
+    }
+
+    pub(super) fn T(&self) -> LowRankMatrix<C, R, T> where [(); C*R]: Sized {
+        LowRankMatrix::from_factors(self.rank, self.v.clone(), self.u.clone())
+    }
+
+    /// Entry `(r, c)`, `Σₖ U[r, k] V[c, k]`, straight from the factors.
+    pub(super) fn get(&self, r: usize, c: usize) -> T {
+        assert!(r < R && c < C);
+        (0..self.rank).map(|k| self.u[k*R + r] * self.v[k*C + c]).sum()
+    }
+
+    /// The entries, column-major, multiplied out in one product.
+    fn multiply_out(&self) -> Box<[T]> {
+        let mut entries = vec![T::zero(); R*C];
+        gemm(R, self.rank, C, self.left(), self.right_transposed(), &mut entries);
+        entries.into_boxed_slice()
+    }
+
+    pub(super) fn to_dense(&self) -> DenseMatrix<R, C, T> {
+        match self.entries.get() {
+            Some(entries) => DenseMatrix::from_boxed_slice(entries.clone()),
+            None => DenseMatrix::from_boxed_slice(self.multiply_out()),
+        }
+    }
+
+    /// An upper bound: counting exactly would take multiplying out, and a
+    /// product of nonzero factors is rarely sparse anyway.
+    pub(super) fn nnz(&self) -> usize {
+        if self.rank == 0 { 0 } else { R*C }
+    }
+
+    /// The sum of all entries, `Σₖ (Σ uₖ)(Σ vₖ)`, without multiplying out.
+    pub(super) fn sum(&self) -> T {
+        (0..self.rank)
+            .map(|k| kernels::sum(&self.u[k*R..(k + 1)*R]) * kernels::sum(&self.v[k*C..(k + 1)*C]))
+            .sum()
+    }
+
+    /// `alpha * self`, scaling `U` only.
+    pub(super) fn scaled(&self, alpha: T) -> Self {
+        let mut u = self.u.clone();
+        kernels::scale_assign(&mut u, alpha);
+        LowRankMatrix::from_factors(self.rank, u, self.v.clone())
+    }
+
+    /// `self + alpha * other`, which side by side factors represent exactly.
+    /// The rank is the sum of both ranks, so long chains of sums are worth
+    /// densifying at some point.
+    pub(super) fn plus(&self, alpha: T, other: &Self) -> Self {
+        let mut u = Vec::with_capacity(self.u.len() + other.u.len());
+        u.extend_from_slice(&self.u);
+        u.extend(other.u.iter().map(|&x| alpha * x));
+        let v = [&self.v[..], &other.v[..]].concat();
+        LowRankMatrix::from_factors(self.rank + other.rank, u.into_boxed_slice(), v.into_boxed_slice())
+    }
+
+    /// `x * self = (x U) Vᵀ`. A low-rank `x` only multiplies through the
+    /// small `rank × rank` core `Vₓᵀ U`.
+    pub(super) fn left_mul<const R0: usize>(&self, x: &Matrix<R0, R, T>) -> LowRankMatrix<R0, C, T>
+        where [(); R0*R]: Sized, [(); R0*C]: Sized
+    {
+        let mut u = vec![T::zero(); R0*self.rank];
+        match x {
+            Matrix::Dense(x) => gemm(R0, R, self.rank, x.strided(), self.left(), &mut u),
+            Matrix::LowRank(x) => {
+                let mut core = vec![T::zero(); x.rank*self.rank];
+                gemm(x.rank, R, self.rank, x.right_transposed(), self.left(), &mut core);
+                gemm(R0, x.rank, self.rank, x.left(), Strided::new(&core, Order::COLS, x.rank, self.rank), &mut u);
+            },
+            _ => for (r, c, a) in x.stored() {
+                for k in 0..self.rank {
+                    u[k*R0 + r] += a * self.u[k*R + c];
+                }
+            },
+        }
+        LowRankMatrix::from_factors(self.rank, u.into_boxed_slice(), self.v.clone())
+    }
+
+    /// `self * x = U (xᵀ V)ᵀ`.
+    pub(super) fn right_mul<const C2: usize>(&self, x: &Matrix<C, C2, T>) -> LowRankMatrix<R, C2, T>
+        where [(); C*C2]: Sized, [(); R*C2]: Sized
+    {
+        let mut v = vec![T::zero(); C2*self.rank];
+        match x {
+            Matrix::Dense(x) => {
+                let transposed = Strided::new(&x.data, -x.order, C2, C);
+                gemm(C2, C, self.rank, transposed, Strided::new(&self.v, Order::COLS, C, self.rank), &mut v);
+            },
+            _ => for (r, c, a) in x.stored() {
+                for k in 0..self.rank {
+                    v[k*C2 + c] += a * self.v[k*C + r];
+                }
+            },
+        }
+        LowRankMatrix::from_factors(self.rank, self.u.clone(), v.into_boxed_slice())
+    }
+
+    /// Row `i` for `Order::ROWS`, column `i` for `Order::COLS`, as a
+    /// combination of the columns of one factor.
+    pub(super) fn line(&self, order: Order, i: usize) -> Box<[T]> {
+        let (coeffs, stride, factor, len) = match order {
+            Order::COLS => (&self.v, C, &self.u, R),
+            Order::ROWS => (&self.u, R, &self.v, C),
+        };
+        let mut line = vec![T::zero(); len].into_boxed_slice();
+        for k in 0..self.rank {
+            let a = coeffs[k*stride + i];
+            if a != T::zero() {
+                kernels::axpy(&mut line, a, &factor[k*len..(k + 1)*len]);
+            }
+        }
+        line
+    }
+
+    /// The `RN × CN` block at `(R0, C0)`, which only needs rows of each factor.
+    pub(super) fn block<const R0: usize, const C0: usize, const RN: usize, const CN: usize>(&self) -> LowRankMatrix<RN, CN, T>
+        where [(); RN*CN]: Sized
+    {
+        LowRankMatrix::from_factors(
+            self.rank,
+            rows_of(&self.u, R, R0..R0 + RN),
+            rows_of(&self.v, C, C0..C0 + CN),
+        )
+    }
+}
+
+/// Rows `range` of every column of the column-major `factor` of height `len`.
+fn rows_of<T: Scalar>(factor: &[T], len: usize, range: Range<usize>) -> Box<[T]> {
+    if range.is_empty() {
+        return Box::new([]);
+    }
+    factor.chunks_exact(len).flat_map(|col| col[range.clone()].iter().copied()).collect()
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&Vector<C, T>> for &LowRankMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Vector<R, T>;
+
+    /// `U (Vᵀ x)`. Sparse flavors of `x` only cost their nonzeros.
+    fn mul(self, rhs: &Vector<C, T>) -> Self::Output {
+        let coeffs: Vec<T> = match rhs {
+            Vector::Dense(x) => (0..self.rank).map(|k| kernels::dot(&self.v[k*C..(k + 1)*C], &x.data)).collect(),
+            _ => {
+                let mut coeffs = vec![T::zero(); self.rank];
+                for (c, x) in rhs.nonzeros() {
+                    for (k, w) in coeffs.iter_mut().enumerate() {
+                        *w += self.v[k*C + c] * x;
+                    }
+                }
+                coeffs
+            },
+        };
+        let mut product = vec![T::zero(); R].into_boxed_slice();
+        for (k, &w) in coeffs.iter().enumerate() {
+            kernels::axpy(&mut product, w, &self.u[k*R..(k + 1)*R]);
+        }
+        Vector::from_boxed_slice(product)
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for LowRankMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = T;
+
+    /// The slow path: the first read multiplies out and keeps every entry,
+    /// which is exactly the storage the factors avoid. `Matrix::get` reads
+    /// one entry from the factors instead.
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(r < R && c < C);
+        &self.entries.get_or_init(|| self.multiply_out())[c*R + r]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::test_support::low_rank;
+
+    #[test]
+    fn factors_multiply_out() {
+        let m = low_rank();
+        let dense = m.clone().densify();
+        assert_eq!(dense[(1, 0)], -3.);
+        assert_eq!(dense[(3, 2)], 0.5);
+        assert_eq!(m, dense);
+        assert_eq!(m.T(), dense.T());
+        assert_eq!(m.sum(), dense.sum());
+    }
+
+    fn assert_low_rank_close<const R: usize, const C: usize>(actual: Matrix<R, C>, expected: Matrix<R, C>)
+        where [(); R*C]: Sized
+    {
+        assert!(matches!(actual, Matrix::LowRank(_)), "{actual:?}");
+        assert!((&actual - &expected).norm_frobenius() < 1e-5, "{actual:?}");
+    }
+
+    #[test]
+    fn products_stay_low_rank() {
+        let m = low_rank();
+        let dense = m.clone().densify();
+        let x = Matrix::<3, 3>::from_cols(&[[1., 2., 3.], [0., -1., 4.], [2., 0., 1.]]);
+        let y = Matrix::<2, 4>::from_triplets([(0, 1, 2.), (1, 3, -1.)]);
+
+        assert_low_rank_close(&m * &x, &dense * &x);
+        assert_low_rank_close(&y * &m, &y * &dense);
+        assert_low_rank_close(&m * &m.T(), &dense * &dense.T());
+        assert_low_rank_close(&(&m - &m) * &x, Matrix::zero());
+        assert!(matches!(&m + &Matrix::constant(1.), Matrix::Dense(_)));
+    }
+
+    #[test]
+    fn matvec_skips_multiplying_out() {
+        let m = low_rank();
+        let x = Vector::from_arr([1., -2., 4.]);
+        assert_eq!(&m * &x, &m.clone().densify() * &x);
+        assert_eq!(&m * &Vector::one_hot(2), m.col(2).to_vector());
+        let Matrix::LowRank(m) = m else { unreachable!() };
+        assert!(m.entries.get().is_none(), "matvec and col never multiply out");
+    }
+
+    #[test]
+    fn reductions_and_entrywise_products_read_the_factors() {
+        let m = low_rank();
+        let dense = m.clone().densify();
+        assert_eq!(m.get(1, 0), -3.);
+        assert_eq!(m.density(), 1.);
+        assert_eq!((m.argmax(), m.max()), (dense.argmax(), dense.max()));
+        assert!((m.norm_frobenius() - dense.norm_frobenius()).abs() < 1e-5);
+        assert!((m.variance() - dense.variance()).abs() < 1e-5);
+        assert_eq!(m.hadamard(&dense), dense.hadamard(&dense));
+        assert_eq!(m.kron(&Matrix::<2, 2>::I()), dense.kron(&Matrix::<2, 2>::I()));
+        let Matrix::LowRank(m) = m else { unreachable!() };
+        assert!(m.entries.get().is_none());
+    }
+}
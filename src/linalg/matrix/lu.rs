@@ -0,0 +1,244 @@
+use std::cmp::Ordering;
+
+use crate::linalg::{kernels, LinalgError, Scalar};
+use crate::linalg::vector::Vector;
+
+use super::diagonal::DiagonalMatrix;
+use super::triangular::Triangle;
+use super::wrapper::Matrix;
+
+/// LU decomposition with partial pivoting, `PA = LU`.
+///
+/// `L` (unit diagonal, not stored) and `U` are packed into one column-major
+/// buffer. Row `i` of `PA` is row `pivots[i]` of `A`.
+#[derive(Clone, Debug)]
+pub struct Lu<const D: usize, T: Scalar = f32> {
+    lu: Box<[T]>,
+    pivots: Box<[usize]>,
+    /// `-1` if `P` is an odd permutation, which flips the sign of the determinant.
+    sign: T,
+}
+
+impl<const D: usize, T: Scalar> Lu<D, T> where [(); D*D]: Sized {
+    /// Factors `a` by right-looking Gaussian elimination. Each step picks the
+    /// entry of largest magnitude in the current column as the pivot and
+    /// updates the trailing columns with one contiguous axpy each.
+    ///
+    /// A column without a nonzero pivot is left as is, so the factorization
+    /// always exists; it is `solve` and `inverse` that report singularity.
+    fn new(a: &Matrix<D, D, T>) -> Self {
+        let mut lu: Box<[T]> = (0..D*D).map(|i| a.get(i % D, i / D)).collect();
+        let mut pivots: Box<[usize]> = (0..D).collect();
+        let mut sign = T::one();
+
+        for k in 0..D {
+            let p = (k..D)
+                .max_by(|&i, &j| lu[k*D + i].abs().partial_cmp(&lu[k*D + j].abs()).unwrap_or(Ordering::Equal))
+                .unwrap();
+            if p != k {
+                for c in 0..D {
+                    lu.swap(c*D + k, c*D + p);
+                }
+                pivots.swap(k, p);
+                sign = -sign;
+            }
+
+            let pivot = lu[k*D + k];
+            if pivot == T::zero() {
+                continue;
+            }
+            kernels::scale_assign(&mut lu[k*D + k + 1..(k + 1)*D], T::one() / pivot);
+
+            let (left, right) = lu.split_at_mut((k + 1)*D);
+            let l_col = &left[k*D + k + 1..];
+            for col in right.chunks_exact_mut(D) {
+                let u = col[k];
+                if u != T::zero() {
+                    kernels::axpy(&mut col[k + 1..], -u, l_col);
+                }
+            }
+        }
+
+        Lu { lu, pivots, sign }
+    }
+
+    pub fn det(&self) -> T {
+        self.sign * (0..D).map(|k| self.lu[k*D + k]).fold(T::one(), |acc, x| acc * x)
+    }
+
+    /// Solves `Ax = b`.
+    pub fn solve(&self, b: &Vector<D, T>) -> Result<Vector<D, T>, LinalgError> {
+        let mut x: Box<[T]> = self.pivots.iter().map(|&i| b[i]).collect();
+        self.substitute(&mut x)?;
+        Ok(Vector::from_boxed_slice(x))
+    }
+
+    /// Solves against every column of the identity in turn.
+    pub fn inverse(&self) -> Result<Matrix<D, D, T>, LinalgError> {
+        let mut inv = vec![T::zero(); D*D].into_boxed_slice();
+        for (j, col) in inv.chunks_exact_mut(D).enumerate() {
+            for (i, x) in col.iter_mut().enumerate() {
+                *x = if self.pivots[i] == j { T::one() } else { T::zero() };
+            }
+            self.substitute(col)?;
+        }
+        Ok(Matrix::from_boxed_slice(inv))
+    }
+
+    /// The unit lower triangular factor.
+    pub fn L(&self) -> Matrix<D, D, T> {
+        Matrix::from_boxed_slice((0..D*D).map(|i| match (i % D, i / D) {
+            (r, c) if r > c => self.lu[i],
+            (r, c) if r == c => T::one(),
+            _ => T::zero(),
+        }).collect())
+    }
+
+    /// The upper triangular factor.
+    pub fn U(&self) -> Matrix<D, D, T> {
+        Matrix::from_boxed_slice((0..D*D).map(|i| {
+            if i % D <= i / D { self.lu[i] } else { T::zero() }
+        }).collect())
+    }
+
+    pub fn pivots(&self) -> &[usize] {
+        &self.pivots
+    }
+
+    /// Overwrites the permuted right-hand side `x` with the solution, by
+    /// forward substitution through `L` and back substitution through `U`.
+    /// Both sweep the packed factors column by column.
+    fn substitute(&self, x: &mut [T]) -> Result<(), LinalgError> {
+        for k in 0..D {
+            let y = x[k];
+            if y != T::zero() {
+                kernels::axpy(&mut x[k + 1..], -y, &self.lu[k*D + k + 1..(k + 1)*D]);
+            }
+        }
+        for k in (0..D).rev() {
+            let pivot = self.lu[k*D + k];
+            if pivot == T::zero() {
+                return Err(LinalgError::Singular { column: k });
+            }
+            x[k] /= pivot;
+            let y = x[k];
+            if y != T::zero() {
+                kernels::axpy(&mut x[..k], -y, &self.lu[k*D..k*D + k]);
+            }
+        }
+        Ok(())
+    }
+}
+
+// The flavors with closed-form answers skip the factorization. Everything
+// else is factored densely.
+impl<const D: usize, T: Scalar> Matrix<D, D, T> where [(); D*D]: Sized {
+    pub fn lu(&self) -> Lu<D, T> {
+        Lu::new(self)
+    }
+
+    pub fn det(&self) -> T {
+        use Matrix as M;
+        match self {
+            M::Diagonal(m) => m.diagonal_data.iter().fold(T::one(), |acc, &x| acc * x),
+            M::Identity(m) => m.1.powi(D as i32),
+            M::LowerTriangular(m) | M::UpperTriangular(m) => m.det(),
+            M::Permutation(m) => m.sign() * m.one.powi(D as i32),
+            M::Zero(_) if D > 0 => T::zero(),
+            _ => self.lu().det(),
+        }
+    }
+
+    /// Solves `self * x = b` for `x`.
+    pub fn solve(&self, b: &Vector<D, T>) -> Result<Vector<D, T>, LinalgError> {
+        use Matrix as M;
+        match self {
+            M::Diagonal(_) | M::Identity(_) | M::Permutation(_) | M::Zero(_) => Ok(&self.inverse()? * b),
+            M::LowerTriangular(m) => m.solve(Triangle::Lower, b),
+            M::UpperTriangular(m) => m.solve(Triangle::Upper, b),
+            _ => self.lu().solve(b),
+        }
+    }
+
+    pub fn inverse(&self) -> Result<Matrix<D, D, T>, LinalgError> {
+        use Matrix as M;
+        match self {
+            M::Diagonal(m) => match m.diagonal_data.iter().position(|&d| d == T::zero()) {
+                Some(column) => Err(LinalgError::Singular { column }),
+                None => Ok(M::Diagonal(DiagonalMatrix::from_fun(|i| T::one() / m.diagonal_data[i]))),
+            },
+            M::Identity(m) if m.1 == T::one() => Ok(self.clone()),
+            M::LowerTriangular(m) => m.inverse(Triangle::Lower).map(M::LowerTriangular),
+            M::Permutation(m) if m.one != T::zero() => Ok(M::Permutation(m.inverse())),
+            M::UpperTriangular(m) => m.inverse(Triangle::Upper).map(M::UpperTriangular),
+            M::Zero(_) if D > 0 => Err(LinalgError::Singular { column: 0 }),
+            _ => self.lu().inverse(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::dense::DenseMatrix;
+    use super::super::test_support::{assert_close, square, EPS};
+
+    #[test]
+    fn factors_multiply_back_to_permuted_input() {
+        let a = square();
+        let lu = a.lu();
+        let pa = Matrix::Dense(DenseMatrix::from_fun(|r, c| a[(lu.pivots()[r], c)]));
+        assert_close(&(&lu.L() * &lu.U()), &pa);
+    }
+
+    #[test]
+    fn solve_inverse_and_det_agree() {
+        let a = square();
+        let b = Vector::from_arr([1., -2., 0.5]);
+        let x = a.solve(&b).unwrap();
+        let ax = &a * &x;
+        assert!((0..3).all(|i| (ax[i] - b[i]).abs() < EPS));
+
+        assert_close(&(&a * &a.inverse().unwrap()), &Matrix::I());
+        // Cofactor expansion along the first row.
+        assert!((a.det() + 50.5).abs() < EPS);
+        assert!((a.T().det() - a.det()).abs() < EPS);
+    }
+
+    #[test]
+    fn singular_matrices_are_reported() {
+        let rank_two = Matrix::<3, 3>::from_cols(&[
+            [1., 2., 3.],
+            [2., 4., 6.],
+            [0., 1., 1.],
+        ]);
+        assert_eq!(rank_two.det(), 0.);
+        assert!(matches!(rank_two.inverse(), Err(LinalgError::Singular { .. })));
+        assert!(Matrix::<3, 3>::constant(2.).solve(&Vector::one_hot(0)).is_err());
+        assert_eq!(Matrix::<3, 3>::zero().inverse().unwrap_err(), LinalgError::Singular { column: 0 });
+    }
+
+    #[test]
+    fn f64_solves_ill_conditioned_systems() {
+        // The 5x5 Hilbert matrix has a condition number near 5e5, which eats
+        // most of an f32 mantissa but leaves plenty of an f64 one.
+        let h = Matrix::<5, 5, f64>::Dense(DenseMatrix::from_fun(|r, c| 1. / (r + c + 1) as f64));
+        let x = Vector::<5, f64>::from_arr([1., -1., 2., 0.5, -3.]);
+        let y = h.solve(&(&h * &x)).unwrap();
+        assert!((0..5).all(|i| (y[i] - x[i]).abs() < 1e-9), "{y:?}");
+    }
+
+    #[test]
+    fn special_flavors_take_shortcuts() {
+        let diag = Matrix::diag(Vector::from_arr([2., -4., 0.5]));
+        assert!(matches!(diag.inverse(), Ok(Matrix::Diagonal(_))));
+        assert_eq!(diag.det(), -4.);
+        let x = diag.solve(&Vector::one_hot(1)).unwrap();
+        assert!(matches!(x, Vector::Sparse(_)));
+        assert_eq!(x[1], -0.25);
+
+        assert!(matches!(Matrix::<3, 3>::I().inverse(), Ok(Matrix::Identity(_))));
+        assert_eq!(Matrix::<3, 3>::I().det(), 1.);
+        assert!(Matrix::diag(Vector::<2>::from_arr([1., 0.])).inverse().is_err());
+    }
+}
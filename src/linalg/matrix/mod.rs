@@ -4,19 +4,68 @@ use std::ops::{Add, Mul, Sub};
 use super::order::Order;
 use super::OldVectorDoNotUse;
 
+#[allow(unused_imports)] pub(super) use blockdiag::BlockDiagonalMatrix;
 #[allow(unused_imports)] pub(super) use constant::ConstantMatrix;
 #[allow(unused_imports)] pub(super) use dense::DenseMatrix;
 #[allow(unused_imports)] pub(super) use diagonal::DiagonalMatrix;
 #[allow(unused_imports)] pub(super) use identity::IdentityMatrix;
+#[allow(unused_imports)] pub use jacobi::{Svd, SymmetricEigen};
+#[allow(unused_imports)] pub(super) use lowrank::LowRankMatrix;
+#[allow(unused_imports)] pub use lu::Lu;
+#[allow(unused_imports)] pub(super) use permutation::PermutationMatrix;
+#[allow(unused_imports)] pub use qr::Qr;
+#[allow(unused_imports)] pub(super) use quantized::QuantizedMatrix;
 #[allow(unused_imports)] pub(super) use sparse::SparseMatrix;
+#[allow(unused_imports)] pub(super) use triangular::TriangularMatrix;
+#[allow(unused_imports)] pub(super) use view::DenseMatrixView;
+#[allow(unused_imports)] pub use view::MatrixView;
 #[allow(unused_imports)] pub use wrapper::Matrix;
 #[allow(unused_imports)] pub(super) use zero::ZeroMatrix;
 
+/// Scalars on the left, deferring to the scalar-on-the-right impls. A blanket
+/// `impl<T: Scalar> Add<&M<R, C, T>> for T` breaks the orphan rule, so every
+/// entry type is spelled out.
+macro_rules! scalar_lhs_ops {
+    ($m:ident, add: $add:ident, mul: $mul:ident) => {
+        scalar_lhs_ops!($m, $add, $mul; f32, f64, $crate::linalg::bf16, $crate::linalg::f16);
+    };
+    ($m:ident, $add:ident, $mul:ident; $($t:ty),*) => {$(
+        impl<const R: usize, const C: usize> Add<&$m<R, C, $t>> for $t where [(); R*C]: Sized {
+            type Output = $add<R, C, $t>;
+
+            fn add(self, rhs: &$m<R, C, $t>) -> Self::Output {
+                rhs + self
+            }
+        }
+
+        impl<const R: usize, const C: usize> Mul<&$m<R, C, $t>> for $t where [(); R*C]: Sized {
+            type Output = $mul<R, C, $t>;
+
+            fn mul(self, rhs: &$m<R, C, $t>) -> Self::Output {
+                rhs * self
+            }
+        }
+    )*};
+}
+
+mod blockdiag;
+mod cholesky;
 mod constant;
 mod dense;
 mod diagonal;
+mod gemm;
 mod identity;
+mod jacobi;
+mod lowrank;
+mod lu;
+mod permutation;
+mod product;
+mod qr;
+mod quantized;
+mod reduce;
 mod sparse;
+mod triangular;
+mod view;
 mod wrapper;
 mod zero;
 
@@ -35,10 +84,9 @@ impl<const R: usize, const C: usize> OldMatrixDoNotUse<R, C> where [(); R*C]: Si
 
     pub fn from_cols(cols: &[[f32; R]; C]) -> Self {
         let mut arr = [0f32; R*C];
-        for c_ind in 0..C {
+        for (c_ind, col) in cols.iter().enumerate() {
             let from = c_ind * R;
             let to = from + R;
-            let col = &cols[c_ind];
             arr[from..to].copy_from_slice(col);
         }
         OldMatrixDoNotUse(arr)
@@ -49,8 +97,8 @@ impl<const R: usize, const C: usize> OldMatrixDoNotUse<R, C> where [(); R*C]: Si
      */
     pub fn T(&self) -> OldMatrixDoNotUse<C, R> where [(); C*R]: Sized {
         let mut arr = [0f32; C*R];
-        for i in 0..(C * R) {
-            arr[i] = self.0[(i % C) * R + (i / C)];
+        for (i, x) in arr.iter_mut().enumerate() {
+            *x = self.0[(i % C) * R + (i / C)];
         }
         OldMatrixDoNotUse(arr)
     }
@@ -79,7 +127,7 @@ impl<const D: usize> OldMatrixDoNotUse<D, D> where [(); D*D]: Sized {
 }
 
 /////////////////////////
-/// &Matrix + &Matrix ///
+//  &Matrix + &Matrix  //
 /////////////////////////
 
 impl<const R: usize, const C: usize> Add for &OldMatrixDoNotUse<R, C> where [(); R*C]: Sized {
@@ -97,7 +145,7 @@ impl<const R: usize, const C: usize> Add for &OldMatrixDoNotUse<R, C> where [();
 }
 
 /////////////////////
-/// f32 + &Matrix ///
+//  f32 + &Matrix  //
 /////////////////////
 
 impl<const R: usize, const C: usize> Add<&OldMatrixDoNotUse<R, C>> for f32 where [(); R*C]: Sized {
@@ -113,7 +161,7 @@ impl<const R: usize, const C: usize> Add<&OldMatrixDoNotUse<R, C>> for f32 where
 }
 
 /////////////////////
-/// &Matrix + f32 ///
+//  &Matrix + f32  //
 /////////////////////
 
 impl<const R: usize, const C: usize> Add<f32> for &OldMatrixDoNotUse<R, C> where [(); R*C]: Sized {
@@ -129,7 +177,7 @@ impl<const R: usize, const C: usize> Add<f32> for &OldMatrixDoNotUse<R, C> where
 }
 
 /////////////////////////
-/// &Matrix - &Matrix ///
+//  &Matrix - &Matrix  //
 /////////////////////////
 
 impl<const R: usize, const C: usize> Sub for &OldMatrixDoNotUse<R, C> where [(); R*C]: Sized {
@@ -147,7 +195,7 @@ impl<const R: usize, const C: usize> Sub for &OldMatrixDoNotUse<R, C> where [();
 }
 
 /////////////////////////
-/// &Matrix * &Matrix ///
+//  &Matrix * &Matrix  //
 /////////////////////////
 
 impl<const R: usize, const C: usize, const C2: usize> Mul<&OldMatrixDoNotUse<C, C2>> for &OldMatrixDoNotUse<R, C>
@@ -160,22 +208,15 @@ impl<const R: usize, const C: usize, const C2: usize> Mul<&OldMatrixDoNotUse<C,
 
     fn mul(self, rhs: &OldMatrixDoNotUse<C, C2>) -> Self::Output {
         let mut arr = [0f32; R*C2];
-        for i in 0..(R * C2) {
-            let from = (i / R) * C;
-            let to = from + C;
-            let x: f32 = rhs.0[from..to]
-                .into_iter()
-                .enumerate()
-                .map(|(j, x)| x * self.0[j * R + i % R])
-                .sum();
-            arr[i] = x;
-        }
+        let lhs = gemm::Strided::new(&self.0, Order::COLS, R, C);
+        let rhs = gemm::Strided::new(&rhs.0, Order::COLS, C, C2);
+        gemm::gemm(R, C, C2, lhs, rhs, &mut arr);
         OldMatrixDoNotUse(arr)
     }
 }
 
 /////////////////////////
-/// &Matrix * &Vector ///
+//  &Matrix * &Vector  //
 /////////////////////////
 
 impl<const R: usize, const C: usize> Mul<&OldVectorDoNotUse<C>> for &OldMatrixDoNotUse<R, C> where [(); R*C]: Sized {
@@ -183,20 +224,19 @@ impl<const R: usize, const C: usize> Mul<&OldVectorDoNotUse<C>> for &OldMatrixDo
 
     fn mul(self, rhs: &OldVectorDoNotUse<C>) -> Self::Output {
         let mut arr = [0f32; R];
-        for i in 0..R {
-            let x: f32 = rhs.0
+        for (i, y) in arr.iter_mut().enumerate() {
+            *y = rhs.0
                 .into_iter()
                 .enumerate()
                 .map(|(j, x)| x * self.0[j * R + i])
                 .sum();
-            arr[i] = x;
         }
         OldVectorDoNotUse(arr)
     }
 } 
 
 /////////////////////
-/// f32 * &Matrix ///
+//  f32 * &Matrix  //
 /////////////////////
 
 impl<const R: usize, const C: usize> Mul<&OldMatrixDoNotUse<R, C>> for f32 where [(); R*C]: Sized {
@@ -212,7 +252,7 @@ impl<const R: usize, const C: usize> Mul<&OldMatrixDoNotUse<R, C>> for f32 where
 }
 
 /////////////////////
-/// &Matrix * f32 ///
+//  &Matrix * f32  //
 /////////////////////
 
 impl<const R: usize, const C: usize> Mul<f32> for &OldMatrixDoNotUse<R, C> where [(); R*C]: Sized {
@@ -228,7 +268,7 @@ impl<const R: usize, const C: usize> Mul<f32> for &OldMatrixDoNotUse<R, C> where
 }
 
 /////////////////////
-/// UTILITY IMPLS ///
+//  UTILITY IMPLS  //
 /////////////////////
 
 impl<const R: usize, const C: usize> Display for OldMatrixDoNotUse<R, C> where [(); R*C]: Sized {
@@ -236,8 +276,8 @@ impl<const R: usize, const C: usize> Display for OldMatrixDoNotUse<R, C> where [
         write!(f, "[")?;
         for r in 0..R {
             let mut arr = [0f32; C];
-            for c in 0..C {
-                arr[c] = self.0[c*R+r];
+            for (c, x) in arr.iter_mut().enumerate() {
+                *x = self.0[c*R+r];
             }
             write!(f, "[{}]", arr.map(|n| n.to_string()).join(","))?;
         }
@@ -287,3 +327,622 @@ mod tests {
         assert_eq!(&m1 * &m2, expected);
     }
 }
+
+/// Tolerances, comparisons and fixtures shared by the unit tests of the
+/// linalg modules.
+#[cfg(test)]
+pub(super) mod test_support {
+    use super::Matrix;
+    use crate::linalg::{Tensor3, Tensor4, Vector};
+
+    pub const EPS: f32 = 1e-4;
+
+    pub fn assert_close<const R: usize, const C: usize>(actual: &Matrix<R, C>, expected: &Matrix<R, C>)
+        where [(); R*C]: Sized
+    {
+        for r in 0..R {
+            for c in 0..C {
+                let (a, e) = (actual[(r, c)], expected[(r, c)]);
+                assert!((a - e).abs() < EPS, "({r},{c}) is {a}, expected {e}");
+            }
+        }
+    }
+
+    pub fn assert_vectors_close<const D: usize>(actual: &Vector<D>, expected: &Vector<D>) {
+        for i in 0..D {
+            let (a, e) = (actual[i], expected[i]);
+            assert!((a - e).abs() < EPS, "{i} is {a}, expected {e}");
+        }
+    }
+
+    /// Of full column rank, with a mix of signs.
+    pub fn tall() -> Matrix<4, 3> {
+        Matrix::from_cols(&[
+            [1., 2., 0., -1.],
+            [0., 1., 3., 2.],
+            [4., -2., 1., 0.5],
+        ])
+    }
+
+    /// Invertible, with a nonzero diagonal, and the largest entry of the
+    /// first column off the diagonal so that elimination has to pivot.
+    pub fn square() -> Matrix<3, 3> {
+        Matrix::from_cols(&[
+            [1., 4., -2.],
+            [2., 3., 5.],
+            [-1., 0.5, 4.],
+        ])
+    }
+
+    /// Rank two, from a `4 × 2` and a `3 × 2` factor.
+    pub fn low_rank() -> Matrix<4, 3> {
+        let u = Matrix::<4, 2>::from_cols(&[[1., 0., 2., -1.], [0., 3., 1., 1.]]);
+        let v = Matrix::<3, 2>::from_cols(&[[1., 2., 0.], [-1., 0., 0.5]]);
+        Matrix::low_rank(&u, &v)
+    }
+
+    /// Blocks of size two, one and one.
+    pub fn block_diagonal() -> Matrix<4, 4> {
+        let block = Matrix::<2, 2>::from_cols(&[[2., -1.], [0.5, 3.]]);
+        block.direct_sum(&Matrix::<1, 1>::from_arr([4.])).direct_sum(&Matrix::<1, 1>::from_arr([-2.]))
+    }
+
+    /// Entry `(a, b, c)` is `a + 10b + 100c`, so each index reads off a digit.
+    pub fn tensor3() -> Tensor3<2, 3, 4> {
+        Tensor3::from_fun(|a, b, c| (a + 10 * b + 100 * c) as f32)
+    }
+
+    /// Entry `(a, b, c, d)` is `a + 2b + 6c + 24d`, distinct for every index.
+    pub fn tensor4() -> Tensor4<2, 3, 4, 5> {
+        Tensor4::from_fun(|a, b, c, d| (a + 2 * b + 6 * c + 24 * d) as f32)
+    }
+}
+
+#[cfg(test)]
+mod flavor_tests {
+    use super::*;
+    use crate::linalg::{f16, Scalar};
+    use crate::linalg::vector::{ConstantVector, SparseVector, Vector, VectorView, ZeroVector};
+
+    const EPS: f32 = 1e-5;
+
+    type Arr = [[f32; 3]; 3];
+
+    /// One sample of each of the basic flavors, plus transposed variants of the
+    /// stored ones.
+    fn samples() -> Vec<Matrix<3, 3>> {
+        let dense = Matrix::from_cols(&[
+            [1., -2., 3.],
+            [0.5, 4., -1.],
+            [2., 0., 7.],
+        ]);
+        let sparse = SparseMatrix::<3, 3>::from_triplets([
+            (0, 1, 2.),
+            (2, 0, -1.),
+            (1, 1, 0.5),
+            (2, 2, 3.),
+        ]);
+        vec![
+            Matrix::constant(1.5),
+            dense.T(),
+            dense,
+            Matrix::Diagonal(DiagonalMatrix::from_fun(|i| i as f32 - 0.5)),
+            Matrix::I(),
+            Matrix::Sparse(sparse.T()),
+            Matrix::Sparse(sparse),
+            Matrix::zero(),
+        ]
+    }
+
+    /// `samples`, followed by one of each structured flavor.
+    fn all_samples() -> Vec<Matrix<3, 3>> {
+        let dense = samples().swap_remove(2);
+        let low_rank = Matrix::low_rank(
+            &Matrix::<3, 2>::from_cols(&[[1., 0., -2.], [0.5, 3., 1.]]),
+            &Matrix::<3, 2>::from_cols(&[[2., -1., 0.], [1., 1., 4.]]),
+        );
+        let block_diagonal = Matrix::<2, 2>::from_cols(&[[2., -1.], [0.5, 3.]]).direct_sum(&Matrix::<1, 1>::from_arr([3.]));
+        let mut samples = samples();
+        samples.extend([
+            low_rank,
+            block_diagonal,
+            dense.upper_triangle(),
+            dense.T().lower_triangle(),
+            Matrix::permutation([2, 0, 1]),
+        ]);
+        samples
+    }
+
+    fn to_arr(m: &Matrix<3, 3>) -> Arr {
+        std::array::from_fn(|r| std::array::from_fn(|c| m[(r, c)]))
+    }
+
+    fn assert_close(actual: &Matrix<3, 3>, expected: Arr, what: &str) {
+        for r in 0..3 {
+            for c in 0..3 {
+                let (a, e) = (actual[(r, c)], expected[r][c]);
+                assert!((a - e).abs() < EPS, "{what}: ({r},{c}) is {a}, expected {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn mixed_flavor_arithmetic_matches_dense_reference() {
+        for m1 in all_samples() {
+            for m2 in all_samples() {
+                let (a, b) = (to_arr(&m1), to_arr(&m2));
+                let sum: Arr = std::array::from_fn(|r| std::array::from_fn(|c| a[r][c] + b[r][c]));
+                let diff: Arr = std::array::from_fn(|r| std::array::from_fn(|c| a[r][c] - b[r][c]));
+                let prod: Arr = std::array::from_fn(|r| std::array::from_fn(|c| {
+                    (0..3).map(|k| a[r][k] * b[k][c]).sum()
+                }));
+                let what = format!("{m1:?} and {m2:?}");
+
+                assert_close(&(&m1 + &m2), sum, &format!("add {what}"));
+                assert_close(&(&m1 - &m2), diff, &format!("sub {what}"));
+                assert_close(&(&m1 * &m2), prod, &format!("mul {what}"));
+
+                let mut acc = m1.clone();
+                acc += &m2;
+                assert_close(&acc, sum, &format!("add_assign {what}"));
+            }
+        }
+    }
+
+    #[test]
+    fn mixed_flavor_arithmetic_keeps_tightest_flavor() {
+        let diag = Matrix::<3, 3>::Diagonal(DiagonalMatrix::from_fun(|i| i as f32 + 1.));
+        let sparse = Matrix::<3, 3>::Sparse(SparseMatrix::from_triplets([(0, 2, 1.)]));
+
+        assert!(matches!(&diag * &diag, Matrix::Diagonal(_)));
+        assert!(matches!(&sparse + &diag, Matrix::Sparse(_)));
+        assert!(matches!(&Matrix::<3, 3>::I() - &Matrix::I(), Matrix::Zero(_)));
+        for m in all_samples() {
+            let same_flavor = std::mem::discriminant(&m);
+            assert_eq!(std::mem::discriminant(&(&Matrix::I() * &m)), same_flavor);
+            assert!(matches!(&Matrix::<3, 3>::zero() * &m, Matrix::Zero(_)));
+        }
+    }
+
+    fn vector_samples() -> Vec<Vector<3>> {
+        vec![
+            Vector::Constant(ConstantVector(0.5)),
+            Vector::from_arr([2., -1., 0.25]),
+            Vector::one_hot(1),
+            Vector::Sparse(SparseVector::from_pairs([(0, 3.), (2, -2.)])),
+            Vector::Zero(ZeroVector(0f32)),
+        ]
+    }
+
+    #[test]
+    fn matrix_vector_products_match_dense_reference() {
+        for m in all_samples() {
+            for v in vector_samples() {
+                let product = &m * &v;
+                for r in 0..3 {
+                    let expected: f32 = (0..3).map(|k| m[(r, k)] * v[k]).sum();
+                    assert!((product[r] - expected).abs() < EPS, "{m:?} * {v:?} at {r}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matrix_vector_products_keep_sparsity() {
+        let dense = Matrix::<3, 3>::from_cols(&[[1., 2., 3.], [4., 5., 6.], [7., 8., 9.]]);
+        let diag = Matrix::<3, 3>::Diagonal(DiagonalMatrix::from_fun(|i| i as f32 + 1.));
+        let sparse = &vector_samples()[3];
+
+        assert!(matches!(&dense * &Vector::one_hot(2), Vector::Dense(_)));
+        assert!(matches!(&diag * sparse, Vector::Sparse(_)));
+        assert!(matches!(&diag * &Vector::one_hot(0), Vector::Sparse(_)));
+        assert!(matches!(&Matrix::<3, 3>::I() * &Vector::one_hot(0), Vector::OneHot(_)));
+    }
+
+    #[test]
+    fn mixed_flavor_equality() {
+        assert_eq!(Matrix::<2, 2>::I(), Matrix::from_arr([1., 0., 0., 1.]));
+        assert_eq!(Matrix::<2, 2>::zero(), Matrix::constant(0.));
+        assert_ne!(Matrix::<2, 2>::I(), Matrix::zero());
+    }
+
+    #[test]
+    fn in_place_ops_match_out_of_place() {
+        for m1 in all_samples() {
+            for m2 in all_samples() {
+                let (a, b) = (to_arr(&m1), to_arr(&m2));
+                let what = format!("{m1:?} and {m2:?}");
+
+                let mut acc = m1.clone();
+                acc -= &m2;
+                assert_close(&acc, to_arr(&(&m1 - &m2)), &format!("sub_assign {what}"));
+
+                let mut acc = m1.clone();
+                acc.axpy(-0.5, &m2);
+                let expected: Arr = std::array::from_fn(|r| std::array::from_fn(|c| a[r][c] - 0.5 * b[r][c]));
+                assert_close(&acc, expected, &format!("axpy {what}"));
+
+                let mut acc = m1.clone();
+                acc *= &m2;
+                assert_close(&acc, to_arr(&(&m1 * &m2)), &format!("mul_assign {what}"));
+            }
+
+            let mut scaled = m1.clone();
+            scaled *= -3.;
+            assert_close(&scaled, to_arr(&(&m1 * -3.)), &format!("scale {m1:?}"));
+
+            let mut mapped = m1.clone();
+            mapped.map_in_place(|x| x * x + 1.);
+            let expected = to_arr(&m1).map(|row| row.map(|x| x * x + 1.));
+            assert_close(&mapped, expected, &format!("map_in_place {m1:?}"));
+        }
+    }
+
+    #[test]
+    fn add_outer_matches_outer_product() {
+        for m in all_samples() {
+            for u in vector_samples() {
+                for v in vector_samples() {
+                    let expected = to_arr(&(&m - &(&u.outer(&v) * 0.1)));
+                    let mut acc = m.clone();
+                    acc.add_outer(-0.1, &u, &v);
+                    assert_close(&acc, expected, &format!("{m:?} - 0.1 * {u:?} ⊗ {v:?}"));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn index_mut_promotes_to_dense() {
+        for m in all_samples() {
+            let mut written = m.clone();
+            written[(2, 1)] = 9.;
+            let mut expected = to_arr(&m);
+            expected[2][1] = 9.;
+            assert!(matches!(written, Matrix::Dense(_)));
+            assert_close(&written, expected, &format!("{m:?}"));
+        }
+    }
+
+    #[test]
+    fn vector_in_place_ops_match_out_of_place() {
+        for v1 in vector_samples() {
+            for v2 in vector_samples() {
+                let what = format!("{v1:?} and {v2:?}");
+
+                let mut acc = v1.clone();
+                acc -= &v2;
+                let diff = &v1 - &v2;
+                assert!((0..3).all(|i| (acc[i] - diff[i]).abs() < EPS), "sub_assign {what}");
+
+                let mut acc = v1.clone();
+                acc.axpy(2., &v2);
+                assert!((0..3).all(|i| (acc[i] - (v1[i] + 2. * v2[i])).abs() < EPS), "axpy {what}");
+            }
+
+            let mut scaled = v1.clone();
+            scaled *= -2.;
+            assert!((0..3).all(|i| scaled[i] == -2. * v1[i]), "scale {v1:?}");
+
+            let mut mapped = v1.clone();
+            mapped.map_in_place(|x| x - 1.);
+            assert!((0..3).all(|i| mapped[i] == v1[i] - 1.), "map_in_place {v1:?}");
+
+            let mut written = v1.clone();
+            written[2] = 4.;
+            assert_eq!([written[0], written[1], written[2]], [v1[0], v1[1], 4.]);
+        }
+    }
+
+    #[test]
+    fn scalar_minus_vector_matches_entrywise() {
+        for v in vector_samples() {
+            let difference = 10. - &v;
+            for i in 0..3 {
+                assert_eq!(difference[i], 10. - v[i], "{v:?} at {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn scalar_minus_vector_matches_densified() {
+        fn check<T: Scalar>(samples: [Vector<3, T>; 5], minus: impl Fn(&Vector<3, T>) -> Vector<3, T>) {
+            for v in samples {
+                let (difference, expected) = (minus(&v), minus(&Vector::Dense(v.clone().into_dense())));
+                for i in 0..3 {
+                    assert_eq!(difference[i], expected[i], "{v:?} at {i}");
+                }
+            }
+            assert!(matches!(minus(&Vector::zero()), Vector::Constant(_)));
+        }
+        check(vector_samples().try_into().unwrap(), |v| 10. - v);
+        check([
+            Vector::Constant(ConstantVector(0.5f64)),
+            Vector::from_arr([2., -1., 0.25]),
+            Vector::one_hot(1),
+            Vector::Sparse(SparseVector::from_pairs([(0, 3.), (2, -2.)])),
+            Vector::Zero(ZeroVector(0.)),
+        ], |v| 10. - v);
+        check([
+            Vector::Constant(ConstantVector(f16::from_f32(0.5))),
+            Vector::from_fun(|i| f16::from_f32(i as f32 - 1.)),
+            Vector::one_hot(1),
+            Vector::Sparse(SparseVector::from_pairs([(0, f16::from_f32(3.))])),
+            Vector::Zero(ZeroVector(f16::from_f32(0.))),
+        ], |v| f16::from_f32(10.) - v);
+    }
+
+    #[test]
+    fn sparse_axpy_stays_sparse() {
+        let mut v = Vector::<3>::from_pairs([(0, 1.)]);
+        v.axpy(3., &Vector::one_hot(2));
+        v.axpy(-1., &Vector::from_pairs([(0, 1.)]));
+        assert!(matches!(v, Vector::Sparse(_)));
+        assert_eq!(v, Vector::from_pairs([(2, 3.)]));
+    }
+
+    #[test]
+    fn views_match_indexing() {
+        for m in all_samples() {
+            let block = m.block::<1, 0, 2, 3>();
+            let centered = m.block::<1, 1, 2, 2>();
+            for r in 0..3 {
+                let (row, col) = (m.row(r), m.col(r));
+                for c in 0..3 {
+                    assert_eq!(row[c], m[(r, c)], "row {r} of {m:?}");
+                    assert_eq!(col[c], m[(c, r)], "col {r} of {m:?}");
+                }
+            }
+            for r in 0..2 {
+                for c in 0..3 {
+                    assert_eq!(block[(r, c)], m[(r + 1, c)], "block of {m:?}");
+                }
+                for c in 0..2 {
+                    assert_eq!(centered[(r, c)], m[(r + 1, c + 1)], "centered block of {m:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn views_keep_flavor_and_order() {
+        let transposed = samples()[2].T();
+        let MatrixView::Borrowed(block) = transposed.block::<0, 1, 3, 2>() else { panic!() };
+        assert_eq!(block.order, Order::ROWS);
+        let Matrix::Dense(copied) = transposed.block_owned::<0, 1, 3, 2>() else { panic!() };
+        assert_eq!(copied.order, Order::ROWS);
+        assert!(matches!(Matrix::<3, 3>::I().block::<1, 1, 2, 2>(), MatrixView::Owned(Matrix::Identity(_))));
+        assert!(matches!(Matrix::<3, 3>::I().block::<0, 1, 2, 2>(), MatrixView::Owned(Matrix::Sparse(_))));
+        assert!(matches!(Matrix::<3, 3>::I().row(1), VectorView::Owned(Vector::OneHot(_))));
+        assert!(matches!(Matrix::<3, 3>::constant(2.).col(0), VectorView::Owned(Vector::Constant(_))));
+        assert!(matches!(Matrix::<3, 3>::I().row_owned(1), Vector::OneHot(_)));
+    }
+
+    #[test]
+    fn views_borrow_dense_storage() {
+        for m in [samples().swap_remove(2), samples()[2].T()] {
+            let Matrix::Dense(storage) = &m else { panic!() };
+            let MatrixView::Borrowed(block) = m.block::<1, 0, 2, 3>() else { panic!() };
+            assert!(std::ptr::eq(block.data, &*storage.data));
+            for i in 0..3 {
+                let (VectorView::Borrowed(row), VectorView::Borrowed(col)) = (m.row(i), m.col(i)) else { panic!() };
+                assert!(std::ptr::eq(row.data, &*storage.data) && std::ptr::eq(col.data, &*storage.data));
+            }
+        }
+        let v = &vector_samples()[1];
+        let Vector::Dense(storage) = v else { panic!() };
+        let VectorView::Borrowed(tail) = v.slice::<1, 2>() else { panic!() };
+        assert!(std::ptr::eq(tail.data, &*storage.data));
+    }
+
+    #[test]
+    fn view_products_match_copied_blocks() {
+        for m in all_samples().into_iter().chain([samples()[2].T()]) {
+            let (block, copied) = (m.block::<1, 0, 2, 3>(), m.block_owned::<1, 0, 2, 3>());
+            assert_eq!(block.to_matrix(), copied, "{m:?}");
+            for v in vector_samples() {
+                let (product, expected) = (&block * &v, &copied * &v);
+                for r in 0..2 {
+                    assert!((product[r] - expected[r]).abs() < EPS, "{m:?} * {v:?} at {r}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn vector_slices_match_indexing() {
+        for v in vector_samples() {
+            let tail = v.slice::<1, 2>();
+            assert_eq!([tail[0], tail[1]], [v[1], v[2]], "{v:?}");
+            assert_eq!(tail.to_vector(), v.slice_owned::<1, 2>(), "{v:?}");
+        }
+        assert!(matches!(Vector::<3>::one_hot(0).slice::<1, 2>(), VectorView::Owned(Vector::Zero(_))));
+        assert!(matches!(Vector::<3>::one_hot(2).slice_owned::<1, 2>(), Vector::OneHot(_)));
+    }
+
+    #[test]
+    fn stacking_matches_indexing() {
+        for m1 in all_samples() {
+            for m2 in all_samples() {
+                let (h, v) = (m1.hstack(&m2), m1.vstack(&m2));
+                for r in 0..3 {
+                    for c in 0..3 {
+                        assert_eq!((h[(r, c)], h[(r, c + 3)]), (m1[(r, c)], m2[(r, c)]), "hstack {m1:?} {m2:?}");
+                        assert_eq!((v[(r, c)], v[(r + 3, c)]), (m1[(r, c)], m2[(r, c)]), "vstack {m1:?} {m2:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stacking_keeps_structure() {
+        let sparse = &samples()[6];
+        assert!(matches!(sparse.hstack(&sparse.T()), Matrix::Sparse(_)));
+        assert!(matches!(Matrix::<3, 3>::I().vstack(&Matrix::<2, 3>::zero()), Matrix::Sparse(_)));
+        assert!(matches!(Matrix::<3, 3>::zero().hstack(&Matrix::<3, 1>::zero()), Matrix::Zero(_)));
+        assert!(matches!(Matrix::<3, 3>::I().hstack(&Matrix::<3, 1>::constant(1.)), Matrix::Dense(_)));
+    }
+
+    #[test]
+    fn concat_matches_indexing() {
+        for v1 in vector_samples() {
+            for v2 in vector_samples() {
+                let v = v1.concat(&v2);
+                assert!((0..3).all(|i| v[i] == v1[i] && v[i + 3] == v2[i]), "{v1:?} {v2:?}");
+            }
+        }
+        assert!(matches!(Vector::<3>::zero().concat(&Vector::<2>::one_hot(1)), Vector::OneHot(_)));
+        assert!(matches!(Vector::<3>::one_hot(0).concat(&Vector::<2>::one_hot(1)), Vector::Sparse(_)));
+    }
+
+    #[test]
+    fn from_vector_reads_column_major() {
+        let dense = Matrix::<2, 3>::from_vector(Vector::from_arr([1., 2., 3., 4., 5., 6.]));
+        let one_hot = Matrix::<2, 3>::from_vector(Vector::one_hot(3));
+        let sparse = Matrix::<2, 3>::from_vector(Vector::from_pairs([(1, 2.), (4, -1.)]));
+
+        assert_eq!(dense[(1, 2)], 6.);
+        assert!(matches!(one_hot, Matrix::Sparse(_)));
+        assert_eq!(one_hot, Matrix::from_triplets([(1, 1, 1.)]));
+        assert!(matches!(sparse, Matrix::Sparse(_)));
+        assert_eq!(sparse, Matrix::from_triplets([(1, 0, 2.), (0, 2, -1.)]));
+
+        // A row-major transpose is read back column by column all the same.
+        assert_eq!(dense.T().into_vector()[1], dense[(0, 1)]);
+        assert!(matches!(Matrix::<3, 3>::I().into_vector(), Vector::Sparse(_)));
+        for m in [dense, one_hot, sparse] {
+            assert_eq!(Matrix::from_vector(m.clone().into_vector()), m);
+        }
+    }
+
+    /// First index of the extremum of `xs`, and the reference for `argmax`/`argmin`.
+    fn first_arg(xs: &[f32], wins: impl Fn(f32, f32) -> bool) -> usize {
+        (1..xs.len()).fold(0, |best, i| if wins(xs[i], xs[best]) { i } else { best })
+    }
+
+    #[test]
+    fn matrix_reductions_match_dense_reference() {
+        for m in all_samples() {
+            let a = to_arr(&m);
+            let flat: Vec<f32> = a.iter().flatten().copied().collect();
+            let mean = flat.iter().sum::<f32>() / 9.;
+            let close = |x: f32, e: f32, what: &str| assert!((x - e).abs() < EPS, "{what} of {m:?} is {x}, expected {e}");
+
+            let (row_sums, col_sums) = (m.row_sums(), m.col_sums());
+            for i in 0..3 {
+                close(row_sums[i], (0..3).map(|c| a[i][c]).sum(), "row_sums");
+                close(col_sums[i], (0..3).map(|r| a[r][i]).sum(), "col_sums");
+            }
+            close(m.sum(), flat.iter().sum(), "sum");
+            close(m.mean(), mean, "mean");
+            close(m.variance(), flat.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / 9., "variance");
+            close(m.norm_frobenius(), flat.iter().map(|x| x * x).sum::<f32>().sqrt(), "norm_frobenius");
+            close(m.norm_l1(), (0..3).map(|c| (0..3).map(|r| a[r][c].abs()).sum::<f32>()).fold(0., f32::max), "norm_l1");
+            close(m.norm_inf(), (0..3).map(|r| (0..3).map(|c| a[r][c].abs()).sum::<f32>()).fold(0., f32::max), "norm_inf");
+
+            let (argmax, argmin) = (first_arg(&flat, |x, y| x > y), first_arg(&flat, |x, y| x < y));
+            assert_eq!(m.argmax(), (argmax / 3, argmax % 3), "argmax of {m:?}");
+            assert_eq!(m.argmin(), (argmin / 3, argmin % 3), "argmin of {m:?}");
+            assert_eq!((m.max(), m.min()), (flat[argmax], flat[argmin]), "{m:?}");
+        }
+    }
+
+    #[test]
+    fn vector_reductions_match_dense_reference() {
+        let mut samples = vector_samples();
+        samples.push(Vector::from_pairs([(1, -2.)]));
+        samples.push(Vector::OneHot(crate::linalg::vector::OneHotVector { zero: 0., one: -1., index: 2 }));
+        for v in samples {
+            let xs = [v[0], v[1], v[2]];
+            let mean = xs.iter().sum::<f32>() / 3.;
+            let close = |x: f32, e: f32, what: &str| assert!((x - e).abs() < EPS, "{what} of {v:?} is {x}, expected {e}");
+
+            close(v.mean(), mean, "mean");
+            close(v.variance(), xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / 3., "variance");
+            close(v.norm_l1(), xs.iter().map(|x| x.abs()).sum(), "norm_l1");
+            close(v.norm_l2(), xs.iter().map(|x| x * x).sum::<f32>().sqrt(), "norm_l2");
+            close(v.norm_inf(), xs.iter().map(|x| x.abs()).fold(0., f32::max), "norm_inf");
+            assert_eq!(v.argmax(), first_arg(&xs, |x, y| x > y), "argmax of {v:?}");
+            assert_eq!(v.argmin(), first_arg(&xs, |x, y| x < y), "argmin of {v:?}");
+            assert_eq!((v.max(), v.min()), (xs[v.argmax()], xs[v.argmin()]), "{v:?}");
+        }
+    }
+
+    #[test]
+    fn reductions_keep_structure() {
+        assert_eq!(Vector::<5>::one_hot(3).argmax(), 3);
+        assert_eq!(Vector::<4>::Constant(ConstantVector(0.5)).sum(), 2.);
+        assert!(matches!(Matrix::<3, 3>::constant(2.).row_sums(), Vector::Constant(_)));
+        assert!(matches!(Matrix::<3, 3>::I().col_sums(), Vector::Constant(_)));
+        assert!(matches!(samples()[6].row_sums(), Vector::Sparse(_)));
+        assert!(matches!(Matrix::<3, 3>::zero().col_sums(), Vector::Zero(_)));
+
+        // Sparse flavors that store nothing are all zeros.
+        let empty = Matrix::<2, 2>::sparse();
+        assert_eq!((empty.argmax(), empty.argmin()), ((0, 0), (0, 0)));
+        let cleared = Matrix::<2, 2>::from_cols(&[[0.001, 0.], [0., 0.001]]).sparsify(1.);
+        assert_eq!((cleared.max(), cleared.min()), (0., 0.));
+    }
+
+    #[test]
+    fn hadamard_matches_dense_reference() {
+        for m1 in all_samples() {
+            for m2 in all_samples() {
+                let (a, b) = (to_arr(&m1), to_arr(&m2));
+                let prod: Arr = std::array::from_fn(|r| std::array::from_fn(|c| a[r][c] * b[r][c]));
+                assert_close(&m1.hadamard(&m2), prod, &format!("hadamard {m1:?} and {m2:?}"));
+            }
+        }
+        for v1 in vector_samples() {
+            for v2 in vector_samples() {
+                let v = v1.hadamard(&v2);
+                assert!((0..3).all(|i| v[i] == v1[i] * v2[i]), "{v1:?} {v2:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn hadamard_keeps_sparser_flavor() {
+        let [constant, dense, _, diagonal, identity, _, sparse, _] = samples().try_into().unwrap();
+        assert!(matches!(dense.hadamard(&identity), Matrix::Diagonal(_)));
+        assert!(matches!(sparse.hadamard(&constant), Matrix::Sparse(_)));
+        assert!(matches!(diagonal.hadamard(&sparse), Matrix::Diagonal(_)));
+        assert!(matches!(identity.hadamard(&identity), Matrix::Identity(_)));
+        assert!(matches!(Vector::<3>::one_hot(1).hadamard(&Vector::from_arr([1., 2., 3.])), Vector::OneHot(_)));
+    }
+
+    #[test]
+    fn kron_matches_dense_reference() {
+        for m1 in all_samples() {
+            let wide = Matrix::<2, 3>::from_cols(&[[1., -1.], [0., 2.], [0.5, 3.]]);
+            for m2 in all_samples() {
+                let k = m1.kron(&m2);
+                let k_wide = m1.kron(&wide);
+                for r in 0..9 {
+                    for c in 0..9 {
+                        let e = m1[(r / 3, c / 3)] * m2[(r % 3, c % 3)];
+                        assert!((k[(r, c)] - e).abs() < EPS, "{m1:?} ⊗ {m2:?} at ({r},{c})");
+                    }
+                }
+                for r in 0..6 {
+                    for c in 0..9 {
+                        let e = m1[(r / 2, c / 3)] * wide[(r % 2, c % 3)];
+                        assert!((k_wide[(r, c)] - e).abs() < EPS, "{m1:?} ⊗ wide at ({r},{c})");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn kron_keeps_structure() {
+        let [constant, dense, _, diagonal, identity, _, sparse, _] = samples().try_into().unwrap();
+        assert!(matches!(identity.kron(&identity), Matrix::Identity(_)));
+        assert!(matches!(identity.kron(&diagonal), Matrix::Diagonal(_)));
+        assert!(matches!(identity.kron(&dense), Matrix::Sparse(_)));
+        assert!(matches!(sparse.kron(&constant), Matrix::Sparse(_)));
+        assert!(matches!(constant.kron(&constant), Matrix::Constant(_)));
+        assert!(matches!(dense.kron(&Matrix::<2, 2>::zero()), Matrix::Zero(_)));
+    }
+}
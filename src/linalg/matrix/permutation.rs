@@ -0,0 +1,182 @@
+use std::ops::{Index, Mul};
+
+use crate::linalg::Scalar;
+use crate::linalg::vector::{ConstantVector, OneHotVector, SparseVector, Vector};
+
+use super::Order;
+use super::constant::ConstantMatrix;
+use super::dense::DenseMatrix;
+use super::sparse::SparseMatrix;
+use super::wrapper::Matrix;
+
+/// A permutation matrix scaled by `one`: row `i` holds `one` in column
+/// `perm[i]` and zeros elsewhere, so `(P x)[i] = one * x[perm[i]]`.
+/// Products with it only move entries around.
+#[derive(Clone, Debug)]
+pub struct PermutationMatrix<const R: usize, const C: usize, T: Scalar = f32> {
+    pub(super) perm: Box<[usize]>,
+    pub(super) one: T,
+}
+
+impl<const R: usize, const C: usize, T: Scalar> PermutationMatrix<R, C, T> where [(); R*C]: Sized {
+    /// `inverse[perm[i]] == i`.
+    fn inverse_perm(&self) -> Box<[usize]> {
+        let mut inverse = vec![0; self.perm.len()].into_boxed_slice();
+        for (i, &j) in self.perm.iter().enumerate() {
+            inverse[j] = i;
+        }
+        inverse
+    }
+
+    pub(super) fn T(&self) -> PermutationMatrix<C, R, T> {
+        PermutationMatrix { perm: self.inverse_perm(), one: self.one }
+    }
+
+    pub(super) fn scaled<const R2: usize, const C2: usize>(&self, alpha: T) -> PermutationMatrix<R2, C2, T> {
+        PermutationMatrix { perm: self.perm.clone(), one: self.one * alpha }
+    }
+
+    /// `self * other`, which picks `x[other.perm[self.perm[i]]]` for row `i`.
+    pub(super) fn then<const C2: usize>(&self, other: &PermutationMatrix<C, C2, T>) -> PermutationMatrix<R, C2, T> {
+        PermutationMatrix {
+            perm: self.perm.iter().map(|&j| other.perm[j]).collect(),
+            one: self.one * other.one,
+        }
+    }
+
+    /// `1` for an even permutation and `-1` for an odd one. A cycle of
+    /// length `n` takes `n - 1` swaps.
+    pub(super) fn sign(&self) -> T {
+        let mut seen = vec![false; self.perm.len()];
+        let mut cycles = 0;
+        for start in 0..self.perm.len() {
+            if !seen[start] {
+                cycles += 1;
+                let mut i = start;
+                while !seen[i] {
+                    seen[i] = true;
+                    i = self.perm[i];
+                }
+            }
+        }
+        if (self.perm.len() - cycles).is_multiple_of(2) { T::one() } else { -T::one() }
+    }
+
+    /// The position of the nonzero in row `i` for `Order::ROWS`, or in
+    /// column `i` for `Order::COLS`.
+    pub(super) fn line_index(&self, order: Order, i: usize) -> usize {
+        match order {
+            Order::ROWS => self.perm[i],
+            Order::COLS => self.perm.iter().position(|&j| j == i).unwrap(),
+        }
+    }
+
+    pub(super) fn stored(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        self.perm.iter().enumerate().map(|(i, &j)| (i, j, self.one))
+    }
+
+    /// `self * x`: row `i` of the result is row `perm[i]` of `x`, scaled.
+    /// Sparse and constant flavors stay that way.
+    pub(super) fn permute_rows<const C2: usize>(&self, x: &Matrix<C, C2, T>) -> Matrix<R, C2, T>
+        where [(); C*C2]: Sized, [(); R*C2]: Sized
+    {
+        use Matrix as M;
+        match x {
+            M::Constant(m) => M::Constant(ConstantMatrix(self.one * m.0)),
+            x if x.has_sparse_structure() => {
+                let inverse = self.inverse_perm();
+                M::Sparse(SparseMatrix::from_triplets(x.to_sparse().nonzeros().map(|(r, c, y)| (inverse[r], c, self.one * y))))
+            },
+            x => M::Dense(DenseMatrix::from_fun(|r, c| self.one * x.get(self.perm[r], c))),
+        }
+    }
+
+    /// `x * self`: column `perm[j]` of the result is column `j` of `x`, scaled.
+    pub(super) fn permute_cols<const R0: usize>(&self, x: &Matrix<R0, R, T>) -> Matrix<R0, C, T>
+        where [(); R0*R]: Sized, [(); R0*C]: Sized
+    {
+        use Matrix as M;
+        match x {
+            M::Constant(m) => M::Constant(ConstantMatrix(self.one * m.0)),
+            x if x.has_sparse_structure() => {
+                M::Sparse(SparseMatrix::from_triplets(x.to_sparse().nonzeros().map(|(r, c, y)| (r, self.perm[c], self.one * y))))
+            },
+            x => {
+                let inverse = self.inverse_perm();
+                M::Dense(DenseMatrix::from_fun(|r, c| self.one * x.get(r, inverse[c])))
+            },
+        }
+    }
+
+    pub(super) fn inverse(&self) -> PermutationMatrix<C, R, T> {
+        PermutationMatrix { perm: self.inverse_perm(), one: T::one() / self.one }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&Vector<C, T>> for &PermutationMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Vector<R, T>;
+
+    /// A gather, which keeps the flavor of `rhs`.
+    fn mul(self, rhs: &Vector<C, T>) -> Self::Output {
+        use Vector as V;
+        match rhs {
+            V::Constant(v) => V::Constant(ConstantVector(self.one * v.0)),
+            V::Dense(v) => V::from_fun(|i| self.one * v.data[self.perm[i]]),
+            V::OneHot(v) => V::OneHot(OneHotVector {
+                zero: T::zero(),
+                one: self.one * v.one,
+                index: self.line_index(Order::COLS, v.index),
+            }),
+            V::Sparse(_) => {
+                let inverse = self.inverse_perm();
+                V::Sparse(SparseVector::from_pairs(rhs.nonzeros().map(|(j, x)| (inverse[j], self.one * x))))
+            },
+            V::Zero(_) => V::zero(),
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for PermutationMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(r < R && c < C);
+        if self.perm[r] == c { &self.one } else { T::ZERO_REF }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::linalg::{Matrix, Vector};
+
+    #[test]
+    fn permutations_move_entries() {
+        let p = Matrix::<3, 3>::permutation([2, 0, 1]);
+        let x = Vector::from_arr([1., 2., 3.]);
+        assert_eq!(&p * &x, Vector::from_arr([3., 1., 2.]));
+        assert!(matches!(&p * &Vector::one_hot(0), Vector::OneHot(_)));
+        assert_eq!(&p.T() * &(&p * &x), x);
+
+        let m = Matrix::<3, 2>::from_cols(&[[1., 2., 3.], [4., 5., 6.]]);
+        assert_eq!(&p * &m, Matrix::from_cols(&[[3., 1., 2.], [6., 4., 5.]]));
+        let sparse = Matrix::<3, 3>::from_triplets([(0, 1, 2.)]);
+        assert!(matches!(&p * &sparse, Matrix::Sparse(_)));
+        assert!(matches!(&sparse * &p, Matrix::Sparse(_)));
+        assert!(matches!(&p * &p, Matrix::Permutation(_)));
+        assert!(matches!(&p * &Matrix::<3, 4>::constant(2.), Matrix::Constant(_)));
+    }
+
+    #[test]
+    fn sign_and_inverse() {
+        let even = Matrix::<3, 3>::permutation([2, 0, 1]);
+        let odd = Matrix::<3, 3>::permutation([1, 0, 2]);
+        assert_eq!(even.det(), 1.);
+        assert_eq!(odd.det(), -1.);
+        assert_eq!((&odd * 2.).det(), -8.);
+
+        let inv = even.inverse().unwrap();
+        assert!(matches!(inv, Matrix::Permutation(_)));
+        assert_eq!(&even * &inv, Matrix::I());
+        assert_eq!(even.solve(&Vector::from_arr([3., 1., 2.])).unwrap(), Vector::from_arr([1., 2., 3.]));
+    }
+}
@@ -0,0 +1,74 @@
+//! Products other than the matrix product, which lives with the flavors.
+
+use crate::linalg::Scalar;
+
+use super::constant::ConstantMatrix;
+use super::dense::DenseMatrix;
+use super::diagonal::DiagonalMatrix;
+use super::identity::IdentityMatrix;
+use super::sparse::SparseMatrix;
+use super::triangular::TriangularMatrix;
+use super::wrapper::Matrix;
+use super::zero::ZeroMatrix;
+
+impl<const R: usize, const C: usize, T: Scalar> Matrix<R, C, T> where [(); R*C]: Sized {
+    /// The entrywise product. An entry that is zero on either side stays
+    /// zero, so diagonal and sparse operands bound the flavor of the result.
+    pub fn hadamard(&self, other: &Matrix<R, C, T>) -> Matrix<R, C, T> {
+        use Matrix as M;
+        match (self, other) {
+            (M::Zero(_), _) | (_, M::Zero(_)) => M::Zero(ZeroMatrix(T::zero())),
+            (M::Constant(m), x) | (x, M::Constant(m)) => x * m.0,
+            (M::Identity(m1), M::Identity(m2)) => M::Identity(IdentityMatrix(T::zero(), m1.1 * m2.1)),
+            (M::Identity(m), x) | (x, M::Identity(m)) => {
+                M::Diagonal(DiagonalMatrix::from_fun(|i| m.1 * x.get(i, i)))
+            },
+            (M::Diagonal(m), x) | (x, M::Diagonal(m)) => {
+                M::Diagonal(DiagonalMatrix::from_fun(|i| m.diagonal_data[i] * x.get(i, i)))
+            },
+            (M::Sparse(m), x) | (x, M::Sparse(m)) => {
+                M::Sparse(SparseMatrix::from_triplets(m.nonzeros().map(|(r, c, y)| (r, c, y * x.get(r, c)))))
+            },
+            (M::Permutation(m), x) | (x, M::Permutation(m)) => {
+                M::Sparse(SparseMatrix::from_triplets(m.stored().map(|(r, c, y)| (r, c, y * x.get(r, c)))))
+            },
+            (M::BlockDiagonal(m), x) | (x, M::BlockDiagonal(m)) => M::BlockDiagonal(m.map_indexed(|r, c, y| y * x.get(r, c))),
+            (M::LowerTriangular(m1), M::UpperTriangular(m2)) | (M::UpperTriangular(m2), M::LowerTriangular(m1)) => {
+                M::Diagonal(DiagonalMatrix::from_fun(|i| m1.0[(i, i)] * m2.0[(i, i)]))
+            },
+            (M::LowerTriangular(m), x) | (x, M::LowerTriangular(m)) => M::LowerTriangular(TriangularMatrix(m.0.hadamard(&x.to_dense()))),
+            (M::UpperTriangular(m), x) | (x, M::UpperTriangular(m)) => M::UpperTriangular(TriangularMatrix(m.0.hadamard(&x.to_dense()))),
+            (M::Dense(m1), M::Dense(m2)) => M::Dense(m1.hadamard(m2)),
+            _ => M::Dense(self.to_dense().hadamard(&other.to_dense())),
+        }
+    }
+
+    /// The Kronecker product `self ⊗ other`: block `(i, j)` of the result is
+    /// `self[(i, j)] * other`.
+    ///
+    /// Diagonal and identity factors (with square `other`) give a diagonal or
+    /// identity result. A factor with sparse structure on either side gives a
+    /// sparse result built only from the stored entries of both sides.
+    pub fn kron<const R2: usize, const C2: usize>(&self, other: &Matrix<R2, C2, T>) -> Matrix<{R*R2}, {C*C2}, T>
+        where
+            [(); R2*C2]: Sized,
+            [(); (R*R2)*(C*C2)]: Sized,
+    {
+        use Matrix as M;
+        match (self, other) {
+            (M::Zero(_), _) | (_, M::Zero(_)) => M::Zero(ZeroMatrix(T::zero())),
+            (M::Constant(m1), M::Constant(m2)) => M::Constant(ConstantMatrix(m1.0 * m2.0)),
+            (M::Identity(m1), M::Identity(m2)) if R2 == C2 => M::Identity(IdentityMatrix(T::zero(), m1.1 * m2.1)),
+            (M::Diagonal(_) | M::Identity(_), M::Diagonal(_) | M::Identity(_)) if R2 == C2 => {
+                M::Diagonal(DiagonalMatrix::from_fun(|i| self.get(i / R2, i / R2) * other.get(i % R2, i % R2)))
+            },
+            (M::Dense(m1), M::Dense(m2)) => M::Dense(m1.kron(m2)),
+            _ if !self.has_sparse_structure() && !other.has_sparse_structure() => {
+                M::Dense(DenseMatrix::from_fun(|r, c| self.get(r / R2, c / C2) * other.get(r % R2, c % C2)))
+            },
+            _ => M::Sparse(SparseMatrix::from_triplets(self.stored().flat_map(|(i, j, x)| {
+                other.stored().map(move |(k, l, y)| (i*R2 + k, j*C2 + l, x * y))
+            }))),
+        }
+    }
+}
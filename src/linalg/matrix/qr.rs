@@ -0,0 +1,171 @@
+use crate::linalg::{kernels, Scalar};
+use crate::linalg::vector::Vector;
+
+use super::wrapper::Matrix;
+
+/// Householder QR decomposition, `A = QR`.
+///
+/// Reflector `k` is `I - τₖvₖvₖᵀ`, where `vₖ` is zero above row `k`, one at
+/// row `k`, and stored below the diagonal of column `k` of the packed
+/// column-major buffer. The diagonal and everything above it hold `R`.
+#[derive(Clone, Debug)]
+pub struct Qr<const R: usize, const C: usize, T: Scalar = f32> {
+    qr: Box<[T]>,
+    taus: Box<[T]>,
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Qr<R, C, T> where [(); R*C]: Sized {
+    /// Each reflector maps the rest of its column onto a multiple of `eₖ`,
+    /// picking the sign that avoids cancellation, and is then applied to
+    /// the columns to its right.
+    fn new(a: &Matrix<R, C, T>) -> Self {
+        let mut qr: Box<[T]> = (0..R*C).map(|i| a.get(i % R, i / R)).collect();
+        let mut taus = vec![T::zero(); R.min(C)].into_boxed_slice();
+
+        for k in 0..R.min(C) {
+            let (left, right) = qr.split_at_mut((k + 1)*R);
+            let col = &mut left[k*R + k..];
+            let x0 = col[0];
+            let norm = (x0 * x0 + kernels::sum_of_squares(&col[1..])).sqrt();
+            if norm == T::zero() {
+                continue;
+            }
+
+            let alpha = if x0 > T::zero() { -norm } else { norm };
+            let v0 = x0 - alpha;
+            kernels::scale_assign(&mut col[1..], T::one() / v0);
+            col[0] = alpha;
+            taus[k] = -v0 / alpha;
+
+            let v = &col[1..];
+            for next in right.chunks_exact_mut(R) {
+                reflect(taus[k], v, &mut next[k..]);
+            }
+        }
+
+        Qr { qr, taus }
+    }
+
+    /// The orthogonal factor, built by reflecting the columns of the identity.
+    pub fn Q(&self) -> Matrix<R, R, T> where [(); R*R]: Sized {
+        let mut q = vec![T::zero(); R*R].into_boxed_slice();
+        for (j, col) in q.chunks_exact_mut(R).enumerate() {
+            col[j] = T::one();
+            for k in (0..R.min(C)).rev() {
+                reflect(self.taus[k], self.reflector(k), &mut col[k..]);
+            }
+        }
+        Matrix::from_boxed_slice(q)
+    }
+
+    /// The upper trapezoidal factor.
+    pub fn R(&self) -> Matrix<R, C, T> {
+        Matrix::from_boxed_slice((0..R*C).map(|i| {
+            if i % R <= i / R { self.qr[i] } else { T::zero() }
+        }).collect())
+    }
+
+    /// The `x` minimizing `‖Ax - b‖₂`, from `Rx = Qᵀb` by back substitution.
+    ///
+    /// Diagonal entries of `R` that are negligible next to the largest one
+    /// mark directions `A` does not determine. Their components are set to
+    /// zero, as are those of the trailing columns when `C > R`.
+    pub fn lstsq(&self, b: &Vector<R, T>) -> Vector<C, T> {
+        let n = R.min(C);
+        let mut qtb: Box<[T]> = (0..R).map(|i| b[i]).collect();
+        for k in 0..n {
+            reflect(self.taus[k], self.reflector(k), &mut qtb[k..]);
+        }
+
+        let diag = |k: usize| self.qr[k*R + k];
+        let largest = (0..n).map(|k| diag(k).abs()).fold(T::zero(), T::max);
+        let cutoff = largest * T::from_usize(R.max(C)) * T::epsilon();
+
+        let mut x = vec![T::zero(); C].into_boxed_slice();
+        for k in (0..n).rev() {
+            if diag(k).abs() <= cutoff {
+                qtb[k] = T::zero();
+                continue;
+            }
+            let y = qtb[k] / diag(k);
+            x[k] = y;
+            kernels::axpy(&mut qtb[..k], -y, &self.qr[k*R..k*R + k]);
+        }
+        Vector::from_boxed_slice(x)
+    }
+
+    /// The stored part of `vₖ`, below its implicit leading one.
+    fn reflector(&self, k: usize) -> &[T] {
+        &self.qr[k*R + k + 1..(k + 1)*R]
+    }
+}
+
+/// Applies `I - τvvᵀ` to `x`, where `v` is `[1, tail...]`.
+fn reflect<T: Scalar>(tau: T, tail: &[T], x: &mut [T]) {
+    if tau == T::zero() {
+        return;
+    }
+    let (head, rest) = x.split_first_mut().unwrap();
+    let w = tau * (*head + kernels::dot(tail, rest));
+    *head -= w;
+    kernels::axpy(rest, -w, tail);
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Matrix<R, C, T> where [(); R*C]: Sized {
+    pub fn qr(&self) -> Qr<R, C, T> {
+        Qr::new(self)
+    }
+
+    /// Least squares through QR, which keeps the conditioning of `self`
+    /// rather than squaring it as the normal equations would.
+    pub fn lstsq(&self, b: &Vector<R, T>) -> Vector<C, T> {
+        self.qr().lstsq(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::test_support::{assert_close, tall, EPS};
+
+    #[test]
+    fn tall_and_wide_factors_multiply_back() {
+        let tall = tall();
+        let qr = tall.qr();
+        assert_close(&(&qr.Q() * &qr.R()), &tall);
+        assert_close(&(&qr.Q().T() * &qr.Q()), &Matrix::I());
+
+        let wide = tall.T();
+        let qr = wide.qr();
+        assert_close(&(&qr.Q() * &qr.R()), &wide);
+        assert!((0..3).all(|c| (c + 1..3).all(|r| qr.R()[(r, c)] == 0.)));
+    }
+
+    #[test]
+    fn lstsq_recovers_exact_fit() {
+        // y = 2 - 3x sampled at x = 0..5, with a column of ones for the intercept.
+        let a = Matrix::<5, 2>::from_cols(&[[1.; 5], [0., 1., 2., 3., 4.]]);
+        let y = Vector::from_fun(|i| 2. - 3. * i as f32);
+        let coef = a.lstsq(&y);
+        assert!((coef[0] - 2.).abs() < EPS && (coef[1] + 3.).abs() < EPS);
+    }
+
+    #[test]
+    fn lstsq_residual_is_orthogonal_to_columns() {
+        let a = Matrix::<5, 2>::from_cols(&[[1.; 5], [0., 1., 2., 3., 4.]]);
+        let y = Vector::from_arr([1., 3., 2., 5., 4.]);
+        let residual = &(&a * &a.lstsq(&y)) - &y;
+        let normal = &a.T() * &residual;
+        assert!((0..2).all(|i| normal[i].abs() < EPS));
+    }
+
+    #[test]
+    fn lstsq_zeroes_undetermined_components() {
+        // The second column repeats the first, so only their sum is determined.
+        let a = Matrix::<3, 2>::from_cols(&[[1., 2., 3.], [1., 2., 3.]]);
+        let x = a.lstsq(&Vector::from_arr([2., 4., 6.]));
+        let ax = &a * &x;
+        assert!(x[0].is_finite() && x[1].is_finite());
+        assert!((0..3).all(|i| (ax[i] - 2. * (i + 1) as f32).abs() < EPS));
+    }
+}
@@ -0,0 +1,286 @@
+use std::marker::PhantomData;
+use std::ops::{Index, Mul};
+use std::sync::OnceLock;
+
+use crate::linalg::{kernels, Scalar};
+use crate::linalg::vector::Vector;
+
+use super::Order;
+use super::dense::DenseMatrix;
+
+/// Entries stored as `i8`, with one affine map per row (`per == Order::ROWS`)
+/// or per column (`per == Order::COLS`): entry `q` on line `i` stands for
+/// `scales[i] * (q - zero_points[i])`. The lines that carry a scale are the
+/// contiguous ones, so a transpose only flips `per`.
+///
+/// Products with dense vectors quantize the vector on the fly and accumulate
+/// in `i32`, which reads a quarter of the bytes of an `f32` matrix.
+#[derive(Clone, Debug)]
+pub struct QuantizedMatrix<const R: usize, const C: usize, T: Scalar = f32> where [(); R*C]: Sized {
+    pub(super) data: Box<[i8]>,
+    pub(super) scales: Box<[T]>,
+    pub(super) zero_points: Box<[i8]>,
+    pub(super) per: Order,
+    /// The dequantized entries, column-major, filled in the first time one
+    /// is read through `Index`. Internal reads go through `get` instead, so
+    /// this stays empty unless a caller asks for a reference.
+    entries: OnceLock<Box<[T]>>,
+    size_marker: PhantomData<[[T; R]; C]>,
+}
+
+/// The symmetric quantization of `x`, `x ≈ scale * q`, for the vector side of
+/// a product. All zeros come back with a scale of zero.
+fn quantize_symmetric<T: Scalar>(x: &[T]) -> (T, Box<[i8]>) {
+    let max = x.iter().fold(T::zero(), |acc, &y| acc.max(y.abs()));
+    if max == T::zero() {
+        return (T::zero(), vec![0; x.len()].into_boxed_slice());
+    }
+    let scale = max / T::from_f32(127.);
+    (scale, x.iter().map(|&y| (y / scale).round().to_f32() as i8).collect())
+}
+
+impl<const R: usize, const C: usize, T: Scalar> QuantizedMatrix<R, C, T> where [(); R*C]: Sized {
+    // constructor
+    /// Maps each line of `m` along `per` onto `-128..=127` so that its
+    /// smallest and largest entries land on the ends of the range. The range
+    /// always covers zero, which therefore survives exactly.
+    pub(super) fn quantize(m: &DenseMatrix<R, C, T>, per: Order) -> Self {
+        let (lines, len) = match per {
+            Order::ROWS => (R, C),
+            Order::COLS => (C, R),
+        };
+        let mut data = Vec::with_capacity(R*C);
+        let mut scales = Vec::with_capacity(lines);
+        let mut zero_points = Vec::with_capacity(lines);
+        for i in 0..lines {
+            let line = m.line(per, i);
+            let lo = line.iter().fold(0f32, |acc, &x| acc.min(x.to_f32()));
+            let hi = line.iter().fold(0f32, |acc, &x| acc.max(x.to_f32()));
+            let scale = if hi > lo { (hi - lo) / 255. } else { 1. };
+            let zero_point = (-128. - lo / scale).round().clamp(-128., 127.);
+            data.extend(line.iter().map(|&x| (x.to_f32() / scale + zero_point).round().clamp(-128., 127.) as i8));
+            scales.push(T::from_f32(scale));
+            zero_points.push(zero_point as i8);
+        }
+        debug_assert_eq!(data.len(), lines * len);
+        QuantizedMatrix {
+            data: data.into_boxed_slice(),
+            scales: scales.into_boxed_slice(),
+            zero_points: zero_points.into_boxed_slice(),
+            per,
+            entries: OnceLock::new(),
+            size_marker: PhantomData,
+        }
+    }
+
+    fn line_len(&self) -> usize {
+        match self.per {
+            Order::ROWS => C,
+            Order::COLS => R,
+        }
+    }
+
+    /// The stored integers of line `i` along `per`.
+    fn quantized_line(&self, i: usize) -> &[i8] {
+        let len = self.line_len();
+        &self.data[i*len..(i + 1)*len]
+    }
+
+    fn dequantize(&self, i: usize, q: i8) -> T {
+        self.scales[i] * T::from_f32(f32::from(q) - f32::from(self.zero_points[i]))
+    }
+
+    /// Entry `(r, c)`, dequantized.
+    fn entry(&self, r: usize, c: usize) -> T {
+        match self.per {
+            Order::ROWS => self.dequantize(r, self.data[r*C + c]),
+            Order::COLS => self.dequantize(c, self.data[c*R + r]),
+        }
+    }
+
+    /// Entry `(r, c)`, dequantized on the spot.
+    pub(super) fn get(&self, r: usize, c: usize) -> T {
+        assert!(r < R && c < C);
+        self.entry(r, c)
+    }
+
+    pub(super) fn T(&self) -> QuantizedMatrix<C, R, T> where [(); C*R]: Sized {
+        QuantizedMatrix {
+            data: self.data.clone(),
+            scales: self.scales.clone(),
+            zero_points: self.zero_points.clone(),
+            per: -self.per,
+            entries: OnceLock::new(),
+            size_marker: PhantomData,
+        }
+    }
+
+    /// `alpha * self`, which only touches the scales. Products with the
+    /// identity come through here too, hence the free dimensions.
+    pub(super) fn scaled<const R2: usize, const C2: usize>(&self, alpha: T) -> QuantizedMatrix<R2, C2, T>
+        where [(); R2*C2]: Sized
+    {
+        QuantizedMatrix {
+            data: self.data.clone(),
+            scales: self.scales.iter().map(|&s| alpha * s).collect(),
+            zero_points: self.zero_points.clone(),
+            per: self.per,
+            entries: OnceLock::new(),
+            size_marker: PhantomData,
+        }
+    }
+
+    pub(super) fn to_dense(&self) -> DenseMatrix<R, C, T> {
+        let len = self.line_len();
+        let data = self.data.iter().enumerate().map(|(k, &q)| self.dequantize(k / len, q)).collect();
+        DenseMatrix { data, order: self.per, size_marker: PhantomData }
+    }
+
+    pub(super) fn nnz(&self) -> usize {
+        let len = self.line_len();
+        self.data.iter().enumerate()
+            .filter(|&(k, &q)| q != self.zero_points[k / len] && self.scales[k / len] != T::zero())
+            .count()
+    }
+
+    /// Every `(row, col, value)`, dequantized, in storage order.
+    pub(super) fn stored(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        let len = self.line_len();
+        self.data.iter().enumerate().map(move |(k, &q)| {
+            let (i, j) = (k / len, k % len);
+            let x = self.dequantize(i, q);
+            match self.per {
+                Order::ROWS => (i, j, x),
+                Order::COLS => (j, i, x),
+            }
+        })
+    }
+
+    /// Row `i` for `Order::ROWS`, column `i` for `Order::COLS`, dequantized.
+    pub(super) fn line(&self, order: Order, i: usize) -> Box<[T]> {
+        if order == self.per {
+            return self.quantized_line(i).iter().map(|&q| self.dequantize(i, q)).collect();
+        }
+        match order {
+            Order::ROWS => (0..C).map(|c| self.entry(i, c)).collect(),
+            Order::COLS => (0..R).map(|r| self.entry(r, i)).collect(),
+        }
+    }
+
+    /// `self * x` with integer arithmetic throughout: `x` is quantized
+    /// symmetrically, every line is an `i8` dot product or axpy into `i32`,
+    /// and the zero points are corrected for with the sum of the quantized
+    /// `x`. Per-column scales are folded into `x` before it is quantized.
+    fn mul_dense(&self, x: &[T]) -> Box<[T]> {
+        match self.per {
+            Order::ROWS => {
+                let (sx, qx) = quantize_symmetric(x);
+                let qx_sum: i32 = qx.iter().map(|&q| i32::from(q)).sum();
+                (0..R).map(|r| {
+                    let acc = kernels::dot_i8(self.quantized_line(r), &qx) - i32::from(self.zero_points[r]) * qx_sum;
+                    self.scales[r] * sx * T::from_f32(acc as f32)
+                }).collect()
+            },
+            Order::COLS => {
+                let scaled: Box<[T]> = x.iter().zip(&self.scales).map(|(&y, &s)| y * s).collect();
+                let (sx, qx) = quantize_symmetric(&scaled);
+                let mut acc = vec![0i32; R];
+                let mut offset = 0i32;
+                for (c, &q) in qx.iter().enumerate() {
+                    if q != 0 {
+                        kernels::axpy_i8(&mut acc, i32::from(q), self.quantized_line(c));
+                        offset += i32::from(q) * i32::from(self.zero_points[c]);
+                    }
+                }
+                acc.iter().map(|&a| sx * T::from_f32((a - offset) as f32)).collect()
+            },
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&Vector<C, T>> for &QuantizedMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Vector<R, T>;
+
+    /// Dense vectors take the integer path. The sparse flavors are cheaper
+    /// against the dequantized entries of their nonzeros alone.
+    fn mul(self, rhs: &Vector<C, T>) -> Self::Output {
+        if let Vector::Dense(x) = rhs {
+            return Vector::from_boxed_slice(self.mul_dense(&x.data));
+        }
+        let mut product = vec![T::zero(); R].into_boxed_slice();
+        for (c, x) in rhs.nonzeros() {
+            for (r, y) in product.iter_mut().enumerate() {
+                *y += self.entry(r, c) * x;
+            }
+        }
+        Vector::from_boxed_slice(product)
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for QuantizedMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = T;
+
+    /// The slow path: a reference needs somewhere to point, so the first
+    /// read dequantizes every entry into a cache as large as the full
+    /// precision matrix. `Matrix::get` reads one entry without it.
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(r < R && c < C);
+        let entries = self.entries.get_or_init(|| (0..R*C).map(|i| self.entry(i % R, i / R)).collect());
+        &entries[c*R + r]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::linalg::{Matrix, Vector};
+    use super::super::test_support::tall;
+
+    #[test]
+    fn quantization_stays_within_half_a_step() {
+        let m = tall().T();
+        for q in [m.quantize_rows(), m.quantize_cols()] {
+            assert!(matches!(q, Matrix::Quantized(_)));
+            // The widest line spans 6, so a step is at most 6/255.
+            for r in 0..3 {
+                for c in 0..4 {
+                    assert!((q[(r, c)] - m[(r, c)]).abs() <= 3. / 255. + 1e-6, "{q:?} at ({r}, {c})");
+                }
+            }
+            assert_eq!(q[(1, 0)], 0., "zero is exact");
+            assert!(matches!(q.T(), Matrix::Quantized(_)));
+            assert_eq!(q.T().T(), q);
+            assert!(matches!(&q * -2., Matrix::Quantized(_)));
+        }
+    }
+
+    #[test]
+    fn reads_by_value_leave_the_cache_empty() {
+        let m = tall().T();
+        let q = m.quantize_rows();
+        let cache_filled = |q: &Matrix<3, 4>| {
+            let Matrix::Quantized(q) = q else { unreachable!() };
+            q.entries.get().is_some()
+        };
+        assert!((q.get(2, 1) - m[(2, 1)]).abs() <= 3. / 255. + 1e-6);
+        let _ = (q.max(), q.argmin(), q.variance(), q.norm_frobenius());
+        let _ = (q.hadamard(&m), q.kron(&Matrix::<1, 1>::from_arr([2.])), q == m);
+        assert!(!cache_filled(&q));
+        assert_eq!(q[(2, 1)], q.get(2, 1));
+        assert!(cache_filled(&q));
+    }
+
+    #[test]
+    fn integer_matvec_tracks_f32() {
+        let m = tall().T();
+        let x = Vector::from_arr([1., -0.5, 2., 0.25]);
+        let expected = &m * &x;
+        for q in [m.quantize_rows(), m.quantize_cols()] {
+            let exact = &q.clone().densify() * &x;
+            let error = (&(&q * &x) - &expected).sum_of_squares().sqrt();
+            assert!(error < 0.05 * expected.sum_of_squares().sqrt(), "{q:?}");
+            assert!((&(&q * &x) - &exact).sum_of_squares().sqrt() < 0.05, "{q:?}");
+            assert_eq!(&q * &Vector::one_hot(2), q.col(2).to_vector());
+            assert_eq!(&q.T() * &Vector::<3>::zero(), Vector::zero());
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::linalg::Scalar;
+use crate::linalg::vector::{ConstantVector, Vector};
+
+use super::Order;
+use super::triangular::Triangle;
+use super::wrapper::Matrix;
+
+/// Reductions over all entries or along one axis. The structured flavors
+/// visit only their stored entries, and account for the implicit zeros in bulk.
+impl<const R: usize, const C: usize, T: Scalar> Matrix<R, C, T> where [(); R*C]: Sized {
+    pub fn row_sums(&self) -> Vector<R, T> {
+        self.axis_sums(Order::ROWS)
+    }
+
+    pub fn col_sums(&self) -> Vector<C, T> {
+        self.axis_sums(Order::COLS)
+    }
+
+    pub fn sum(&self) -> T {
+        use Matrix as M;
+        match self {
+            M::Constant(m) => T::from_usize(R*C) * m.0,
+            M::LowRank(m) => m.sum(),
+            M::Zero(_) => T::zero(),
+            _ => self.stored().map(|(_, _, x)| x).sum(),
+        }
+    }
+
+    pub fn mean(&self) -> T {
+        self.sum() / T::from_usize(R*C)
+    }
+
+    /// Population variance over all entries, i.e. divided by `R * C`.
+    pub fn variance(&self) -> T {
+        use Matrix as M;
+        let mean = self.mean();
+        match self {
+            M::Constant(_) | M::Zero(_) => T::zero(),
+            _ => {
+                let (mut count, mut sum) = (0, T::zero());
+                for (_, _, x) in self.stored() {
+                    count += 1;
+                    sum += (x - mean) * (x - mean);
+                }
+                (sum + T::from_usize(R*C - count) * mean * mean) / T::from_usize(R*C)
+            },
+        }
+    }
+
+    /// Square root of the sum of squared entries.
+    pub fn norm_frobenius(&self) -> T {
+        use Matrix as M;
+        match self {
+            M::Constant(m) => T::from_usize(R*C).sqrt() * m.0.abs(),
+            M::Zero(_) => T::zero(),
+            _ => self.stored().map(|(_, _, x)| x * x).sum::<T>().sqrt(),
+        }
+    }
+
+    /// The norm induced by the vector 1-norm, i.e. the largest absolute
+    /// column sum. The induced 2-norm is `spectral_norm`.
+    pub fn norm_l1(&self) -> T {
+        self.abs_axis_sums(Order::COLS).into_iter().fold(T::zero(), T::max)
+    }
+
+    /// The norm induced by the vector ∞-norm, i.e. the largest absolute row sum.
+    pub fn norm_inf(&self) -> T {
+        self.abs_axis_sums(Order::ROWS).into_iter().fold(T::zero(), T::max)
+    }
+
+    pub fn max(&self) -> T {
+        let (r, c) = self.argmax();
+        self.get(r, c)
+    }
+
+    pub fn min(&self) -> T {
+        let (r, c) = self.argmin();
+        self.get(r, c)
+    }
+
+    /// The first `(row, col)` in row-major order holding the largest entry.
+    pub fn argmax(&self) -> (usize, usize) {
+        self.arg_extremum(|a, b| a > b)
+    }
+
+    /// The first `(row, col)` in row-major order holding the smallest entry.
+    pub fn argmin(&self) -> (usize, usize) {
+        self.arg_extremum(|a, b| a < b)
+    }
+
+    /// Every `(row, col, value)` that `self` stores. Constant and zero
+    /// matrices store nothing and low-rank ones store factors, so for them
+    /// it is every entry instead.
+    pub(super) fn stored(&self) -> Box<dyn Iterator<Item = (usize, usize, T)> + '_> {
+        use Matrix as M;
+        match self {
+            M::BlockDiagonal(m) => Box::new(m.stored()),
+            M::Dense(m) => Box::new((0..R*C).map(move |i| match m.order {
+                Order::COLS => (i % R, i / R, m.data[i]),
+                Order::ROWS => (i / C, i % C, m.data[i]),
+            })),
+            M::Diagonal(m) => Box::new(m.diagonal_data.iter().enumerate().map(|(i, &d)| (i, i, d))),
+            M::Identity(m) => Box::new((0..R.min(C)).map(move |i| (i, i, m.1))),
+            M::LowerTriangular(m) => Box::new(m.stored(Triangle::Lower)),
+            M::Permutation(m) => Box::new(m.stored()),
+            M::Quantized(m) => Box::new(m.stored()),
+            M::Sparse(m) => Box::new(m.nonzeros()),
+            M::UpperTriangular(m) => Box::new(m.stored(Triangle::Upper)),
+            M::Constant(_) | M::LowRank(_) | M::Zero(_) => Box::new((0..R*C).map(move |i| (i % R, i / R, self.get(i % R, i / R)))),
+        }
+    }
+
+    fn axis_sums<const D: usize>(&self, order: Order) -> Vector<D, T> {
+        use Matrix as M;
+        let across = match order {
+            Order::ROWS => C,
+            Order::COLS => R,
+        };
+        match self {
+            M::Constant(m) => Vector::Constant(ConstantVector(T::from_usize(across) * m.0)),
+            M::Dense(m) => Vector::from_boxed_slice(match order {
+                Order::ROWS => m.row_sums(),
+                Order::COLS => m.col_sums(),
+            }.into_boxed_slice()),
+            M::Identity(m) if R == C => Vector::Constant(ConstantVector(m.1)),
+            M::Zero(_) => Vector::zero(),
+            _ => Vector::from_pairs(self.stored().map(|(r, c, x)| match order {
+                Order::ROWS => (r, x),
+                Order::COLS => (c, x),
+            })),
+        }
+    }
+
+    fn abs_axis_sums(&self, order: Order) -> Vec<T> {
+        let (lines, across) = match order {
+            Order::ROWS => (R, C),
+            Order::COLS => (C, R),
+        };
+        match self {
+            Matrix::Constant(m) => vec![T::from_usize(across) * m.0.abs(); lines],
+            _ => {
+                let mut sums = vec![T::zero(); lines];
+                for (r, c, x) in self.stored() {
+                    sums[if order == Order::ROWS { r } else { c }] += x.abs();
+                }
+                sums
+            },
+        }
+    }
+
+    /// The first position, in row-major order, whose entry no other
+    /// entry `wins` against.
+    fn arg_extremum(&self, wins: impl Fn(T, T) -> bool) -> (usize, usize) {
+        use Matrix as M;
+        assert!(R*C > 0, "empty matrix has no extremum");
+        if let M::Constant(_) | M::Zero(_) = self {
+            return (0, 0);
+        }
+
+        let mut count = 0;
+        let mut best: Option<((usize, usize), T)> = None;
+        for (r, c, x) in self.stored() {
+            count += 1;
+            if best.is_none_or(|(at, y)| wins(x, y) || (x == y && (r, c) < at)) {
+                best = Some(((r, c), x));
+            }
+        }
+        // All that matters about the implicit zeros is where the first one is.
+        let first_zero = || (0..R*C).map(|i| (i / C, i % C)).find(|&(r, c)| self.get(r, c) == T::zero()).unwrap();
+        let Some((at, x)) = best else {
+            return first_zero();
+        };
+        if count == R*C || wins(x, T::zero()) {
+            return at;
+        }
+        let zero = first_zero();
+        if wins(T::zero(), x) || zero < at { zero } else { at }
+    }
+}
@@ -1,5 +1,10 @@
 use std::fmt::Display;
-use std::ops::{Add, Mul, Neg, Sub};
+use std::marker::PhantomData;
+use std::ops::{Add, Index, Mul, Neg, Sub};
+
+use crate::linalg::Scalar;
+use crate::linalg::vector::{ConstantVector, DenseVector, OneHotVector, SparseVector, ZeroVector};
+use crate::linalg::vector::traits::{CanStackHorizontally, CanStackVertically};
 
 use super::Order;
 
@@ -9,293 +14,767 @@ use super::diagonal::DiagonalMatrix;
 use super::identity::IdentityMatrix;
 use super::zero::ZeroMatrix;
 
+/// Compressed sparse storage. With `Order::COLS` this is CSC: `offsets` has
+/// one slot per column plus one, and `indices[offsets[c]..offsets[c+1]]` are
+/// the (increasing) rows of the nonzero entries of column `c`. With
+/// `Order::ROWS` the same arrays are read as CSR, so a transpose only flips
+/// the order, just like for `DenseMatrix`.
 #[derive(Clone, Debug)]
-pub struct SparseMatrix<const R: usize, const C: usize>(
-    #[allow(dead_code)] pub(super) Vec<usize>,
-    #[allow(dead_code)] pub(super) Vec<f32>,
-    #[allow(dead_code)] pub(super) Order,
-);
+pub struct SparseMatrix<const R: usize, const C: usize, T: Scalar = f32> {
+    pub(super) offsets: Vec<usize>,
+    pub(super) indices: Vec<usize>,
+    pub(super) values: Vec<T>,
+    pub(super) order: Order,
+}
+
+impl<const R: usize, const C: usize, T: Scalar> SparseMatrix<R, C, T> {
+    // constructor
+    /// Builds a column-ordered sparse matrix out of `(row, col, value)`
+    /// triplets. Repeated positions are summed and explicit zeros dropped.
+    pub(crate) fn from_triplets(triplets: impl IntoIterator<Item = (usize, usize, T)>) -> Self {
+        let mut entries: Vec<(usize, usize, T)> = triplets.into_iter()
+            .inspect(|&(r, c, _)| assert!(r < R && c < C))
+            .collect();
+        // Stable, so that duplicates are summed in the order they were given.
+        entries.sort_by_key(|&(r, c, _)| (c, r));
+        Self::compress(Order::COLS, entries.into_iter().map(|(r, c, x)| (c, r, x)))
+    }
+
+    /// Packs `(major, minor, value)` entries that are already sorted by
+    /// `(major, minor)`, summing adjacent duplicates and dropping zeros.
+    fn compress(order: Order, sorted: impl Iterator<Item = (usize, usize, T)>) -> Self {
+        let majors = match order {
+            Order::COLS => C,
+            Order::ROWS => R,
+        };
+        let mut offsets = vec![0usize; majors + 1];
+        let mut indices: Vec<usize> = Vec::new();
+        let mut values: Vec<T> = Vec::new();
+        let mut last: Option<(usize, usize)> = None;
+        for (major, minor, x) in sorted {
+            if last == Some((major, minor)) {
+                *values.last_mut().unwrap() += x;
+            } else {
+                offsets[major + 1] += 1;
+                indices.push(minor);
+                values.push(x);
+                last = Some((major, minor));
+            }
+        }
+        for m in 0..majors {
+            offsets[m + 1] += offsets[m];
+        }
+
+        // Summing may have cancelled some entries out, squeeze them away.
+        if values.contains(&T::zero()) {
+            let (mut kept, mut from) = (0, 0);
+            for m in 0..majors {
+                let to = offsets[m + 1];
+                for i in from..to {
+                    if values[i] != T::zero() {
+                        indices[kept] = indices[i];
+                        values[kept] = values[i];
+                        kept += 1;
+                    }
+                }
+                from = to;
+                offsets[m + 1] = kept;
+            }
+            indices.truncate(kept);
+            values.truncate(kept);
+        }
+        SparseMatrix { offsets, indices, values, order }
+    }
 
-impl<const R: usize, const C: usize> SparseMatrix<R, C> {
-    pub(super) fn T(&self) -> SparseMatrix<C, R> {
-        todo!() //TODO
+    /// Iterates over `(major, minor, value)` in storage order.
+    fn entries(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        (0..self.offsets.len() - 1)
+            .flat_map(move |m| self.major(m).map(move |(minor, x)| (m, minor, x)))
+    }
+
+    /// The stored `(minor, value)` entries of a single column (CSC) or row (CSR).
+    pub(super) fn major(&self, m: usize) -> impl Iterator<Item = (usize, T)> + '_ {
+        let range = self.offsets[m]..self.offsets[m + 1];
+        self.indices[range.clone()].iter().copied().zip(self.values[range].iter().copied())
+    }
+
+    /// Iterates over `(row, col, value)` of the stored entries.
+    pub(super) fn nonzeros(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        let order = self.order;
+        self.entries().map(move |(major, minor, x)| match order {
+            Order::COLS => (minor, major, x),
+            Order::ROWS => (major, minor, x),
+        })
+    }
+
+    /// The entries of the `RN × CN` block at `(R0, C0)`, kept in the order of `self`.
+    pub(super) fn block<const R0: usize, const C0: usize, const RN: usize, const CN: usize>(&self) -> SparseMatrix<RN, CN, T> {
+        let ((m0, mn), (n0, nn)) = match self.order {
+            Order::COLS => ((C0, CN), (R0, RN)),
+            Order::ROWS => ((R0, RN), (C0, CN)),
+        };
+        SparseMatrix::compress(self.order, (0..mn).flat_map(|m| {
+            self.major(m0 + m)
+                .filter(move |&(minor, _)| (n0..n0 + nn).contains(&minor))
+                .map(move |(minor, x)| (m, minor - n0, x))
+        }))
+    }
+
+    /// The `(position, value)` entries of row `i` for `Order::ROWS`, or of
+    /// column `i` for `Order::COLS`. Across the storage order this takes a
+    /// binary search per major line.
+    pub(super) fn line(&self, order: Order, i: usize) -> Vec<(usize, T)> {
+        if order == self.order {
+            return self.major(i).collect();
+        }
+        (0..self.offsets.len() - 1).filter_map(|m| {
+            let (from, to) = (self.offsets[m], self.offsets[m + 1]);
+            let at = self.indices[from..to].binary_search(&i).ok()?;
+            Some((m, self.values[from + at]))
+        }).collect()
+    }
+
+    /// Stacks the major lines of `other` after those of `self`. Both must be
+    /// stored in the same order, so this is a concatenation of the arrays.
+    fn concat_majors<const R2: usize, const C2: usize, const R3: usize, const C3: usize>(
+        &self,
+        other: &SparseMatrix<R2, C2, T>,
+    ) -> SparseMatrix<R3, C3, T> {
+        debug_assert_eq!(self.order, other.order);
+        let nnz = self.nnz();
+        SparseMatrix {
+            offsets: self.offsets.iter().copied().chain(other.offsets[1..].iter().map(|o| o + nnz)).collect(),
+            indices: [&self.indices[..], &other.indices[..]].concat(),
+            values: [&self.values[..], &other.values[..]].concat(),
+            order: self.order,
+        }
+    }
+
+    /// Extends each major line of `self` by the same line of `other`, whose
+    /// minor indices are moved past the `shift` minors of `self`.
+    fn zip_majors<const R2: usize, const C2: usize, const R3: usize, const C3: usize>(
+        &self,
+        other: &SparseMatrix<R2, C2, T>,
+        shift: usize,
+    ) -> SparseMatrix<R3, C3, T> {
+        debug_assert_eq!(self.order, other.order);
+        let majors = self.offsets.len() - 1;
+        let mut offsets = Vec::with_capacity(majors + 1);
+        let mut indices = Vec::with_capacity(self.nnz() + other.nnz());
+        let mut values = Vec::with_capacity(self.nnz() + other.nnz());
+        offsets.push(0);
+        for m in 0..majors {
+            let theirs = other.major(m).map(|(minor, x)| (minor + shift, x));
+            for (minor, x) in self.major(m).chain(theirs) {
+                indices.push(minor);
+                values.push(x);
+            }
+            offsets.push(indices.len());
+        }
+        SparseMatrix { offsets, indices, values, order: self.order }
+    }
+
+    pub(super) fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Re-packs the entries so that they are stored in `order`.
+    /// A no-op clone if they already are.
+    pub(super) fn with_order(&self, order: Order) -> Self {
+        if self.order == order {
+            return self.clone();
+        }
+        let majors = match order {
+            Order::COLS => C,
+            Order::ROWS => R,
+        };
+        // Counting sort by the old minor index, which becomes the new major.
+        let mut offsets = vec![0usize; majors + 1];
+        for &minor in &self.indices {
+            offsets[minor + 1] += 1;
+        }
+        for m in 0..majors {
+            offsets[m + 1] += offsets[m];
+        }
+        let mut next = offsets.clone();
+        let mut indices = vec![0usize; self.nnz()];
+        let mut values = vec![T::zero(); self.nnz()];
+        for (major, minor, x) in self.entries() {
+            let slot = next[minor];
+            indices[slot] = major;
+            values[slot] = x;
+            next[minor] += 1;
+        }
+        SparseMatrix { offsets, indices, values, order }
+    }
+
+    fn map(&self, f: impl Fn(T) -> T) -> Self {
+        SparseMatrix {
+            offsets: self.offsets.clone(),
+            indices: self.indices.clone(),
+            values: self.values.iter().copied().map(f).collect(),
+            order: self.order,
+        }
+    }
+
+    pub(super) fn T(&self) -> SparseMatrix<C, R, T> {
+        SparseMatrix {
+            offsets: self.offsets.clone(),
+            indices: self.indices.clone(),
+            values: self.values.clone(),
+            order: -self.order,
+        }
     }
 }
 
-impl<const R: usize, const C: usize> PartialEq for SparseMatrix<R, C> where [(); R*C]: Sized {
-    fn eq(&self, _other: &Self) -> bool {
-        todo!() // TODO: handle transposed args.
+impl<const R: usize, const C: usize, T: Scalar> PartialEq for SparseMatrix<R, C, T> where [(); R*C]: Sized {
+    fn eq(&self, other: &Self) -> bool {
+        let other = other.with_order(self.order);
+        self.offsets == other.offsets && self.indices == other.indices && self.values == other.values
     }
 }
 
 ///////////////////////////////
-/// SPARSE MATRIX ADD IMPLS ///
+//  SPARSE MATRIX ADD IMPLS  //
 ///////////////////////////////
 
-impl<const R: usize, const C: usize> Add<&ConstantMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ConstantMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        self + rhs.0
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DenseMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DenseMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DiagonalMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DiagonalMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        let diagonal = rhs.diagonal_data.iter().enumerate()
+            .map(|(i, &x)| (i, i, x));
+        SparseMatrix::from_triplets(self.nonzeros().chain(diagonal))
     }
 }
 
-impl<const R: usize, const C: usize> Add<&IdentityMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&IdentityMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        let diagonal = (0..R.min(C)).map(|i| (i, i, rhs.1));
+        SparseMatrix::from_triplets(self.nonzeros().chain(diagonal))
     }
 }
 
-impl<const R: usize, const C: usize> Add<&SparseMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&SparseMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        SparseMatrix::from_triplets(self.nonzeros().chain(rhs.nonzeros()))
     }
 }
 
-impl<const R: usize, const C: usize> Add<&ZeroMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ZeroMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 ///////////////////////////////
-/// SPARSE MATRIX SUB IMPLS ///
+//  SPARSE MATRIX SUB IMPLS  //
 ///////////////////////////////
 
-impl<const R: usize, const C: usize> Sub<&ConstantMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ConstantMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        self - rhs.0
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DenseMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DenseMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        &(-rhs) + self
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DiagonalMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DiagonalMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        self + &(-rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&IdentityMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&IdentityMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        self + &(-rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&SparseMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&SparseMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        self + &(-rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&ZeroMatrix<R, C>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ZeroMatrix<R, C, T>> for &SparseMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 ///////////////////////////////
-/// SPARSE MATRIX MUL IMPLS ///
+//  SPARSE MATRIX MUL IMPLS  //
 ///////////////////////////////
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ConstantMatrix<C, C2>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ConstantMatrix<C, C2, T>> for &SparseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = SparseMatrix<R, C2>;
-
-    fn mul(self, _rhs: &ConstantMatrix<C, C2>) -> Self::Output {
-        todo!()
+    type Output = DenseMatrix<R, C2, T>;
+
+    #[allow(clippy::suspicious_arithmetic_impl)] // a product is a sum of products
+    fn mul(self, rhs: &ConstantMatrix<C, C2, T>) -> Self::Output {
+        let mut row_sums = [T::zero(); R];
+        for (r, _, x) in self.nonzeros() {
+            row_sums[r] += x;
+        }
+        DenseMatrix::from_fun(|r, _| row_sums[r] * rhs.0)
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DenseMatrix<C, C2>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DenseMatrix<C, C2, T>> for &SparseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = DenseMatrix<R, C2>;
-
-    fn mul(self, _rhs: &DenseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    type Output = DenseMatrix<R, C2, T>;
+
+    fn mul(self, rhs: &DenseMatrix<C, C2, T>) -> Self::Output {
+        // Each nonzero (r, k) adds a scaled row k of `rhs` to row r of the
+        // product, so the product is built row-major.
+        let mut data = vec![T::zero(); R*C2];
+        for (r, k, x) in self.nonzeros() {
+            let row = &mut data[r*C2..(r+1)*C2];
+            match rhs.order {
+                Order::ROWS => for (acc, &y) in row.iter_mut().zip(&rhs.data[k*C2..(k+1)*C2]) {
+                    *acc += x * y;
+                },
+                Order::COLS => for (c, acc) in row.iter_mut().enumerate() {
+                    *acc += x * rhs.data[c*C + k];
+                },
+            }
+        }
+        DenseMatrix {
+            data: data.into_boxed_slice(),
+            order: Order::ROWS,
+            size_marker: PhantomData,
+        }
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DiagonalMatrix<C, C2>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DiagonalMatrix<C, C2, T>> for &SparseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = SparseMatrix<R, C2>;
-
-    fn mul(self, _rhs: &DiagonalMatrix<C, C2>) -> Self::Output {
-        todo!()
+    type Output = SparseMatrix<R, C2, T>;
+
+    fn mul(self, rhs: &DiagonalMatrix<C, C2, T>) -> Self::Output {
+        // Right-multiplying by a diagonal matrix scales the columns of `self`.
+        let d = &rhs.diagonal_data;
+        SparseMatrix::from_triplets(self.nonzeros()
+            .filter(|&(_, c, _)| c < d.len())
+            .map(|(r, c, x)| (r, c, x * d[c])))
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&IdentityMatrix<C, C2>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&IdentityMatrix<C, C2, T>> for &SparseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = SparseMatrix<R, C2>;
-
-    fn mul(self, _rhs: &IdentityMatrix<C, C2>) -> Self::Output {
-        todo!()
+    type Output = SparseMatrix<R, C2, T>;
+
+    fn mul(self, _rhs: &IdentityMatrix<C, C2, T>) -> Self::Output {
+        debug_assert_eq!(C, C2);
+        SparseMatrix {
+            offsets: self.offsets.clone(),
+            indices: self.indices.clone(),
+            values: self.values.clone(),
+            order: self.order,
+        }
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&SparseMatrix<C, C2>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&SparseMatrix<C, C2, T>> for &SparseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = SparseMatrix<R, C2>;
-
-    fn mul(self, _rhs: &SparseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    type Output = SparseMatrix<R, C2, T>;
+
+    fn mul(self, rhs: &SparseMatrix<C, C2, T>) -> Self::Output {
+        // Gustavson's row-by-row product: both operands as CSR, every row of
+        // the product accumulated in a dense scratch row, then compressed.
+        let (lhs, rhs) = (self.with_order(Order::ROWS), rhs.with_order(Order::ROWS));
+        let mut scratch = vec![T::zero(); C2];
+        let mut touched = vec![false; C2];
+        let mut columns: Vec<usize> = Vec::new();
+
+        let mut offsets = Vec::with_capacity(R + 1);
+        let mut indices: Vec<usize> = Vec::new();
+        let mut values: Vec<T> = Vec::new();
+        offsets.push(0);
+        for r in 0..R {
+            for (k, x) in lhs.major(r) {
+                for (c, y) in rhs.major(k) {
+                    if !touched[c] {
+                        touched[c] = true;
+                        columns.push(c);
+                    }
+                    scratch[c] += x * y;
+                }
+            }
+            columns.sort_unstable();
+            for c in columns.drain(..) {
+                if scratch[c] != T::zero() {
+                    indices.push(c);
+                    values.push(scratch[c]);
+                }
+                scratch[c] = T::zero();
+                touched[c] = false;
+            }
+            offsets.push(indices.len());
+        }
+        SparseMatrix { offsets, indices, values, order: Order::ROWS }
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ZeroMatrix<C, C2>> for &SparseMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ZeroMatrix<C, C2, T>> for &SparseMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &ZeroMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &ZeroMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-////////////////////////////////
-/// SPARSE MATRIX MATH IMPLS ///
-////////////////////////////////
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ConstantVector<C, T>> for &SparseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = DenseVector<R, T>;
+
+    #[allow(clippy::suspicious_arithmetic_impl)] // a product is a sum of products
+    fn mul(self, rhs: &ConstantVector<C, T>) -> Self::Output {
+        let mut row_sums = [T::zero(); R];
+        for (r, _, x) in self.nonzeros() {
+            row_sums[r] += x;
+        }
+        DenseVector::from_fun(|r| row_sums[r] * rhs.0)
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&DenseVector<C, T>> for &SparseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = DenseVector<R, T>;
+
+    fn mul(self, rhs: &DenseVector<C, T>) -> Self::Output {
+        let mut container = vec![T::zero(); R];
+        for (r, c, x) in self.nonzeros() {
+            container[r] += x * rhs.data[c];
+        }
+        DenseVector::from_boxed_slice(container.into_boxed_slice())
+    }
+}
 
-impl<const R: usize, const C: usize> Add<&SparseMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<&OneHotVector<C, T>> for &SparseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = SparseVector<R, T>;
+
+    fn mul(self, rhs: &OneHotVector<C, T>) -> Self::Output {
+        match self.order {
+            Order::COLS => SparseVector::from_pairs(self.major(rhs.index)
+                .map(|(r, x)| (r, x * rhs.one))),
+            Order::ROWS => SparseVector::from_pairs((0..R)
+                .map(|r| (r, self[(r, rhs.index)] * rhs.one))),
+        }
+    }
+}
 
-    fn add(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+impl<const R: usize, const C: usize, T: Scalar> Mul<&SparseVector<C, T>> for &SparseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = SparseVector<R, T>;
+
+    fn mul(self, rhs: &SparseVector<C, T>) -> Self::Output {
+        SparseVector::from_pairs(self.nonzeros()
+            .filter_map(|(r, c, x)| rhs.elems.get(&c).map(|&y| (r, x * y))))
     }
 }
 
-impl<const R: usize, const C: usize> Add<f32> for &SparseMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ZeroVector<C, T>> for &SparseMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ZeroVector<R, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &ZeroVector<C, T>) -> Self::Output {
+        ZeroVector(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize> Sub<f32> for &SparseMatrix<R, C> where [(); R*C]: Sized {
-    type Output = DenseMatrix<R, C>;
+////////////////////////////////
+//  SPARSE MATRIX MATH IMPLS  //
+////////////////////////////////
+
+impl<const R: usize, const C: usize, T: Scalar> Add<T> for &SparseMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn add(self, rhs: T) -> Self::Output {
+        let mut out = DenseMatrix::from_fun(|_, _| rhs);
+        out += self;
+        out
     }
 }
 
-impl<const R: usize, const C: usize> Mul<&SparseMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = SparseMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Sub<T> for &SparseMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = DenseMatrix<R, C, T>;
 
-    fn mul(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        self + (-rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Mul<f32> for &SparseMatrix<R, C> where [(); R*C]: Sized {
-    type Output = SparseMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<T> for &SparseMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = SparseMatrix<R, C, T>;
 
-    fn mul(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: T) -> Self::Output {
+        self.map(|x| x * rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Neg for &SparseMatrix<R, C> where [(); R*C]: Sized {
-    type Output = SparseMatrix<R, C>;
+scalar_lhs_ops!(SparseMatrix, add: DenseMatrix, mul: SparseMatrix);
+
+impl<const R: usize, const C: usize, T: Scalar> Neg for &SparseMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = SparseMatrix<R, C, T>;
 
     fn neg(self) -> Self::Output {
-        todo!()
+        self.map(|x| -x)
+    }
+}
+
+/////////////////////////////////
+//  SPARSE MATRIX STACK IMPLS  //
+/////////////////////////////////
+
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> CanStackHorizontally<&SparseMatrix<R, C2, T>> for SparseMatrix<R, C, T>
+    where [(); C+C2]: Sized
+{
+    type Output = SparseMatrix<R, {C+C2}, T>;
+
+    /// Keeps the order of `self`, re-packing `other` first if it differs.
+    fn hstack(&self, other: &SparseMatrix<R, C2, T>) -> Self::Output {
+        let other = other.with_order(self.order);
+        match self.order {
+            Order::COLS => self.concat_majors(&other),
+            Order::ROWS => self.zip_majors(&other, C),
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, const R2: usize, T: Scalar> CanStackVertically<&SparseMatrix<R2, C, T>> for SparseMatrix<R, C, T>
+    where [(); R+R2]: Sized
+{
+    type Output = SparseMatrix<{R+R2}, C, T>;
+
+    /// Keeps the order of `self`, re-packing `other` first if it differs.
+    fn vstack(&self, other: &SparseMatrix<R2, C, T>) -> Self::Output {
+        let other = other.with_order(self.order);
+        match self.order {
+            Order::COLS => self.zip_majors(&other, R),
+            Order::ROWS => self.concat_majors(&other),
+        }
     }
 }
 
 ///////////////////////////////////
-/// SPARSE MATRIX UTILITY IMPLS ///
+//  SPARSE MATRIX UTILITY IMPLS  //
 ///////////////////////////////////
 
-impl<const D: usize> Display for SparseMatrix<D, D> where [(); D*D]: Sized {
-    /// Displays the rows of an identity matrix.
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+impl<const D: usize, T: Scalar> Display for SparseMatrix<D, D, T> where [(); D*D]: Sized {
+    /// Displays the rows of a sparse matrix.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}]", (0..D)
+            .map(|r| (0..D)
+                .map(|c| self[(r, c)].to_string())
+                .collect::<Vec<_>>()
+                .join(","))
+            .collect::<Vec<_>>()
+            .join("],["))
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for SparseMatrix<R, C, T> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(r < R && c < C);
+        let (major, minor) = match self.order {
+            Order::COLS => (c, r),
+            Order::ROWS => (r, c),
+        };
+        let (from, to) = (self.offsets[major], self.offsets[major + 1]);
+        match self.indices[from..to].binary_search(&minor) {
+            Ok(i) => &self.values[from + i],
+            Err(_) => T::ZERO_REF,
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> From<&DenseMatrix<R, C, T>> for SparseMatrix<R, C, T> where [(); R*C]: Sized {
+    /// Keeps the storage order of `dense`, so this is a single pass over its data.
+    fn from(dense: &DenseMatrix<R, C, T>) -> Self {
+        let minors = match dense.order {
+            Order::COLS => R,
+            Order::ROWS => C,
+        };
+        let entries = dense.data.iter().enumerate()
+            .filter(|&(_, &x)| x != T::zero())
+            .map(|(i, &x)| (i / minors, i % minors, x));
+        SparseMatrix::compress(dense.order, entries)
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> From<&SparseMatrix<R, C, T>> for DenseMatrix<R, C, T> where [(); R*C]: Sized {
+    fn from(sparse: &SparseMatrix<R, C, T>) -> Self {
+        let minors = match sparse.order {
+            Order::COLS => R,
+            Order::ROWS => C,
+        };
+        let mut data = vec![T::zero(); R*C];
+        for (major, minor, x) in sparse.entries() {
+            data[major * minors + minor] = x;
+        }
+        DenseMatrix {
+            data: data.into_boxed_slice(),
+            order: sparse.order,
+            size_marker: PhantomData,
+        }
+    }
+}
+
+mod tests {
+    #[test]
+    fn sparse_matrix_from_triplets() {
+        use super::{DenseMatrix, SparseMatrix};
+
+        // Duplicates are summed, cancelling entries vanish.
+        let m = SparseMatrix::<2, 3>::from_triplets([
+            (1, 2, 4.),
+            (0, 0, 1.),
+            (1, 2, 1.),
+            (0, 1, 3.),
+            (0, 1, -3.),
+        ]);
+
+        let expected = DenseMatrix::from_cols(&[
+            [1., 0.],
+            [0., 0.],
+            [0., 5.],
+        ]);
+
+        assert_eq!(m.nnz(), 2);
+        assert_eq!(m.offsets, vec![0, 1, 1, 2]);
+        assert_eq!(DenseMatrix::from(&m), expected);
+        assert_eq!(SparseMatrix::from(&expected), m);
+    }
+
+    #[test]
+    fn sparse_matrix_transpose_is_reinterpretation() {
+        use super::{DenseMatrix, Order, SparseMatrix};
+
+        let m = SparseMatrix::<2, 3>::from_triplets([(0, 0, 1.), (0, 2, 2.), (1, 1, 3.)]);
+        let t = m.T();
+
+        assert_eq!(t.values, m.values);
+        assert_eq!(t.order, Order::ROWS);
+        assert_eq!(DenseMatrix::from(&t), DenseMatrix::from(&m).T());
+        assert_eq!(t.with_order(Order::COLS), t);
+        assert_eq!(t.T(), m);
+    }
+
+    #[test]
+    fn sparse_matrix_multiply() {
+        use super::{DenseMatrix, SparseMatrix};
+
+        let m1 = SparseMatrix::<2, 3>::from_triplets([(0, 0, 1.), (0, 2, 2.), (1, 1, 3.)]);
+        let m2 = SparseMatrix::<3, 2>::from_triplets([(0, 1, 4.), (1, 0, 5.), (2, 1, -2.)]);
+        let (d1, d2) = (DenseMatrix::from(&m1), DenseMatrix::from(&m2));
+
+        // Row 0 cancels out entirely: 1*4 + 2*(-2).
+        let expected = DenseMatrix::from_cols(&[
+            [0., 15.],
+            [0., 0.],
+        ]);
+
+        assert_eq!(DenseMatrix::from(&(&m1 * &m2)), expected);
+        assert_eq!((&m1 * &m2).nnz(), 1);
+        assert_eq!(&m1 * &d2, expected);
+        assert_eq!(&d1 * &m2, expected);
+        assert_eq!(&m2.T() * &m1.T(), SparseMatrix::from(&expected.T()));
+        assert_eq!(&m1.T() * &d1, &d1.T() * &d1);
     }
 }
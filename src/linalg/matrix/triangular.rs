@@ -0,0 +1,226 @@
+use std::ops::{Index, Range};
+
+use crate::linalg::{kernels, LinalgError, Scalar};
+use crate::linalg::vector::Vector;
+
+use super::Order;
+use super::dense::DenseMatrix;
+
+/// Which side of the main diagonal a triangular matrix keeps. The diagonal
+/// itself belongs to both.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Triangle {
+    Upper,
+    Lower,
+}
+
+impl Triangle {
+    pub(super) fn contains(self, r: usize, c: usize) -> bool {
+        match self {
+            Triangle::Upper => r <= c,
+            Triangle::Lower => r >= c,
+        }
+    }
+
+    /// The positions along line `i` of the given order that lie in the
+    /// triangle, for lines of length `len`.
+    fn span(self, order: Order, i: usize, len: usize) -> Range<usize> {
+        match (self, order) {
+            (Triangle::Upper, Order::COLS) | (Triangle::Lower, Order::ROWS) => 0..(i + 1).min(len),
+            (Triangle::Lower, Order::COLS) | (Triangle::Upper, Order::ROWS) => i.min(len)..len,
+        }
+    }
+}
+
+/// Dense storage with the entries outside the triangle held at zero. Which
+/// triangle that is comes from the `Matrix` variant, so the same struct
+/// serves both, and a transpose only has to flip the variant along with the
+/// storage order.
+#[derive(Clone, Debug)]
+pub struct TriangularMatrix<const R: usize, const C: usize, T: Scalar = f32>(
+    pub(super) DenseMatrix<R, C, T>,
+) where [(); R*C]: Sized;
+
+impl<const R: usize, const C: usize, T: Scalar> TriangularMatrix<R, C, T> where [(); R*C]: Sized {
+    // constructor
+    pub(super) fn from_fun(side: Triangle, f: impl Fn(usize, usize) -> T) -> Self {
+        TriangularMatrix(DenseMatrix::from_fun(|r, c| if side.contains(r, c) { f(r, c) } else { T::zero() }))
+    }
+
+    pub(super) fn T(&self) -> TriangularMatrix<C, R, T> where [(); C*R]: Sized {
+        TriangularMatrix(self.0.T())
+    }
+
+    /// `diag(rows) * self * diag(cols)`, which is triangular on the same
+    /// side. Products with diagonal and identity matrices go through here.
+    pub(super) fn scale<const R2: usize, const C2: usize>(&self, rows: impl Fn(usize) -> T, cols: impl Fn(usize) -> T) -> TriangularMatrix<R2, C2, T>
+        where [(); R2*C2]: Sized
+    {
+        TriangularMatrix(DenseMatrix::from_fun(|r, c| rows(r) * self.0[(r, c)] * cols(c)))
+    }
+
+    /// `self + diag(f)`.
+    pub(super) fn add_diagonal(&self, f: impl Fn(usize) -> T) -> Self {
+        let mut sum = self.clone();
+        for i in 0..R.min(C) {
+            sum.0[(i, i)] += f(i);
+        }
+        sum
+    }
+
+    /// Every `(row, col, value)` inside the triangle.
+    pub(super) fn stored(&self, side: Triangle) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        (0..C).flat_map(move |c| side.span(Order::COLS, c, R).map(move |r| (r, c, self.0[(r, c)])))
+    }
+
+    /// `self * x`, visiting only the triangle. With a column-major `self`
+    /// every nonzero of `x` is an axpy over part of a column, with a
+    /// row-major one every row is a dot product over part of `x`.
+    pub(super) fn mul_vec(&self, side: Triangle, x: &Vector<C, T>) -> Vector<R, T> {
+        let m = &self.0;
+        let mut product = vec![T::zero(); R].into_boxed_slice();
+        match m.order {
+            Order::COLS => for (c, y) in x.nonzeros() {
+                let rows = side.span(Order::COLS, c, R);
+                kernels::axpy(&mut product[rows.clone()], y, &m.data[c*R + rows.start..c*R + rows.end]);
+            },
+            Order::ROWS => {
+                let dense;
+                let x = match x {
+                    Vector::Dense(x) => &x.data,
+                    _ => {
+                        dense = x.clone().into_dense();
+                        &dense.data
+                    },
+                };
+                for (r, acc) in product.iter_mut().enumerate() {
+                    let cols = side.span(Order::ROWS, r, C);
+                    *acc = kernels::dot(&m.data[r*C + cols.start..r*C + cols.end], &x[cols]);
+                }
+            },
+        }
+        Vector::from_boxed_slice(product)
+    }
+}
+
+impl<const D: usize, T: Scalar> TriangularMatrix<D, D, T> where [(); D*D]: Sized {
+    pub(super) fn det(&self) -> T {
+        (0..D).map(|i| self.0[(i, i)]).fold(T::one(), |acc, x| acc * x)
+    }
+
+    /// Overwrites `x` with the solution of `self * y = x`, by forward
+    /// substitution for a lower triangle and back substitution for an upper
+    /// one. Like `mul_vec`, this runs over columns or rows, whichever are
+    /// contiguous.
+    pub(super) fn substitute(&self, side: Triangle, x: &mut [T]) -> Result<(), LinalgError> {
+        let m = &self.0;
+        let ks: Box<dyn Iterator<Item = usize>> = match side {
+            Triangle::Lower => Box::new(0..D),
+            Triangle::Upper => Box::new((0..D).rev()),
+        };
+        for k in ks {
+            let pivot = m[(k, k)];
+            if pivot == T::zero() {
+                return Err(LinalgError::Singular { column: k });
+            }
+            // The rest of line `k` outside the diagonal.
+            let rest = match side {
+                Triangle::Lower => k + 1..D,
+                Triangle::Upper => 0..k,
+            };
+            match m.order {
+                Order::COLS => {
+                    x[k] /= pivot;
+                    let y = x[k];
+                    if y != T::zero() {
+                        kernels::axpy(&mut x[rest.clone()], -y, &m.data[k*D + rest.start..k*D + rest.end]);
+                    }
+                },
+                Order::ROWS => {
+                    let solved = match side {
+                        Triangle::Lower => 0..k,
+                        Triangle::Upper => k + 1..D,
+                    };
+                    let known = kernels::dot(&m.data[k*D + solved.start..k*D + solved.end], &x[solved]);
+                    x[k] = (x[k] - known) / pivot;
+                },
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn solve(&self, side: Triangle, b: &Vector<D, T>) -> Result<Vector<D, T>, LinalgError> {
+        let mut x = b.clone().into_dense().data;
+        self.substitute(side, &mut x)?;
+        Ok(Vector::from_boxed_slice(x))
+    }
+
+    /// Solves against every column of the identity. The inverse of a
+    /// triangular matrix is triangular on the same side.
+    pub(super) fn inverse(&self, side: Triangle) -> Result<Self, LinalgError> {
+        let mut inv = vec![T::zero(); D*D].into_boxed_slice();
+        for (j, col) in inv.chunks_exact_mut(D).enumerate() {
+            col[j] = T::one();
+            self.substitute(side, col)?;
+        }
+        Ok(TriangularMatrix(DenseMatrix::from_boxed_slice(inv)))
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for TriangularMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::linalg::{LinalgError, Matrix, Vector};
+    use super::super::test_support::square;
+
+    #[test]
+    fn triangles_keep_their_side() {
+        let (upper, lower) = (square().upper_triangle(), square().lower_triangle());
+        assert!(matches!(upper, Matrix::UpperTriangular(_)));
+        assert!(matches!(upper.T(), Matrix::LowerTriangular(_)));
+        assert!(matches!(&upper * &upper, Matrix::UpperTriangular(_)));
+        assert!(matches!(&lower - &Matrix::I(), Matrix::LowerTriangular(_)));
+        assert!(matches!(&upper * &lower, Matrix::Dense(_)));
+        assert!(matches!(Matrix::<3, 3>::I().upper_triangle(), Matrix::Identity(_)));
+        assert_eq!(&upper + &lower, &square() + &Matrix::diag(Vector::from_arr([1., 3., 4.])));
+    }
+
+    #[test]
+    fn matvec_matches_dense_in_both_orders() {
+        let x = Vector::from_arr([1., -2., 0.5]);
+        for m in [square().upper_triangle(), square().lower_triangle()] {
+            for m in [m.clone(), m.T().T(), m.T()] {
+                let dense = m.clone().densify();
+                assert_eq!(&m * &x, &dense * &x, "{m:?}");
+                assert_eq!(&m * &Vector::one_hot(1), dense.col(1).to_vector(), "{m:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn substitution_solves() {
+        let b = Vector::from_arr([3., -1., 2.]);
+        for m in [square().upper_triangle(), square().lower_triangle()] {
+            for m in [m.clone(), m.T()] {
+                let x = m.solve(&b).unwrap();
+                let residual = &(&m * &x) - &b;
+                assert!(residual.sum_of_squares() < 1e-10, "{m:?}");
+                assert!((m.det() - m.clone().densify().det()).abs() < 1e-4, "{m:?}");
+
+                let inv = m.inverse().unwrap();
+                assert_eq!(std::mem::discriminant(&inv), std::mem::discriminant(&m));
+                assert!((&(&m * &inv) - &Matrix::I()).norm_frobenius() < 1e-5, "{m:?}");
+            }
+        }
+
+        let singular = Matrix::<2, 2>::from_cols(&[[1., 2.], [0., 0.]]).lower_triangle();
+        assert_eq!(singular.solve(&Vector::from_arr([1., 1.])).unwrap_err(), LinalgError::Singular { column: 1 });
+    }
+}
@@ -0,0 +1,264 @@
+use std::marker::PhantomData;
+use std::ops::{Index, Mul};
+
+use crate::linalg::{kernels, Scalar};
+use crate::linalg::vector::{ConstantVector, DenseVectorView, OneHotVector, Vector, VectorView};
+
+use super::Order;
+use super::constant::ConstantMatrix;
+use super::dense::DenseMatrix;
+use super::diagonal::DiagonalMatrix;
+use super::identity::IdentityMatrix;
+use super::sparse::SparseMatrix;
+use super::triangular::TriangularMatrix;
+use super::wrapper::Matrix;
+use super::zero::ZeroMatrix;
+
+/// An `R × C` block read in place from the storage of a dense matrix: one
+/// contiguous run per major line of `order`, the runs `stride` apart and
+/// the first at `offset`. The same layout `gemm::Strided` reads, cut down
+/// to a block.
+#[derive(Clone, Copy, Debug)]
+pub struct DenseMatrixView<'a, const R: usize, const C: usize, T: Scalar = f32> where [(); R*C]: Sized {
+    pub(super) data: &'a [T],
+    pub(super) offset: usize,
+    pub(super) stride: usize,
+    pub(super) order: Order,
+    size_marker: PhantomData<[T; R*C]>,
+}
+
+impl<'a, const R: usize, const C: usize, T: Scalar> DenseMatrixView<'a, R, C, T> where [(); R*C]: Sized {
+    pub(super) fn new(data: &'a [T], offset: usize, stride: usize, order: Order) -> Self {
+        Self { data, offset, stride, order, size_marker: PhantomData }
+    }
+
+    fn at(&self, r: usize, c: usize) -> usize {
+        match self.order {
+            Order::COLS => self.offset + c*self.stride + r,
+            Order::ROWS => self.offset + r*self.stride + c,
+        }
+    }
+
+    /// Column `i` for `Order::COLS` storage, row `i` for `Order::ROWS`.
+    fn major(&self, i: usize) -> &'a [T] {
+        let len = match self.order {
+            Order::COLS => R,
+            Order::ROWS => C,
+        };
+        &self.data[self.offset + i*self.stride..][..len]
+    }
+
+    /// Row `i` for `Order::ROWS`, column `i` for `Order::COLS`, of length `D`,
+    /// borrowed along with the block.
+    fn line<const D: usize>(&self, order: Order, i: usize) -> DenseVectorView<'a, D, T> {
+        let (offset, stride) = match (order, self.order) {
+            (Order::ROWS, Order::ROWS) | (Order::COLS, Order::COLS) => (self.offset + i*self.stride, 1),
+            (Order::ROWS, Order::COLS) | (Order::COLS, Order::ROWS) => (self.offset + i, self.stride),
+        };
+        DenseVectorView::new(self.data, offset, stride)
+    }
+
+    /// Copies the block out, one contiguous run per major line in the order
+    /// of the storage it borrows.
+    pub(super) fn to_dense(self) -> DenseMatrix<R, C, T> {
+        let majors = match self.order {
+            Order::COLS => C,
+            Order::ROWS => R,
+        };
+        let data = (0..majors).flat_map(|i| self.major(i).iter().copied()).collect();
+        DenseMatrix { data, order: self.order, size_marker: PhantomData }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for DenseMatrixView<'_, R, C, T> where [(); R*C]: Sized {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(r < R && c < C);
+        &self.data[self.at(r, c)]
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&Vector<C, T>> for &DenseMatrixView<'_, R, C, T> where [(); R*C]: Sized {
+    type Output = Vector<R, T>;
+
+    fn mul(self, rhs: &Vector<C, T>) -> Self::Output {
+        let mut product = vec![T::zero(); R].into_boxed_slice();
+        match (self.order, rhs) {
+            // Rows are contiguous, so every entry is a single dot product.
+            (Order::ROWS, Vector::Dense(x)) => for (r, acc) in product.iter_mut().enumerate() {
+                *acc = kernels::dot(self.major(r), &x.data);
+            },
+            (Order::ROWS, _) => for (c, x) in rhs.nonzeros() {
+                for (r, acc) in product.iter_mut().enumerate() {
+                    *acc += self.major(r)[c] * x;
+                }
+            },
+            // Accumulate scaled columns, which are contiguous in storage.
+            (Order::COLS, _) => for (c, x) in rhs.nonzeros() {
+                kernels::axpy(&mut product, x, self.major(c));
+            },
+        }
+        Vector::from_boxed_slice(product)
+    }
+}
+
+/// What `Matrix::block` returns. Blocks of dense storage, which includes
+/// triangular matrices cut off their diagonal, are borrowed rather than
+/// copied. The other flavors are cheap to cut and come out as a matrix, in
+/// the flavor of `self` wherever it can be kept.
+#[derive(Clone, Debug)]
+pub enum MatrixView<'a, const R: usize, const C: usize, T: Scalar = f32> where [(); R*C]: Sized {
+    Borrowed(DenseMatrixView<'a, R, C, T>),
+    Owned(Matrix<R, C, T>),
+}
+
+impl<const R: usize, const C: usize, T: Scalar> MatrixView<'_, R, C, T> where [(); R*C]: Sized {
+    /// Entry `(r, c)` by value; see `Matrix::get`.
+    pub fn get(&self, r: usize, c: usize) -> T {
+        match self {
+            MatrixView::Borrowed(m) => m[(r, c)],
+            MatrixView::Owned(m) => m.get(r, c),
+        }
+    }
+
+    /// Copies the block out into a matrix of its own.
+    pub fn to_matrix(&self) -> Matrix<R, C, T> {
+        match self {
+            MatrixView::Borrowed(m) => Matrix::Dense(m.to_dense()),
+            MatrixView::Owned(m) => m.clone(),
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for MatrixView<'_, R, C, T> where [(); R*C]: Sized {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        match self {
+            MatrixView::Borrowed(m) => &m[index],
+            MatrixView::Owned(m) => &m[index],
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&Vector<C, T>> for &MatrixView<'_, R, C, T> where [(); R*C]: Sized {
+    type Output = Vector<R, T>;
+
+    fn mul(self, rhs: &Vector<C, T>) -> Self::Output {
+        match self {
+            MatrixView::Borrowed(m) => m * rhs,
+            MatrixView::Owned(m) => m * rhs,
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Matrix<R, C, T> where [(); R*C]: Sized {
+    /// The `RN × CN` block whose top left corner is at `(R0, C0)`. A block
+    /// reaching past the edge of `self` does not compile.
+    ///
+    /// Dense storage is borrowed in place, so cutting a block of a dense
+    /// matrix copies nothing. See `block_owned` for the other flavors.
+    pub fn block<const R0: usize, const C0: usize, const RN: usize, const CN: usize>(&self) -> MatrixView<'_, RN, CN, T>
+        where
+            [(); RN*CN]: Sized,
+            [(); R - (R0 + RN)]: Sized,
+            [(); C - (C0 + CN)]: Sized,
+    {
+        use Matrix as M;
+        let centered = R0 == C0 && RN == CN;
+        match self {
+            M::Dense(m) => MatrixView::Borrowed(m.view::<R0, C0, RN, CN>()),
+            M::LowerTriangular(m) | M::UpperTriangular(m) if !centered => MatrixView::Borrowed(m.0.view::<R0, C0, RN, CN>()),
+            _ => MatrixView::Owned(self.block_owned::<R0, C0, RN, CN>()),
+        }
+    }
+
+    /// Copies out the `RN × CN` block at `(R0, C0)`.
+    ///
+    /// The flavor is kept wherever it can be, so blocks of constant, zero and
+    /// identity matrices cost nothing. Stored flavors copy just the block,
+    /// one contiguous run per major line in the storage order of `self`.
+    pub fn block_owned<const R0: usize, const C0: usize, const RN: usize, const CN: usize>(&self) -> Matrix<RN, CN, T>
+        where
+            [(); RN*CN]: Sized,
+            [(); R - (R0 + RN)]: Sized,
+            [(); C - (C0 + CN)]: Sized,
+    {
+        use Matrix as M;
+        let centered = R0 == C0 && RN == CN;
+        match self {
+            M::BlockDiagonal(_) | M::Permutation(_) => M::Sparse(self.to_sparse().block::<R0, C0, RN, CN>()),
+            M::Constant(m) => M::Constant(ConstantMatrix(m.0)),
+            M::Dense(m) => M::Dense(m.view::<R0, C0, RN, CN>().to_dense()),
+            M::Diagonal(m) if centered => M::Diagonal(DiagonalMatrix::from_fun(|i| m.diagonal_data[R0 + i])),
+            M::Identity(m) if centered => M::Identity(IdentityMatrix(m.0, m.1)),
+            M::Diagonal(_) | M::Identity(_) => M::Sparse(SparseMatrix::from_triplets(
+                (R0.max(C0)..(R0 + RN).min(C0 + CN)).map(|k| (k - R0, k - C0, self[(k, k)]))
+            )),
+            M::LowerTriangular(m) if centered => M::LowerTriangular(TriangularMatrix(m.0.view::<R0, C0, RN, CN>().to_dense())),
+            M::UpperTriangular(m) if centered => M::UpperTriangular(TriangularMatrix(m.0.view::<R0, C0, RN, CN>().to_dense())),
+            M::LowerTriangular(m) | M::UpperTriangular(m) => M::Dense(m.0.view::<R0, C0, RN, CN>().to_dense()),
+            M::LowRank(m) => M::LowRank(m.block::<R0, C0, RN, CN>()),
+            M::Quantized(m) => M::Dense(m.to_dense().view::<R0, C0, RN, CN>().to_dense()),
+            M::Sparse(m) => M::Sparse(m.block::<R0, C0, RN, CN>()),
+            M::Zero(_) => M::Zero(ZeroMatrix(T::zero())),
+        }
+    }
+
+    /// Row `i`, borrowed in place from dense storage.
+    pub fn row(&self, i: usize) -> VectorView<'_, C, T> {
+        assert!(i < R);
+        self.line_view(Order::ROWS, i)
+    }
+
+    /// Column `j`, borrowed in place from dense storage.
+    pub fn col(&self, j: usize) -> VectorView<'_, R, T> {
+        assert!(j < C);
+        self.line_view(Order::COLS, j)
+    }
+
+    /// Copies out row `i`, in whichever vector flavor matches the flavor of `self`.
+    pub fn row_owned(&self, i: usize) -> Vector<C, T> {
+        assert!(i < R);
+        self.line(Order::ROWS, i)
+    }
+
+    /// Copies out column `j`, in whichever vector flavor matches the flavor of `self`.
+    pub fn col_owned(&self, j: usize) -> Vector<R, T> {
+        assert!(j < C);
+        self.line(Order::COLS, j)
+    }
+
+    fn line_view<const D: usize>(&self, order: Order, i: usize) -> VectorView<'_, D, T> {
+        use Matrix as M;
+        match self {
+            M::Dense(m) | M::LowerTriangular(TriangularMatrix(m)) | M::UpperTriangular(TriangularMatrix(m)) => {
+                VectorView::Borrowed(m.view::<0, 0, R, C>().line(order, i))
+            },
+            _ => VectorView::Owned(self.line(order, i)),
+        }
+    }
+
+    /// Row `i` for `Order::ROWS`, column `i` for `Order::COLS`, of length `D`.
+    pub(super) fn line<const D: usize>(&self, order: Order, i: usize) -> Vector<D, T> {
+        use Matrix as M;
+        use Vector as V;
+        match self {
+            M::BlockDiagonal(m) => V::from_pairs(m.line(order, i)),
+            M::Constant(m) => V::Constant(ConstantVector(m.0)),
+            M::Dense(m) => V::from_boxed_slice(m.line(order, i)),
+            M::Diagonal(m) => match m.diagonal_data.get(i) {
+                Some(&d) if d != T::zero() => V::from_pairs([(i, d)]),
+                _ => V::zero(),
+            },
+            M::Identity(m) if i < D => V::OneHot(OneHotVector { zero: T::zero(), one: m.1, index: i }),
+            M::Identity(_) | M::Zero(_) => V::zero(),
+            M::LowerTriangular(m) | M::UpperTriangular(m) => V::from_boxed_slice(m.0.line(order, i)),
+            M::LowRank(m) => V::from_boxed_slice(m.line(order, i)),
+            M::Permutation(m) => V::OneHot(OneHotVector { zero: T::zero(), one: m.one, index: m.line_index(order, i) }),
+            M::Quantized(m) => V::from_boxed_slice(m.line(order, i)),
+            M::Sparse(m) => V::from_pairs(m.line(order, i)),
+        }
+    }
+}
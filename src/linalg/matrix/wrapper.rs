@@ -1,112 +1,504 @@
 use std::marker::PhantomData;
-use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
 
-use crate::linalg::vector::Vector;
+use crate::linalg::{density, kernels, parallel, Scalar};
+use crate::linalg::vector::{ConstantVector, Vector};
+use crate::linalg::vector::traits::{CanStackHorizontally, CanStackVertically};
 
 use super::Order;
+use super::blockdiag::{multiply_blocks, BlockDiagonalMatrix};
 use super::constant::ConstantMatrix;
 use super::dense::DenseMatrix;
 use super::diagonal::DiagonalMatrix;
 use super::identity::IdentityMatrix;
+use super::lowrank::LowRankMatrix;
+use super::permutation::PermutationMatrix;
+use super::quantized::QuantizedMatrix;
 use super::sparse::SparseMatrix;
+use super::triangular::{Triangle, TriangularMatrix};
 use super::zero::ZeroMatrix;
 
 #[derive(Clone, Debug)]
-pub enum Matrix<const R: usize, const C: usize> where [(); R*C]: Sized {
-    Constant(ConstantMatrix<R, C>),
-    Dense(DenseMatrix<R, C>),
-    Diagonal(DiagonalMatrix<R, C>),
-    Identity(IdentityMatrix<R, C>),
-    Sparse(SparseMatrix<R, C>),
-    Zero(ZeroMatrix<R, C>),
+pub enum Matrix<const R: usize, const C: usize, T: Scalar = f32> where [(); R*C]: Sized {
+    BlockDiagonal(BlockDiagonalMatrix<R, C, T>),
+    Constant(ConstantMatrix<R, C, T>),
+    Dense(DenseMatrix<R, C, T>),
+    Diagonal(DiagonalMatrix<R, C, T>),
+    Identity(IdentityMatrix<R, C, T>),
+    LowerTriangular(TriangularMatrix<R, C, T>),
+    LowRank(LowRankMatrix<R, C, T>),
+    Permutation(PermutationMatrix<R, C, T>),
+    /// `i8` weights for inference. Training through one dequantizes it on
+    /// the first update.
+    Quantized(QuantizedMatrix<R, C, T>),
+    Sparse(SparseMatrix<R, C, T>),
+    UpperTriangular(TriangularMatrix<R, C, T>),
+    Zero(ZeroMatrix<R, C, T>),
 }
 
-impl<const R: usize, const C: usize> Matrix<R, C> where [(); R*C]: Sized {
+impl<const R: usize, const C: usize, T: Scalar> Matrix<R, C, T> where [(); R*C]: Sized {
     // constructor
-    pub fn constant(c: f32) -> Self {
+    pub fn constant(c: T) -> Self {
         Self::Constant(ConstantMatrix(c))
     }
 
     // constructor
-    pub fn from_arr(arr: [f32; R*C]) -> Self {
+    pub fn from_arr(arr: [T; R*C]) -> Self {
         Self::Dense(DenseMatrix::from_arr(arr))
     }
 
     // constructor
-    pub(crate) fn from_boxed_slice(slice: Box<[f32]>) -> Self {
+    pub(crate) fn from_boxed_slice(slice: Box<[T]>) -> Self {
         Self::Dense(DenseMatrix::from_boxed_slice(slice))
     }
 
     // constructor
-    pub fn from_vector(v: Vector<{R*C}>) -> Self {
+    pub fn from_vector(v: Vector<{R*C}, T>) -> Self {
         use Matrix as M;
         use Vector as V;
         match v {
-            V::Constant(v) => M::Constant(ConstantMatrix(v.into())),
+            V::Constant(v) => M::Constant(ConstantMatrix(v.0)),
             V::Dense(v) => M::Dense(DenseMatrix {
                 data: v.data,
                 order: Order::COLS,
                 size_marker: PhantomData,
             }),
-            V::OneHot(_v) => todo!(),
-            V::Sparse(_v) => todo!(),
-            V::Zero(_) => M::Zero(ZeroMatrix(0f32)),
+            // Flat index `i` sits at `(i % R, i / R)`, the same column-major
+            // layout the dense arm reinterprets its data with.
+            V::OneHot(v) => M::Sparse(SparseMatrix::from_triplets([(v.index % R, v.index / R, v.one)])),
+            V::Sparse(v) => M::Sparse(SparseMatrix::from_triplets(v.nonzeros()
+                .map(|(i, x)| (i % R, i / R, x)))),
+            V::Zero(_) => M::Zero(ZeroMatrix(T::zero())),
+        }
+    }
+
+    /// The inverse of `from_vector`: the entries read column by column,
+    /// with structurally sparse flavors ending up as a sparse vector.
+    pub fn into_vector(self) -> Vector<{R*C}, T> {
+        use Matrix as M;
+        use Vector as V;
+        match self {
+            M::Constant(m) => V::Constant(ConstantVector(m.0)),
+            M::Dense(m) if m.order == Order::COLS => V::from_boxed_slice(m.data),
+            M::Dense(m) => V::from_fun(|i| m[(i % R, i / R)]),
+            M::Zero(_) => V::zero(),
+            m if m.has_sparse_structure() => V::from_pairs(m.to_sparse().nonzeros().map(|(r, c, x)| (c * R + r, x))),
+            m => V::from_fun(|i| m.get(i % R, i / R)),
         }
     }
 
     // constructor
-    pub fn from_cols(cols: &[[f32; R]; C]) -> Self {
+    pub fn from_cols(cols: &[[T; R]; C]) -> Self {
         Self::Dense(DenseMatrix::from_cols(cols))
     }
 
+    // constructor
+    /// Builds a sparse matrix out of `(row, col, value)` triplets.
+    /// Repeated positions are summed.
+    pub fn from_triplets(triplets: impl IntoIterator<Item = (usize, usize, T)>) -> Self {
+        Self::Sparse(SparseMatrix::from_triplets(triplets))
+    }
+
     // constructor
     pub fn sparse() -> Self {
-        Self::Sparse(SparseMatrix(Vec::new(), Vec::new(), Order::COLS))
+        Self::Sparse(SparseMatrix::from_triplets(std::iter::empty()))
     }
 
     // constructor
     pub fn zero() -> Self {
-        Self::Zero(ZeroMatrix(0f32))
+        Self::Zero(ZeroMatrix(T::zero()))
+    }
+
+    // constructor
+    /// `u * v.T()`, kept as its factors. Products with it cost `O((R + C) K)`
+    /// per column rather than `O(RC)`, and leave it low-rank.
+    pub fn low_rank<const K: usize>(u: &Matrix<R, K, T>, v: &Matrix<C, K, T>) -> Self
+        where
+            [(); R*K]: Sized,
+            [(); C*K]: Sized,
+    {
+        let u = (0..R*K).map(|i| u.get(i % R, i / R)).collect();
+        let v = (0..C*K).map(|i| v.get(i % C, i / C)).collect();
+        Self::LowRank(LowRankMatrix::from_factors(K, u, v))
+    }
+
+    /// An `i8` copy of `self` with a scale and zero point for every row,
+    /// which suits a weight matrix multiplying column vectors.
+    pub fn quantize_rows(&self) -> Self {
+        Self::Quantized(QuantizedMatrix::quantize(&self.to_dense(), Order::ROWS))
+    }
+
+    /// An `i8` copy of `self` with a scale and zero point for every column.
+    pub fn quantize_cols(&self) -> Self {
+        Self::Quantized(QuantizedMatrix::quantize(&self.to_dense(), Order::COLS))
+    }
+
+    /// The entries on and above the main diagonal. Flavors that are already
+    /// upper triangular are returned as they are.
+    pub fn upper_triangle(&self) -> Self {
+        self.triangle(Triangle::Upper)
+    }
+
+    /// The entries on and below the main diagonal.
+    pub fn lower_triangle(&self) -> Self {
+        self.triangle(Triangle::Lower)
+    }
+
+    fn triangle(&self, side: Triangle) -> Self {
+        use Matrix as M;
+        match (self, side) {
+            (M::Diagonal(_) | M::Identity(_) | M::Zero(_), _)
+            | (M::LowerTriangular(_), Triangle::Lower)
+            | (M::UpperTriangular(_), Triangle::Upper) => self.clone(),
+            _ => Self::triangular(side, TriangularMatrix::from_fun(side, |r, c| self.get(r, c))),
+        }
+    }
+
+    fn triangular(side: Triangle, m: TriangularMatrix<R, C, T>) -> Self {
+        match side {
+            Triangle::Upper => Matrix::UpperTriangular(m),
+            Triangle::Lower => Matrix::LowerTriangular(m),
+        }
+    }
+
+    /// Entry `(r, c)` by value. Unlike `Index`, which has to hand out a
+    /// reference, this reads low-rank and quantized entries without caching
+    /// a full precision copy of the matrix.
+    pub fn get(&self, r: usize, c: usize) -> T {
+        match self {
+            Matrix::LowRank(m) => m.get(r, c),
+            Matrix::Quantized(m) => m.get(r, c),
+            _ => self[(r, c)],
+        }
     }
 
     /// Matrix transpose.
-    pub fn T(&self) -> Matrix<C, R> where [(); C*R]: Sized {
+    pub fn T(&self) -> Matrix<C, R, T> where [(); C*R]: Sized {
         use Matrix as M;
         match self {
-            M::Constant(m) => M::Constant::<C, R>(m.T()),
+            M::BlockDiagonal(m) => M::BlockDiagonal::<C, R, T>(m.T()),
+            M::Constant(m) => M::Constant(m.T()),
             M::Dense(m) => M::Dense(m.T()),
             M::Diagonal(m) => M::Diagonal(m.T()),
             M::Identity(m) => M::Identity(m.T()),
+            M::LowerTriangular(m) => M::UpperTriangular(m.T()),
+            M::LowRank(m) => M::LowRank(m.T()),
+            M::Permutation(m) => M::Permutation(m.T()),
+            M::Quantized(m) => M::Quantized(m.T()),
             M::Sparse(m) => M::Sparse(m.T()),
+            M::UpperTriangular(m) => M::LowerTriangular(m.T()),
             M::Zero(m) => M::Zero(m.T()),
         }
     }
+
+    /// `[self other]`, side by side. Sparse and zero matrices stay that way,
+    /// and so does any mix of flavors with sparse structure.
+    pub fn hstack<const C2: usize>(&self, other: &Matrix<R, C2, T>) -> Matrix<R, {C+C2}, T>
+        where
+            [(); R*C2]: Sized,
+            [(); R*(C+C2)]: Sized,
+    {
+        use Matrix as M;
+        match (self, other) {
+            (M::Constant(m1), M::Constant(m2)) if m1.0 == m2.0 => M::Constant(ConstantMatrix(m1.0)),
+            (M::Dense(m1), M::Dense(m2)) => M::Dense(m1.hstack(m2)),
+            (M::Zero(m1), M::Zero(m2)) => M::Zero(m1.hstack(m2)),
+            _ if self.has_sparse_structure() && other.has_sparse_structure() => {
+                M::Sparse(self.to_sparse().hstack(&other.to_sparse()))
+            },
+            _ => M::Dense(DenseMatrix::from_fun(|r, c| if c < C { self.get(r, c) } else { other.get(r, c - C) })),
+        }
+    }
+
+    /// `[self; other]`, one above the other. Keeps structure like `hstack`.
+    pub fn vstack<const R2: usize>(&self, other: &Matrix<R2, C, T>) -> Matrix<{R+R2}, C, T>
+        where
+            [(); R2*C]: Sized,
+            [(); (R+R2)*C]: Sized,
+    {
+        use Matrix as M;
+        match (self, other) {
+            (M::Constant(m1), M::Constant(m2)) if m1.0 == m2.0 => M::Constant(ConstantMatrix(m1.0)),
+            (M::Dense(m1), M::Dense(m2)) => M::Dense(m1.vstack(m2)),
+            (M::Zero(m1), M::Zero(m2)) => M::Zero(m1.vstack(m2)),
+            _ if self.has_sparse_structure() && other.has_sparse_structure() => {
+                M::Sparse(self.to_sparse().vstack(&other.to_sparse()))
+            },
+            _ => M::Dense(DenseMatrix::from_fun(|r, c| if r < R { self.get(r, c) } else { other.get(r - R, c) })),
+        }
+    }
+
+    /// Whether the zeros of `self` follow from its flavor rather than being
+    /// stored, so that `to_sparse` only has the nonzeros to copy.
+    pub(super) fn has_sparse_structure(&self) -> bool {
+        use Matrix as M;
+        match self {
+            M::BlockDiagonal(_) | M::Diagonal(_) | M::Identity(_) | M::Permutation(_) | M::Sparse(_) | M::Zero(_) => true,
+            M::Constant(_) | M::Dense(_) | M::LowerTriangular(_) | M::LowRank(_) | M::Quantized(_) | M::UpperTriangular(_) => false,
+        }
+    }
+
+    /// The flavors with sparse structure as a `SparseMatrix`.
+    pub(super) fn to_sparse(&self) -> SparseMatrix<R, C, T> {
+        use Matrix as M;
+        match self {
+            M::BlockDiagonal(m) => SparseMatrix::from_triplets(m.stored()),
+            M::Diagonal(m) => SparseMatrix::from_triplets(m.diagonal_data.iter().enumerate().map(|(i, &d)| (i, i, d))),
+            M::Identity(m) => SparseMatrix::from_triplets((0..R.min(C)).map(|i| (i, i, m.1))),
+            M::Permutation(m) => SparseMatrix::from_triplets(m.stored()),
+            M::Sparse(m) => m.clone(),
+            M::Zero(_) => SparseMatrix::from_triplets([]),
+            _ => unreachable!("stored densely"),
+        }
+    }
+
+    /// A dense copy of `self`. Low-rank matrices are multiplied out in one go.
+    pub(super) fn to_dense(&self) -> DenseMatrix<R, C, T> {
+        use Matrix as M;
+        match self {
+            M::Dense(m) | M::LowerTriangular(TriangularMatrix(m)) | M::UpperTriangular(TriangularMatrix(m)) => m.clone(),
+            M::LowRank(m) => m.to_dense(),
+            M::Quantized(m) => m.to_dense(),
+            _ => DenseMatrix::from_fun(|r, c| self.get(r, c)),
+        }
+    }
+
+    /// Switches `self` over to the dense flavor, so it can be mutated entrywise.
+    pub(crate) fn make_dense(&mut self) -> &mut DenseMatrix<R, C, T> {
+        if !matches!(self, Matrix::Dense(_)) {
+            *self = Matrix::Dense(self.to_dense());
+        }
+        match self {
+            Matrix::Dense(m) => m,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Applies `f` to every entry without allocating, once `self` is dense.
+    pub fn map_in_place(&mut self, f: impl Fn(T) -> T) {
+        self.make_dense().map_in_place(f);
+    }
+
+    /// The fraction of entries that are nonzero. For low-rank matrices it
+    /// is an upper bound, since counting would mean multiplying out.
+    pub fn density(&self) -> f32 {
+        if R*C == 0 { 0. } else { self.nnz() as f32 / (R*C) as f32 }
+    }
+
+    fn nnz(&self) -> usize {
+        use Matrix as M;
+        match self {
+            M::BlockDiagonal(m) => m.stored().filter(|&(_, _, x)| x != T::zero()).count(),
+            M::Constant(m) if m.0 != T::zero() => R*C,
+            M::Dense(m) | M::LowerTriangular(TriangularMatrix(m)) | M::UpperTriangular(TriangularMatrix(m)) => {
+                m.data.iter().filter(|&&x| x != T::zero()).count()
+            },
+            M::Diagonal(m) => m.diagonal_data.iter().filter(|&&x| x != T::zero()).count(),
+            M::Identity(m) if m.1 != T::zero() => R.min(C),
+            M::LowRank(m) => m.nnz(),
+            M::Quantized(m) => m.nnz(),
+            M::Permutation(m) if m.one != T::zero() => R,
+            M::Sparse(m) => m.nnz(),
+            M::Constant(_) | M::Identity(_) | M::Permutation(_) | M::Zero(_) => 0,
+        }
+    }
+
+    /// The same matrix, stored densely.
+    pub fn densify(mut self) -> Self {
+        self.make_dense();
+        self
+    }
+
+    /// `self` as a sparse matrix, leaving out entries of magnitude `eps`
+    /// or less.
+    pub fn sparsify(self, eps: T) -> Self {
+        use Matrix as M;
+        let keep = |&(_, _, x): &(usize, usize, T)| x.abs() > eps;
+        match self {
+            M::Zero(_) => M::sparse(),
+            m if m.has_sparse_structure() => M::Sparse(SparseMatrix::from_triplets(m.to_sparse().nonzeros().filter(keep))),
+            m => M::Sparse(SparseMatrix::from_triplets(m.stored().filter(keep))),
+        }
+    }
+
+    /// Moves sparse storage to dense once more than `threshold` of the
+    /// entries are nonzero, and dense storage to sparse once at most half
+    /// that many are. Other flavors are left alone.
+    pub fn rebalance(self, threshold: f32) -> Self {
+        use Matrix as M;
+        match self {
+            M::Sparse(_) if !density::prefers_sparse(self.nnz(), R*C, threshold, true) => self.densify(),
+            M::Dense(_) if density::prefers_sparse(self.nnz(), R*C, threshold, false) => self.sparsify(T::zero()),
+            _ => self,
+        }
+    }
+
+    /// Rebalances the result of an op under the global `density::threshold`,
+    /// provided one is set and some operand was sparse.
+    fn settle(self, sparse_operands: bool) -> Self {
+        match density::threshold() {
+            Some(threshold) if sparse_operands => self.rebalance(threshold),
+            _ => self,
+        }
+    }
+
+    fn settle_in_place(&mut self, sparse_operands: bool) {
+        if sparse_operands && density::threshold().is_some() {
+            let m = std::mem::replace(self, Matrix::zero());
+            *self = m.settle(true);
+        }
+    }
+
+    fn is_sparse(&self) -> bool {
+        matches!(self, Matrix::Sparse(_))
+    }
+
+    /// `self + alpha * rhs` for the flavor pairs whose sum has no structure
+    /// to keep. It is sparse if both sides have sparse structure, and dense
+    /// otherwise.
+    fn combine(&self, alpha: T, rhs: &Matrix<R, C, T>) -> Self {
+        use Matrix as M;
+        if self.has_sparse_structure() && rhs.has_sparse_structure() {
+            return M::Sparse(&self.to_sparse() + &(&rhs.to_sparse() * alpha));
+        }
+        let mut sum = self.to_dense();
+        match rhs {
+            M::Dense(m) => sum.axpy(alpha, m),
+            _ => for (r, c, x) in rhs.stored() {
+                sum[(r, c)] += alpha * x;
+            },
+        }
+        M::Dense(sum)
+    }
+
+    /// `self += alpha * x`. Dense storage is updated in place, other flavors
+    /// of `self` fall back to building the sum.
+    pub fn axpy(&mut self, alpha: T, x: &Matrix<R, C, T>) {
+        use Matrix as M;
+        let sparse_operands = self.is_sparse() || x.is_sparse();
+        match (&mut *self, x) {
+            (_, M::Zero(_)) => {},
+            (M::Dense(m1), M::Dense(m2)) => m1.axpy(alpha, m2),
+            (M::Dense(_), _) => *self += &(x * alpha),
+            _ => *self = &*self + &(x * alpha),
+        }
+        self.settle_in_place(sparse_operands);
+    }
+
+    /// `self += alpha * u.outer(v)`, which is the shape of most gradient
+    /// steps. A dense `self` never materializes the outer product.
+    pub fn add_outer(&mut self, alpha: T, u: &Vector<R, T>, v: &Vector<C, T>) {
+        match self {
+            Matrix::Dense(m) => m.add_outer(alpha, u, v),
+            _ => *self = &*self + &(&u.outer(v) * alpha),
+        }
+    }
+
+    /// `out += alpha * self` over the lines of `order` that `out` covers,
+    /// starting at line `i0`, e.g. whole columns `i0..` for `Order::COLS`.
+    pub(in crate::linalg) fn axpy_lines_into(&self, order: Order, alpha: T, i0: usize, out: &mut [T]) {
+        match (self, order) {
+            (Matrix::Dense(m), _) if m.order == order => {
+                let at = i0 * line_len::<R, C>(order);
+                kernels::axpy(out, alpha, &m.data[at..at + out.len()]);
+            },
+            (_, Order::COLS) => for (k, col) in out.chunks_exact_mut(R).enumerate() {
+                self.line::<R>(order, i0 + k).axpy_into(alpha, col);
+            },
+            (_, Order::ROWS) => for (k, row) in out.chunks_exact_mut(C).enumerate() {
+                self.line::<C>(order, i0 + k).axpy_into(alpha, row);
+            },
+        }
+    }
+
+    /// Makes `self` dense and hands its storage to `f` in runs of whole
+    /// lines, in the storage order, possibly from several threads at once.
+    /// `f` gets the order, the first line of its run, and the run itself.
+    pub(in crate::linalg) fn update_lines(&mut self, work: usize, f: impl Fn(Order, usize, &mut [T]) + Sync) {
+        let m = self.make_dense();
+        let order = m.order;
+        parallel::for_each_chunk(&mut m.data, line_len::<R, C>(order), work, |i0, run| f(order, i0, run));
+    }
 }
 
-impl<const D: usize> Matrix<D, D> where [(); D*D]: Sized {
-    pub fn diag(main_diag: Vector<D>) -> Self {
+/// The length of one line of an `R × C` matrix in the given order.
+fn line_len<const R: usize, const C: usize>(order: Order) -> usize {
+    match order {
+        Order::COLS => R,
+        Order::ROWS => C,
+    }
+}
+
+impl<const D: usize, T: Scalar> Matrix<D, D, T> where [(); D*D]: Sized {
+    pub fn diag(main_diag: Vector<D, T>) -> Self {
         Self::Diagonal(main_diag.into())
     }
     
     pub fn I() -> Self {
-        Self::Identity(IdentityMatrix(0f32, 1f32))
+        Self::Identity(IdentityMatrix(T::zero(), T::one()))
+    }
+
+    // constructor
+    /// The permutation matrix whose row `i` picks entry `perm[i]`, so that
+    /// `(P x)[i] == x[perm[i]]`.
+    pub fn permutation(perm: [usize; D]) -> Self {
+        let mut seen = [false; D];
+        for &j in &perm {
+            assert!(j < D && !seen[j], "{perm:?} is not a permutation");
+            seen[j] = true;
+        }
+        Self::Permutation(PermutationMatrix { perm: perm.into(), one: T::one() })
+    }
+
+    /// `self ⊕ other`, with `self` in the top left and `other` in the bottom
+    /// right. Diagonal operands give a diagonal result, anything else a
+    /// block diagonal one.
+    pub fn direct_sum<const D2: usize>(&self, other: &Matrix<D2, D2, T>) -> Matrix<{D+D2}, {D+D2}, T>
+        where
+            [(); D2*D2]: Sized,
+            [(); (D+D2)*(D+D2)]: Sized,
+    {
+        use Matrix as M;
+        match (self, other) {
+            (M::Zero(_), M::Zero(_)) => M::zero(),
+            (M::Identity(m1), M::Identity(m2)) if m1.1 == m2.1 => M::Identity(IdentityMatrix(T::zero(), m1.1)),
+            (M::Diagonal(_) | M::Identity(_) | M::Zero(_), M::Diagonal(_) | M::Identity(_) | M::Zero(_)) => {
+                M::Diagonal(DiagonalMatrix::from_fun(|i| if i < D { self[(i, i)] } else { other[(i - D, i - D)] }))
+            },
+            _ => M::BlockDiagonal(BlockDiagonalMatrix::from_blocks(
+                self.diagonal_blocks().into_iter().chain(other.diagonal_blocks())
+            )),
+        }
+    }
+
+    /// `self` split into the blocks it would keep as part of a block
+    /// diagonal matrix: its own blocks, `1 × 1` blocks for a diagonal, and
+    /// otherwise all of it.
+    fn diagonal_blocks(&self) -> Vec<(usize, Box<[T]>)> {
+        use Matrix as M;
+        match self {
+            M::BlockDiagonal(m) => m.blocks().map(|(_, n, block)| (n, block.into())).collect(),
+            M::Diagonal(_) | M::Identity(_) | M::Zero(_) => (0..D).map(|i| (1, Box::from([self[(i, i)]]))).collect(),
+            _ if D == 0 => Vec::new(),
+            _ => vec![(D, (0..D*D).map(|i| self.get(i % D, i / D)).collect())],
+        }
     }
 }
 
-impl<const R: usize, const C: usize> Add<&Matrix<R, C>> for &Matrix<R, C> where [(); R*C]: Sized {
-    type Output = Matrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Add<&Matrix<R, C, T>> for &Matrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Matrix<R, C, T>;
 
     // Some matrix flavors can only be instantiated as square, but we must provide
     // rectangular impls for all of them to satisfy impl coherence.
-    fn add(self, rhs: &Matrix<R, C>) -> Self::Output {
+    fn add(self, rhs: &Matrix<R, C, T>) -> Self::Output {
         use Matrix as M;
-        match (self, rhs) {
+        let sum = match (self, rhs) {
             (M::Constant(m1), M::Constant(m2)) => M::Constant(m1 + m2),
             (M::Constant(m1), M::Dense(m2)) => M::Dense(m1 + m2),
-            (M::Constant(m1), M::Diagonal(m2)) => M::Constant(m1 + m2),
-            (M::Constant(m1), M::Identity(m2)) => M::Constant(m1 + m2),
-            (M::Constant(m1), M::Sparse(m2)) => M::Sparse(m1 + m2),
-            (M::Constant(m1), M::Zero(m2)) => M::Zero(m1 + m2),
+            (M::Constant(m1), M::Diagonal(m2)) => M::Dense(m1 + m2),
+            (M::Constant(m1), M::Identity(m2)) => M::Dense(m1 + m2),
+            (M::Constant(m1), M::Sparse(m2)) => M::Dense(m1 + m2),
+            (M::Constant(m1), M::Zero(m2)) => M::Constant(m1 + m2),
 
             (M::Dense(m1), M::Constant(m2)) => M::Dense(m1 + m2),
             (M::Dense(m1), M::Dense(m2)) => M::Dense(m1 + m2),
@@ -142,87 +534,111 @@ impl<const R: usize, const C: usize> Add<&Matrix<R, C>> for &Matrix<R, C> where
             (M::Zero(m1), M::Identity(m2)) => M::Identity(m1 + m2),
             (M::Zero(m1), M::Sparse(m2)) => M::Sparse(m1 + m2),
             (M::Zero(m1), M::Zero(m2)) => M::Zero(m1 + m2),
-        }
+
+            (_, M::Zero(_)) => self.clone(),
+            (M::Zero(_), _) => rhs.clone(),
+            (M::BlockDiagonal(m1), M::BlockDiagonal(m2)) if m1.offsets == m2.offsets => {
+                M::BlockDiagonal(m1.zip_blocks(m2, |_, a, b| a.iter().zip(b).map(|(&x, &y)| x + y).collect()).unwrap())
+            },
+            (M::BlockDiagonal(m), d @ (M::Diagonal(_) | M::Identity(_)))
+            | (d @ (M::Diagonal(_) | M::Identity(_)), M::BlockDiagonal(m)) => M::BlockDiagonal(m.add_diagonal(|i| d[(i, i)])),
+            (M::LowerTriangular(m1), M::LowerTriangular(m2)) => M::LowerTriangular(TriangularMatrix(&m1.0 + &m2.0)),
+            (M::LowerTriangular(m), d @ (M::Diagonal(_) | M::Identity(_)))
+            | (d @ (M::Diagonal(_) | M::Identity(_)), M::LowerTriangular(m)) => M::LowerTriangular(m.add_diagonal(|i| d[(i, i)])),
+            (M::LowRank(m1), M::LowRank(m2)) => M::LowRank(m1.plus(T::one(), m2)),
+            (M::UpperTriangular(m1), M::UpperTriangular(m2)) => M::UpperTriangular(TriangularMatrix(&m1.0 + &m2.0)),
+            (M::UpperTriangular(m), d @ (M::Diagonal(_) | M::Identity(_)))
+            | (d @ (M::Diagonal(_) | M::Identity(_)), M::UpperTriangular(m)) => M::UpperTriangular(m.add_diagonal(|i| d[(i, i)])),
+            _ => self.combine(T::one(), rhs),
+        };
+        sum.settle(self.is_sparse() || rhs.is_sparse())
     }
 }
 
-impl<const R: usize, const C: usize> AddAssign<&Matrix<R, C>> for Matrix<R, C> where [(); R*C]: Sized {
-    fn add_assign(&mut self, rhs: &Matrix<R, C>) {
+impl<const R: usize, const C: usize, T: Scalar> AddAssign<&Matrix<R, C, T>> for Matrix<R, C, T> where [(); R*C]: Sized {
+    // Updates in place whenever the result keeps the flavor of `self`,
+    // otherwise falls back to `self + rhs` and replaces the flavor.
+    fn add_assign(&mut self, rhs: &Matrix<R, C, T>) {
         use Matrix as M;
+        let sparse_operands = self.is_sparse() || rhs.is_sparse();
         match (&mut *self, rhs) {
-            (M::Constant(_m1), M::Constant(_m2)) => todo!(),
-            (M::Constant(_m1), M::Dense(_m2)) => todo!(),
-            (M::Constant(_m1), M::Diagonal(_m2)) => todo!(),
-            (M::Constant(_m1), M::Identity(_m2)) => todo!(),
-            (M::Constant(_m1), M::Sparse(_m2)) => todo!(),
-            (M::Constant(_m1), M::Zero(_m2)) => todo!(),
-
-            (M::Dense(_m1), M::Constant(_m2)) => todo!(),
-            (M::Dense(_m1), M::Dense(_m2)) => todo!(),
-            (M::Dense(_m1), M::Diagonal(_m2)) => todo!(),
-            (M::Dense(_m1), M::Identity(_m2)) => todo!(),
-            (M::Dense(_m1), M::Sparse(_m2)) => todo!(),
-            (M::Dense(_m1), M::Zero(_m2)) => todo!(),
-
-            (M::Diagonal(_m1), M::Constant(_m2)) => todo!(),
-            (M::Diagonal(_m1), M::Dense(_m2)) => todo!(),
-            (M::Diagonal(_m1), M::Diagonal(_m2)) => todo!(),
-            (M::Diagonal(_m1), M::Identity(_m2)) => todo!(),
-            (M::Diagonal(_m1), M::Sparse(_m2)) => todo!(),
-            (M::Diagonal(_m1), M::Zero(_m2)) => todo!(),
-
-            (M::Identity(_m1), M::Constant(_m2)) => todo!(),
-            (M::Identity(_m1), M::Dense(_m2)) => todo!(),
-            (M::Identity(_m1), M::Diagonal(_m2)) => todo!(),
-            (M::Identity(_m1), M::Identity(_m2)) => todo!(),
-            (M::Identity(_m1), M::Sparse(_m2)) => todo!(),
-            (M::Identity(_m1), M::Zero(_m2)) => todo!(),
-
-            (M::Sparse(_m1), M::Constant(_m2)) => todo!(),
-            (M::Sparse(_m1), M::Dense(_m2)) => todo!(),
-            (M::Sparse(_m1), M::Diagonal(_m2)) => todo!(),
-            (M::Sparse(_m1), M::Identity(_m2)) => todo!(),
-            (M::Sparse(_m1), M::Sparse(_m2)) => todo!(),
-            (M::Sparse(_m1), M::Zero(_m2)) => todo!(),
-
-            (M::Zero(_m1), M::Constant(_m2)) => todo!(),
-            (M::Zero(_m1), M::Dense(_m2)) => todo!(),
-            (M::Zero(_m1), M::Diagonal(_m2)) => todo!(),
-            (M::Zero(_m1), M::Identity(_m2)) => todo!(),
-            (M::Zero(_m1), M::Sparse(_m2)) => todo!(),
-            (M::Zero(_m1), M::Zero(_m2)) => todo!(),
+            (M::Constant(m1), M::Constant(m2)) => m1.0 += m2.0,
+
+            (M::Dense(m1), M::Constant(m2)) => *m1 += m2,
+            (M::Dense(m1), M::Dense(m2)) => *m1 += m2,
+            (M::Dense(m1), M::Diagonal(m2)) => *m1 += m2,
+            (M::Dense(m1), M::Identity(m2)) => *m1 += m2,
+            (M::Dense(m1), M::Sparse(m2)) => *m1 += m2,
+
+            (M::Diagonal(m1), M::Diagonal(m2)) => *m1 = &*m1 + m2,
+            (M::Diagonal(m1), M::Identity(m2)) => *m1 = &*m1 + m2,
+
+            (M::Sparse(m1), M::Diagonal(m2)) => *m1 = &*m1 + m2,
+            (M::Sparse(m1), M::Identity(m2)) => *m1 = &*m1 + m2,
+            (M::Sparse(m1), M::Sparse(m2)) => *m1 = &*m1 + m2,
+
+            (_, M::Zero(_)) => {},
+            (M::Zero(_), m) => *self = m.clone(),
+            _ => *self = &*self + rhs,
+        }
+        self.settle_in_place(sparse_operands);
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> AddAssign<T> for Matrix<R, C, T> where [(); R*C]: Sized {
+    fn add_assign(&mut self, rhs: T) {
+        use Matrix as M;
+        match self {
+            M::Constant(m) => m.0 += rhs,
+            M::Dense(m) => *m += rhs,
+            _ => *self = &*self + rhs,
         }
     }
 }
 
-impl<const R: usize, const C: usize> AddAssign<f32> for Matrix<R, C> where [(); R*C]: Sized {
-    fn add_assign(&mut self, _rhs: f32) {
+impl<const R: usize, const C: usize, T: Scalar> SubAssign<&Matrix<R, C, T>> for Matrix<R, C, T> where [(); R*C]: Sized {
+    fn sub_assign(&mut self, rhs: &Matrix<R, C, T>) {
+        self.axpy(-T::one(), rhs);
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> SubAssign<T> for Matrix<R, C, T> where [(); R*C]: Sized {
+    fn sub_assign(&mut self, rhs: T) {
+        *self += -rhs;
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Add<T> for &Matrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Matrix<R, C, T>;
+
+    fn add(self, rhs: T) -> Self::Output {
         use Matrix as M;
         match self {
-            M::Constant(_m) => todo!(),
-            M::Dense(_m) => todo!(),
-            M::Diagonal(_m) => todo!(),
-            M::Identity(_m) => todo!(),
-            M::Sparse(_m) => todo!(),
-            M::Zero(_m) => todo!(),
+            M::Constant(m) => M::Constant(m + rhs),
+            M::Dense(m) => M::Dense(m + rhs),
+            M::Diagonal(m) => M::Dense(m + rhs),
+            M::Identity(m) => M::Dense(m + rhs),
+            M::Sparse(m) => M::Dense(m + rhs),
+            M::Zero(m) => M::Constant(m + rhs),
+            _ => M::Dense(&self.to_dense() + rhs),
         }
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&Matrix<R, C>> for &Matrix<R, C> where [(); R*C]: Sized {
-    type Output = Matrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Sub<&Matrix<R, C, T>> for &Matrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Matrix<R, C, T>;
 
     // Some matrix flavors can only be instantiated as square, but we must provide
     // rectangular impls for all of them to satisfy impl coherence.
-    fn sub(self, rhs: &Matrix<R, C>) -> Self::Output {
+    fn sub(self, rhs: &Matrix<R, C, T>) -> Self::Output {
         use Matrix as M;
-        match (self, rhs) {
+        let difference = match (self, rhs) {
             (M::Constant(m1), M::Constant(m2)) => M::Constant(m1 - m2),
             (M::Constant(m1), M::Dense(m2)) => M::Dense(m1 - m2),
-            (M::Constant(m1), M::Diagonal(m2)) => M::Constant(m1 - m2),
-            (M::Constant(m1), M::Identity(m2)) => M::Constant(m1 - m2),
-            (M::Constant(m1), M::Sparse(m2)) => M::Sparse(m1 - m2),
-            (M::Constant(m1), M::Zero(m2)) => M::Zero(m1 - m2),
+            (M::Constant(m1), M::Diagonal(m2)) => M::Dense(m1 - m2),
+            (M::Constant(m1), M::Identity(m2)) => M::Dense(m1 - m2),
+            (M::Constant(m1), M::Sparse(m2)) => M::Dense(m1 - m2),
+            (M::Constant(m1), M::Zero(m2)) => M::Constant(m1 - m2),
 
             (M::Dense(m1), M::Constant(m2)) => M::Dense(m1 - m2),
             (M::Dense(m1), M::Dense(m2)) => M::Dense(m1 - m2),
@@ -258,28 +674,41 @@ impl<const R: usize, const C: usize> Sub<&Matrix<R, C>> for &Matrix<R, C> where
             (M::Zero(m1), M::Identity(m2)) => M::Diagonal(m1 - m2),
             (M::Zero(m1), M::Sparse(m2)) => M::Sparse(m1 - m2),
             (M::Zero(m1), M::Zero(m2)) => M::Zero(m1 - m2),
-        }
+
+            // The newer flavors add the negation, which keeps their structure
+            // at the cost of a copy.
+            _ => self + &-rhs,
+        };
+        difference.settle(self.is_sparse() || rhs.is_sparse())
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&Matrix<C, C2>> for &Matrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<T> for &Matrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Matrix<R, C, T>;
+
+    fn sub(self, rhs: T) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&Matrix<C, C2, T>> for &Matrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = Matrix<R, C2>;
+    type Output = Matrix<R, C2, T>;
     
     // Some matrix flavors can only be instantiated as square, but we must provide
     // rectangular impls for all of them to satisfy impl coherence.
-    fn mul(self, rhs: &Matrix<C, C2>) -> Self::Output {
+    fn mul(self, rhs: &Matrix<C, C2, T>) -> Self::Output {
         use Matrix as M;
-        match (self, rhs) {
+        let product = match (self, rhs) {
             (M::Constant(m1), M::Constant(m2)) => M::Constant(m1 * m2),
             (M::Constant(m1), M::Dense(m2)) => M::Dense(m1 * m2),
-            (M::Constant(m1), M::Diagonal(m2)) => M::Constant(m1 * m2),
+            (M::Constant(m1), M::Diagonal(m2)) => M::Dense(m1 * m2),
             (M::Constant(m1), M::Identity(m2)) => M::Constant(m1 * m2),
-            (M::Constant(m1), M::Sparse(m2)) => M::Sparse(m1 * m2),
+            (M::Constant(m1), M::Sparse(m2)) => M::Dense(m1 * m2),
             (M::Constant(m1), M::Zero(m2)) => M::Zero(m1 * m2),
 
             (M::Dense(m1), M::Constant(m2)) => M::Dense(m1 * m2),
@@ -289,7 +718,7 @@ impl<const R: usize, const C: usize, const C2: usize> Mul<&Matrix<C, C2>> for &M
             (M::Dense(m1), M::Sparse(m2)) => M::Dense(m1 * m2),
             (M::Dense(m1), M::Zero(m2)) => M::Zero(m1 * m2),
 
-            (M::Diagonal(m1), M::Constant(m2)) => M::Constant(m1 * m2),
+            (M::Diagonal(m1), M::Constant(m2)) => M::Dense(m1 * m2),
             (M::Diagonal(m1), M::Dense(m2)) => M::Dense(m1 * m2),
             (M::Diagonal(m1), M::Diagonal(m2)) => M::Diagonal(m1 * m2),
             (M::Diagonal(m1), M::Identity(m2)) => M::Diagonal(m1 * m2),
@@ -303,7 +732,7 @@ impl<const R: usize, const C: usize, const C2: usize> Mul<&Matrix<C, C2>> for &M
             (M::Identity(m1), M::Sparse(m2)) => M::Sparse(m1 * m2),
             (M::Identity(m1), M::Zero(m2)) => M::Zero(m1 * m2),
 
-            (M::Sparse(m1), M::Constant(m2)) => M::Sparse(m1 * m2),
+            (M::Sparse(m1), M::Constant(m2)) => M::Dense(m1 * m2),
             (M::Sparse(m1), M::Dense(m2)) => M::Dense(m1 * m2),
             (M::Sparse(m1), M::Diagonal(m2)) => M::Sparse(m1 * m2),
             (M::Sparse(m1), M::Identity(m2)) => M::Sparse(m1 * m2),
@@ -316,58 +745,166 @@ impl<const R: usize, const C: usize, const C2: usize> Mul<&Matrix<C, C2>> for &M
             (M::Zero(m1), M::Identity(m2)) => M::Zero(m1 * m2),
             (M::Zero(m1), M::Sparse(m2)) => M::Zero(m1 * m2),
             (M::Zero(m1), M::Zero(m2)) => M::Zero(m1 * m2),
-        }
+
+            (M::Zero(_), _) | (_, M::Zero(_)) => M::zero(),
+            (_, M::LowRank(m)) => M::LowRank(m.left_mul(self)),
+            (M::LowRank(m), _) => M::LowRank(m.right_mul(rhs)),
+
+            (M::BlockDiagonal(m1), M::BlockDiagonal(m2)) if m1.offsets == m2.offsets => {
+                M::BlockDiagonal(m1.zip_blocks(m2, multiply_blocks).unwrap())
+            },
+            (d @ (M::Diagonal(_) | M::Identity(_)), M::BlockDiagonal(m)) => M::BlockDiagonal(m.scale(|i| d[(i, i)], |_| T::one())),
+            (M::BlockDiagonal(m), d @ (M::Diagonal(_) | M::Identity(_))) => M::BlockDiagonal(m.scale(|_| T::one(), |j| d[(j, j)])),
+
+            (M::LowerTriangular(m1), M::LowerTriangular(m2)) => M::LowerTriangular(TriangularMatrix(&m1.0 * &m2.0)),
+            (d @ (M::Diagonal(_) | M::Identity(_)), M::LowerTriangular(m)) => M::LowerTriangular(m.scale(|i| d[(i, i)], |_| T::one())),
+            (M::LowerTriangular(m), d @ (M::Diagonal(_) | M::Identity(_))) => M::LowerTriangular(m.scale(|_| T::one(), |j| d[(j, j)])),
+            (M::UpperTriangular(m1), M::UpperTriangular(m2)) => M::UpperTriangular(TriangularMatrix(&m1.0 * &m2.0)),
+            (d @ (M::Diagonal(_) | M::Identity(_)), M::UpperTriangular(m)) => M::UpperTriangular(m.scale(|i| d[(i, i)], |_| T::one())),
+            (M::UpperTriangular(m), d @ (M::Diagonal(_) | M::Identity(_))) => M::UpperTriangular(m.scale(|_| T::one(), |j| d[(j, j)])),
+
+            (M::Identity(i), M::Quantized(m)) => M::Quantized(m.scaled(i.1)),
+            (M::Quantized(m), M::Identity(i)) => M::Quantized(m.scaled(i.1)),
+
+            (M::Identity(m), M::Permutation(p)) => M::Permutation(p.scaled(m.1)),
+            (M::Permutation(p), M::Identity(m)) => M::Permutation(p.scaled(m.1)),
+            (M::Permutation(p1), M::Permutation(p2)) => M::Permutation(p1.then(p2)),
+            (M::Permutation(p), x) => p.permute_rows(x),
+            (x, M::Permutation(p)) => p.permute_cols(x),
+
+            _ if self.has_sparse_structure() && rhs.has_sparse_structure() => M::Sparse(&self.to_sparse() * &rhs.to_sparse()),
+            _ => M::Dense(&self.to_dense() * &rhs.to_dense()),
+        };
+        product.settle(self.is_sparse() || rhs.is_sparse())
     }
 }
 
-impl<const R: usize, const C: usize> Mul<&Matrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = Matrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<T> for &Matrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Matrix<R, C, T>;
 
-    fn mul(self, rhs: &Matrix<R, C>) -> Self::Output {
+    fn mul(self, rhs: T) -> Self::Output {
         use Matrix as M;
-        match rhs {
-            M::Constant(m) => M::Constant(m * self),
-            M::Dense(m) => M::Dense(m * self),
-            M::Diagonal(m) => M::Diagonal(m * self),
-            M::Identity(m) => M::Diagonal(m * self),
-            M::Sparse(m) => M::Sparse(m * self),
-            M::Zero(m) => M::Zero(m * self),
+        match self {
+            M::BlockDiagonal(m) => M::BlockDiagonal(m.map(|x| x * rhs)),
+            M::Constant(m) => M::Constant(m * rhs),
+            M::Dense(m) => M::Dense(m * rhs),
+            M::Diagonal(m) => M::Diagonal(m * rhs),
+            M::Identity(m) => M::Diagonal(m * rhs),
+            M::LowerTriangular(m) => M::LowerTriangular(TriangularMatrix(&m.0 * rhs)),
+            M::LowRank(m) => M::LowRank(m.scaled(rhs)),
+            M::Permutation(m) => M::Permutation(m.scaled(rhs)),
+            M::Quantized(m) => M::Quantized(m.scaled(rhs)),
+            M::Sparse(m) => M::Sparse(m * rhs),
+            M::UpperTriangular(m) => M::UpperTriangular(TriangularMatrix(&m.0 * rhs)),
+            M::Zero(m) => M::Zero(m * rhs),
         }
     }
 }
 
-impl<const R: usize, const C: usize> Neg for &Matrix<R, C> where [(); R*C]: Sized {
-    type Output = Matrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> MulAssign<T> for Matrix<R, C, T> where [(); R*C]: Sized {
+    fn mul_assign(&mut self, rhs: T) {
+        use Matrix as M;
+        match self {
+            M::Constant(m) => m.0 *= rhs,
+            M::Dense(m) => *m *= rhs,
+            M::Zero(_) => {},
+            _ => *self = &*self * rhs,
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> MulAssign<&Matrix<C, C, T>> for Matrix<R, C, T>
+    where
+        [(); R*C]: Sized,
+        [(); C*C]: Sized,
+{
+    /// Right-multiplies in place. The product needs fresh storage whatever
+    /// the flavors, so this only saves the caller the reassignment.
+    fn mul_assign(&mut self, rhs: &Matrix<C, C, T>) {
+        *self = &*self * rhs;
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Neg for &Matrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Matrix<R, C, T>;
 
     fn neg(self) -> Self::Output {
         use Matrix as M;
         match self {
+            M::BlockDiagonal(m) => M::BlockDiagonal(m.map(|x| -x)),
             M::Constant(m) => M::Constant(-m),
             M::Dense(m) => M::Dense(-m),
             M::Diagonal(m) => M::Diagonal(-m),
             M::Identity(m) => M::Diagonal(-m),
+            M::LowerTriangular(m) => M::LowerTriangular(TriangularMatrix(-&m.0)),
+            M::LowRank(m) => M::LowRank(m.scaled(-T::one())),
+            M::Permutation(m) => M::Permutation(m.scaled(-T::one())),
+            M::Quantized(m) => M::Quantized(m.scaled(-T::one())),
             M::Sparse(m) => M::Sparse(-m),
+            M::UpperTriangular(m) => M::UpperTriangular(TriangularMatrix(-&m.0)),
             M::Zero(m) => M::Zero(-m), // This is just clone.
         }
     }
 }
 
-impl<const R: usize, const C: usize> Mul<&Vector<C>> for &Matrix<R, C> where [(); R*C]: Sized {
-    type Output = Vector<R>;
+scalar_lhs_ops!(Matrix, add: Matrix, mul: Matrix);
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&Vector<C, T>> for &Matrix<R, C, T> where [(); R*C]: Sized {
+    type Output = Vector<R, T>;
     
-    fn mul(self, rhs: &Vector<C>) -> Self::Output {
+    fn mul(self, rhs: &Vector<C, T>) -> Self::Output {
         use Matrix as M;
         use Vector as V;
-        match (self, rhs) {
+        let product = match (self, rhs) {
+            (M::Constant(m), V::Constant(v)) => V::Constant(m * v),
+            (M::Constant(m), V::Dense(v)) => V::Constant(m * v),
+            (M::Constant(m), V::OneHot(v)) => V::Constant(m * v),
+            (M::Constant(m), V::Sparse(v)) => V::Constant(m * v),
+            (M::Constant(m), V::Zero(v)) => V::Zero(m * v),
+
+            (M::Dense(m), V::Constant(v)) => V::Dense(m * v),
             (M::Dense(m), V::Dense(v)) => V::Dense(m * v),
+            (M::Dense(m), V::OneHot(v)) => V::Dense(m * v),
+            (M::Dense(m), V::Sparse(v)) => V::Dense(m * v),
+            (M::Dense(m), V::Zero(v)) => V::Zero(m * v),
+
+            (M::Diagonal(m), V::Constant(v)) => V::Dense(m * v),
             (M::Diagonal(m), V::Dense(v)) => V::Dense(m * v),
-            // TODO: matrix-vector mul impls.
-            _ => unimplemented!(),
-        }
+            (M::Diagonal(m), V::OneHot(v)) => V::Sparse(m * v),
+            (M::Diagonal(m), V::Sparse(v)) => V::Sparse(m * v),
+            (M::Diagonal(m), V::Zero(v)) => V::Zero(m * v),
+
+            (M::Identity(m), V::Constant(v)) => V::Constant(m * v),
+            (M::Identity(m), V::Dense(v)) => V::Dense(m * v),
+            (M::Identity(m), V::OneHot(v)) => V::OneHot(m * v),
+            (M::Identity(m), V::Sparse(v)) => V::Sparse(m * v),
+            (M::Identity(m), V::Zero(v)) => V::Zero(m * v),
+
+            (M::Sparse(m), V::Constant(v)) => V::Dense(m * v),
+            (M::Sparse(m), V::Dense(v)) => V::Dense(m * v),
+            (M::Sparse(m), V::OneHot(v)) => V::Sparse(m * v),
+            (M::Sparse(m), V::Sparse(v)) => V::Sparse(m * v),
+            (M::Sparse(m), V::Zero(v)) => V::Zero(m * v),
+
+            (M::Zero(m), V::Constant(v)) => V::Zero(m * v),
+            (M::Zero(m), V::Dense(v)) => V::Zero(m * v),
+            (M::Zero(m), V::OneHot(v)) => V::Zero(m * v),
+            (M::Zero(m), V::Sparse(v)) => V::Zero(m * v),
+            (M::Zero(m), V::Zero(v)) => V::Zero(m * v),
+
+            (_, V::Zero(_)) => V::zero(),
+            (M::BlockDiagonal(m), v) => m * v,
+            (M::LowerTriangular(m), v) => m.mul_vec(Triangle::Lower, v),
+            (M::LowRank(m), v) => m * v,
+            (M::Permutation(m), v) => m * v,
+            (M::Quantized(m), v) => m * v,
+            (M::UpperTriangular(m), v) => m.mul_vec(Triangle::Upper, v),
+        };
+        product.settle(self.is_sparse() || rhs.is_sparse())
     }
 }
 
-impl<const R: usize, const C: usize> PartialEq for Matrix<R, C> where [(); R*C]: Sized {
+impl<const R: usize, const C: usize, T: Scalar> PartialEq for Matrix<R, C, T> where [(); R*C]: Sized {
     fn eq(&self, other: &Self) -> bool {
         use Matrix as M;
         match (self, other) {
@@ -377,13 +914,43 @@ impl<const R: usize, const C: usize> PartialEq for Matrix<R, C> where [(); R*C]:
             (M::Identity(m1), M::Identity(m2)) => m1 == m2,
             (M::Sparse(m1), M::Sparse(m2)) => m1 == m2,
             (M::Zero(m1), M::Zero(m2)) => m1 == m2,
-            _ => false, // TODO: equality between mixed flavors.
+            _ => (0..R).all(|r| (0..C).all(|c| self.get(r, c) == other.get(r, c))),
         }
     }
 }
 
-impl<const R: usize, const C: usize> From<[f32; R*C]> for Matrix<R, C> where [(); R*C]: Sized {
-    fn from(arr: [f32; R*C]) -> Self {
+impl<const R: usize, const C: usize, T: Scalar> From<[T; R*C]> for Matrix<R, C, T> where [(); R*C]: Sized {
+    fn from(arr: [T; R*C]) -> Self {
         Matrix::Dense(DenseMatrix::from_arr(arr))
     }
 }
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for Matrix<R, C, T> where [(); R*C]: Sized {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        use Matrix as M;
+        match self {
+            M::BlockDiagonal(m) => &m[index],
+            M::Constant(m) => &m[index],
+            M::Dense(m) => &m[index],
+            M::Diagonal(m) => &m[index],
+            M::Identity(m) => &m[index],
+            M::LowerTriangular(m) => &m[index],
+            M::LowRank(m) => &m[index],
+            M::Permutation(m) => &m[index],
+            M::Quantized(m) => &m[index],
+            M::Sparse(m) => &m[index],
+            M::UpperTriangular(m) => &m[index],
+            M::Zero(m) => &m[index],
+        }
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> IndexMut<(usize, usize)> for Matrix<R, C, T> where [(); R*C]: Sized {
+    /// Writing through an index needs storage for every entry, so other
+    /// flavors are first promoted to dense.
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self.make_dense()[index]
+    }
+}
@@ -1,5 +1,9 @@
 use std::fmt::Display;
-use std::ops::{Add, Mul, Neg, Sub};
+use std::ops::{Add, Index, Mul, Neg, Sub};
+
+use crate::linalg::Scalar;
+use crate::linalg::vector::{ConstantVector, DenseVector, OneHotVector, SparseVector, ZeroVector};
+use crate::linalg::vector::traits::{CanStackHorizontally, CanStackVertically};
 
 use super::constant::ConstantMatrix;
 use super::dense::DenseMatrix;
@@ -8,296 +12,363 @@ use super::identity::IdentityMatrix;
 use super::sparse::SparseMatrix;
 
 #[derive(Clone, Debug)]
-pub struct ZeroMatrix<const R: usize, const C: usize>(
-    pub(super) f32,
+pub struct ZeroMatrix<const R: usize, const C: usize, T: Scalar = f32>(
+    pub(crate) T,
 );
 
-impl<const R: usize, const C: usize> ZeroMatrix<R, C> {
-    pub(super) fn T(&self) -> ZeroMatrix<C, R> {
+impl<const R: usize, const C: usize, T: Scalar> ZeroMatrix<R, C, T> {
+    pub(super) fn T(&self) -> ZeroMatrix<C, R, T> {
         ZeroMatrix(self.0)
     }
 }
 
-impl<const R: usize, const C: usize> PartialEq for ZeroMatrix<R, C> where [(); R*C]: Sized {
+impl<const R: usize, const C: usize, T: Scalar> PartialEq for ZeroMatrix<R, C, T> where [(); R*C]: Sized {
     fn eq(&self, _other: &Self) -> bool {
         true
     }
 }
 
-///////////////////////////////
-/// SPARSE MATRIX ADD IMPLS ///
-///////////////////////////////
+/////////////////////////////
+//  ZERO MATRIX ADD IMPLS  //
+/////////////////////////////
 
-impl<const R: usize, const C: usize> Add<&ConstantMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ConstantMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ConstantMatrix<R, C>;
+    type Output = ConstantMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        rhs.clone()
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DenseMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DenseMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        rhs.clone()
     }
 }
 
-impl<const R: usize, const C: usize> Add<&DiagonalMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&DiagonalMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn add(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        rhs.clone()
     }
 }
 
-impl<const R: usize, const C: usize> Add<&IdentityMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&IdentityMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = IdentityMatrix<R, C>;
+    type Output = IdentityMatrix<R, C, T>;
 
-    fn add(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        rhs.clone()
     }
 }
 
-impl<const R: usize, const C: usize> Add<&SparseMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&SparseMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn add(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        rhs.clone()
     }
 }
 
-impl<const R: usize, const C: usize> Add<&ZeroMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Add<&ZeroMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ZeroMatrix<R, C>;
+    type Output = ZeroMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-///////////////////////////////
-/// SPARSE MATRIX SUB IMPLS ///
-///////////////////////////////
+/////////////////////////////
+//  ZERO MATRIX SUB IMPLS  //
+/////////////////////////////
 
-impl<const R: usize, const C: usize> Sub<&ConstantMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ConstantMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ConstantMatrix<R, C>;
+    type Output = ConstantMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ConstantMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &ConstantMatrix<R, C, T>) -> Self::Output {
+        -rhs
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DenseMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DenseMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DenseMatrix<R, C>;
+    type Output = DenseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DenseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DenseMatrix<R, C, T>) -> Self::Output {
+        -rhs
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&DiagonalMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&DiagonalMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &DiagonalMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DiagonalMatrix<R, C, T>) -> Self::Output {
+        -rhs
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&IdentityMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&IdentityMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = DiagonalMatrix<R, C>;
+    type Output = DiagonalMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &IdentityMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &IdentityMatrix<R, C, T>) -> Self::Output {
+        -rhs
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&SparseMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&SparseMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = SparseMatrix<R, C>;
+    type Output = SparseMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &SparseMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseMatrix<R, C, T>) -> Self::Output {
+        -rhs
     }
 }
 
-impl<const R: usize, const C: usize> Sub<&ZeroMatrix<R, C>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, T: Scalar> Sub<&ZeroMatrix<R, C, T>> for &ZeroMatrix<R, C, T>
     where [(); R*C]: Sized
 {
-    type Output = ZeroMatrix<R, C>;
+    type Output = ZeroMatrix<R, C, T>;
 
-    fn sub(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroMatrix<R, C, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
 /////////////////////////////
-/// ZERO MATRIX MUL IMPLS ///
+//  ZERO MATRIX MUL IMPLS  //
 /////////////////////////////
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ConstantMatrix<C, C2>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ConstantMatrix<C, C2, T>> for &ZeroMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &ConstantMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &ConstantMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DenseMatrix<C, C2>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DenseMatrix<C, C2, T>> for &ZeroMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &DenseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &DenseMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&DiagonalMatrix<C, C2>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&DiagonalMatrix<C, C2, T>> for &ZeroMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &DiagonalMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &DiagonalMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&IdentityMatrix<C, C2>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&IdentityMatrix<C, C2, T>> for &ZeroMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &IdentityMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &IdentityMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&SparseMatrix<C, C2>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&SparseMatrix<C, C2, T>> for &ZeroMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
         [(); R*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
 
-    fn mul(self, _rhs: &SparseMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &SparseMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize, const C2: usize> Mul<&ZeroMatrix<C, C2>> for &ZeroMatrix<R, C>
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> Mul<&ZeroMatrix<C, C2, T>> for &ZeroMatrix<R, C, T>
     where
         [(); R*C]: Sized,
         [(); C*C2]: Sized,
 {
-    type Output = ZeroMatrix<R, C2>;
+    type Output = ZeroMatrix<R, C2, T>;
+
+    fn mul(self, _rhs: &ZeroMatrix<C, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ConstantVector<C, T>> for &ZeroMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ZeroVector<R, T>;
 
-    fn mul(self, _rhs: &ZeroMatrix<C, C2>) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: &ConstantVector<C, T>) -> Self::Output {
+        ZeroVector(T::zero())
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&DenseVector<C, T>> for &ZeroMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ZeroVector<R, T>;
+
+    fn mul(self, _rhs: &DenseVector<C, T>) -> Self::Output {
+        ZeroVector(T::zero())
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&OneHotVector<C, T>> for &ZeroMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ZeroVector<R, T>;
+
+    fn mul(self, _rhs: &OneHotVector<C, T>) -> Self::Output {
+        ZeroVector(T::zero())
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&SparseVector<C, T>> for &ZeroMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ZeroVector<R, T>;
+
+    fn mul(self, _rhs: &SparseVector<C, T>) -> Self::Output {
+        ZeroVector(T::zero())
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> Mul<&ZeroVector<C, T>> for &ZeroMatrix<R, C, T>
+    where [(); R*C]: Sized
+{
+    type Output = ZeroVector<R, T>;
+
+    fn mul(self, _rhs: &ZeroVector<C, T>) -> Self::Output {
+        ZeroVector(T::zero())
     }
 }
 
 //////////////////////////////
-/// ZERO MATRIX MATH IMPLS ///
+//  ZERO MATRIX MATH IMPLS  //
 //////////////////////////////
 
-impl<const R: usize, const C: usize> Add<&ZeroMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = ConstantMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Add<T> for &ZeroMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = ConstantMatrix<R, C, T>;
 
-    fn add(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: T) -> Self::Output {
+        ConstantMatrix(rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Add<f32> for &ZeroMatrix<R, C> where [(); R*C]: Sized {
-    type Output = ConstantMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Sub<T> for &ZeroMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = ConstantMatrix<R, C, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        ConstantMatrix(-rhs)
     }
 }
 
-impl<const R: usize, const C: usize> Sub<f32> for &ZeroMatrix<R, C> where [(); R*C]: Sized {
-    type Output = ConstantMatrix<R, C>;
+impl<const R: usize, const C: usize, T: Scalar> Mul<T> for &ZeroMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = ZeroMatrix<R, C, T>;
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, _rhs: T) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize> Mul<&ZeroMatrix<R, C>> for f32 where [(); R*C]: Sized {
-    type Output = ZeroMatrix<R, C>;
+scalar_lhs_ops!(ZeroMatrix, add: ConstantMatrix, mul: ZeroMatrix);
 
-    fn mul(self, _rhs: &ZeroMatrix<R, C>) -> Self::Output {
-        todo!()
+impl<const R: usize, const C: usize, T: Scalar> Neg for &ZeroMatrix<R, C, T> where [(); R*C]: Sized {
+    type Output = ZeroMatrix<R, C, T>;
+
+    fn neg(self) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize> Mul<f32> for &ZeroMatrix<R, C> where [(); R*C]: Sized {
-    type Output = ZeroMatrix<R, C>;
+///////////////////////////////
+//  ZERO MATRIX STACK IMPLS  //
+///////////////////////////////
+
+impl<const R: usize, const C: usize, const C2: usize, T: Scalar> CanStackHorizontally<&ZeroMatrix<R, C2, T>> for ZeroMatrix<R, C, T>
+    where [(); C+C2]: Sized
+{
+    type Output = ZeroMatrix<R, {C+C2}, T>;
 
-    fn mul(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn hstack(&self, _other: &ZeroMatrix<R, C2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const R: usize, const C: usize> Neg for &ZeroMatrix<R, C> where [(); R*C]: Sized {
-    type Output = ZeroMatrix<R, C>;
+impl<const R: usize, const C: usize, const R2: usize, T: Scalar> CanStackVertically<&ZeroMatrix<R2, C, T>> for ZeroMatrix<R, C, T>
+    where [(); R+R2]: Sized
+{
+    type Output = ZeroMatrix<{R+R2}, C, T>;
 
-    fn neg(self) -> Self::Output {
-        todo!()
+    fn vstack(&self, _other: &ZeroMatrix<R2, C, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
 /////////////////////////////////
-/// ZERO MATRIX UTILITY IMPLS ///
+//  ZERO MATRIX UTILITY IMPLS  //
 /////////////////////////////////
 
-impl<const R: usize, const C: usize> Display for ZeroMatrix<R, C> where [(); R*C]: Sized {
+impl<const R: usize, const C: usize, T: Scalar> Display for ZeroMatrix<R, C, T> where [(); R*C]: Sized {
     /// Displays the rows of a zero matrix.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}]", (0..R).into_iter()
-            .map(|_| std::iter::repeat("0")
-                .take(C)
+        write!(f, "[{}]", (0..R)
+            .map(|_| std::iter::repeat_n("0", C)
                 .collect::<Vec<_>>()
                 .join(","))
             .collect::<Vec<_>>()
             .join("],["))
     }
 }
+
+impl<const R: usize, const C: usize, T: Scalar> Index<(usize, usize)> for ZeroMatrix<R, C, T> {
+    type Output = T;
+
+    fn index(&self, _index: (usize, usize)) -> &Self::Output {
+        &self.0
+    }
+}
@@ -1,15 +1,27 @@
 pub use matrix::OldMatrixDoNotUse; // re-export
 pub use vector::OldVectorDoNotUse; // re-export
 
+pub mod density;
+mod error;
+mod half;
+mod kernels;
+pub mod lazy;
 pub(super) mod matrix;
 mod order;
+pub mod parallel;
+mod scalar;
+mod tensor;
 pub(super) mod vector;
 
-pub use matrix::Matrix;
-pub use vector::Vector;
+pub use error::LinalgError;
+pub use half::{bf16, f16};
+pub use matrix::{Lu, Matrix, MatrixView, Qr, Svd, SymmetricEigen};
+pub use scalar::Scalar;
+pub use tensor::{Tensor3, Tensor4};
+pub use vector::{Vector, VectorView};
 
 //////////////////////////////////////////
-/// TESTS OF LINEAR ALGEBRA OPERATIONS ///
+//  TESTS OF LINEAR ALGEBRA OPERATIONS  //
 //////////////////////////////////////////
 
 #[cfg(test)]
@@ -4,7 +4,8 @@ use std::ops::Neg;
 /// Indicates whether strides of an array underlying a dense matrix
 /// should be interpreted as matrix rows or matrix columns. Twiddling
 /// this parameter is a simple indicator of transposing a matrix.
-pub(super) enum Order {
+#[allow(clippy::upper_case_acronyms)]
+pub enum Order {
     COLS,
     ROWS,
 }
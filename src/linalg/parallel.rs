@@ -0,0 +1,67 @@
+//! Process-wide settings for splitting large dense products and elementwise
+//! ops across threads.
+//!
+//! Work is always split along the output, and every output entry is computed
+//! by the same sequence of floating point operations as in the single-threaded
+//! path, so results are bitwise identical for any thread count.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Below this many multiply-adds an op runs on the calling thread, as
+/// spawning would cost more than it saves.
+pub const DEFAULT_THRESHOLD: usize = 1 << 18;
+
+/// `0` means "as many as the machine has".
+static NUM_THREADS: AtomicUsize = AtomicUsize::new(0);
+static THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_THRESHOLD);
+
+/// Caps the number of threads a single op may use. `1` disables
+/// multithreading, `0` restores the default of one thread per core.
+pub fn set_num_threads(n: usize) {
+    NUM_THREADS.store(n, Ordering::Relaxed);
+}
+
+pub fn num_threads() -> usize {
+    match NUM_THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+/// Sets the amount of work, in multiply-adds, from which ops get split.
+pub fn set_threshold(work: usize) {
+    THRESHOLD.store(work, Ordering::Relaxed);
+}
+
+pub fn threshold() -> usize {
+    THRESHOLD.load(Ordering::Relaxed)
+}
+
+/// Splits `out` into runs of whole `unit`s (e.g. columns) and hands each run
+/// to `f` together with the index of its first unit. `work` is the cost of
+/// the whole op and decides whether it is worth splitting at all.
+pub(crate) fn for_each_chunk<T: Send>(
+    out: &mut [T],
+    unit: usize,
+    work: usize,
+    f: impl Fn(usize, &mut [T]) + Sync,
+) {
+    let units = out.len().checked_div(unit).unwrap_or(0);
+    let threads = num_threads().min(units);
+    if threads <= 1 || work < threshold() {
+        f(0, out);
+        return;
+    }
+
+    let per_thread = units.div_ceil(threads);
+    let f = &f;
+    thread::scope(|s| {
+        let mut chunks = out.chunks_mut(per_thread * unit).enumerate();
+        let (_, first) = chunks.next().unwrap();
+        for (i, chunk) in chunks {
+            s.spawn(move || f(i * per_thread, chunk));
+        }
+        f(0, first);
+    });
+}
@@ -0,0 +1,48 @@
+use std::fmt::{Debug, Display};
+use std::iter::Sum;
+
+use num_traits::{Float, NumAssign, NumCast};
+
+use super::kernels::Kernels;
+
+/// The entry type of `Vector` and `Matrix`. `f32` is the default everywhere;
+/// `f64` suits gradient checks and anything numerically delicate, and the
+/// 16-bit [`bf16`](super::bf16) and [`f16`](super::f16) halve the memory of
+/// stored weights at the cost of precision.
+pub trait Scalar: Float + NumAssign + Sum + Default + Debug + Display + Send + Sync + 'static + Kernels {
+    /// What `Index` hands out for entries a flavor doesn't store.
+    const ZERO_REF: &'static Self;
+
+    fn from_f32(x: f32) -> Self;
+
+    fn to_f32(self) -> f32;
+
+    /// For counts and dimensions, e.g. the `D` in a mean.
+    fn from_usize(n: usize) -> Self {
+        <Self as NumCast>::from(n).unwrap()
+    }
+}
+
+impl Scalar for f32 {
+    const ZERO_REF: &'static Self = &0.;
+
+    fn from_f32(x: f32) -> Self {
+        x
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+}
+
+impl Scalar for f64 {
+    const ZERO_REF: &'static Self = &0.;
+
+    fn from_f32(x: f32) -> Self {
+        x.into()
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+}
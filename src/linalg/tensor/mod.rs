@@ -0,0 +1,324 @@
+//! Dense tensors of rank three and four. Entries are stored first axis
+//! fastest, the higher-rank version of the column-major layout `Matrix`
+//! and `Matrix::from_vector` use, so reshaping between tensors, matrices
+//! and vectors reinterprets the storage instead of copying it. Every shape,
+//! including the results of reshapes, permutations and slices, is checked
+//! at compile time.
+
+mod rank3;
+mod rank4;
+
+pub use rank3::Tensor3;
+pub use rank4::Tensor4;
+
+// Shape arithmetic for the signatures, public only because the signatures
+// are. A `generic_const_exprs` bound can't index an array, so the
+// rank-specific wrappers take one argument per axis.
+
+const fn dim<const N: usize>(dims: [usize; N], axis: usize) -> usize {
+    assert!(axis < N, "no such axis");
+    dims[axis]
+}
+
+/// Length of the `k`th axis left over once `axis` is indexed away.
+const fn without<const N: usize>(dims: [usize; N], axis: usize, k: usize) -> usize {
+    assert!(axis < N, "no such axis");
+    dim(dims, if k < axis { k } else { k + 1 })
+}
+
+/// Length of the `k`th axis once `axis` is cut down to `len`.
+const fn resized<const N: usize>(dims: [usize; N], axis: usize, len: usize, k: usize) -> usize {
+    assert!(axis < N, "no such axis");
+    if k == axis { len } else { dim(dims, k) }
+}
+
+/// Zero, so it can sit in a bound, if `axes` lists every axis exactly once.
+const fn permutation<const N: usize>(axes: [usize; N]) -> usize {
+    let mut seen = [false; N];
+    let mut i = 0;
+    while i < N {
+        assert!(axes[i] < N && !seen[axes[i]], "not a permutation of the axes");
+        seen[axes[i]] = true;
+        i += 1;
+    }
+    0
+}
+
+pub const fn dim3(a: usize, b: usize, c: usize, axis: usize) -> usize {
+    dim([a, b, c], axis)
+}
+
+pub const fn without3(a: usize, b: usize, c: usize, axis: usize, k: usize) -> usize {
+    without([a, b, c], axis, k)
+}
+
+pub const fn resized3(a: usize, b: usize, c: usize, axis: usize, len: usize, k: usize) -> usize {
+    resized([a, b, c], axis, len, k)
+}
+
+pub const fn permutation3(p0: usize, p1: usize, p2: usize) -> usize {
+    permutation([p0, p1, p2])
+}
+
+pub const fn dim4(a: usize, b: usize, c: usize, d: usize, axis: usize) -> usize {
+    dim([a, b, c, d], axis)
+}
+
+pub const fn without4(a: usize, b: usize, c: usize, d: usize, axis: usize, k: usize) -> usize {
+    without([a, b, c, d], axis, k)
+}
+
+pub const fn resized4(a: usize, b: usize, c: usize, d: usize, axis: usize, len: usize, k: usize) -> usize {
+    resized([a, b, c, d], axis, len, k)
+}
+
+pub const fn permutation4(p0: usize, p1: usize, p2: usize, p3: usize) -> usize {
+    permutation([p0, p1, p2, p3])
+}
+
+/// Position of `index` in storage of shape `dims`.
+fn offset<const N: usize>(dims: [usize; N], index: [usize; N]) -> usize {
+    index.iter().zip(dims).rev().fold(0, |acc, (&i, d)| {
+        assert!(i < d, "index {index:?} out of bounds for shape {dims:?}");
+        acc * d + i
+    })
+}
+
+/// Storage of shape `dims`, filled by calling `f` on every index in storage
+/// order.
+fn gather<T, const N: usize>(dims: [usize; N], f: impl Fn([usize; N]) -> T) -> Box<[T]> {
+    let len = dims.iter().product();
+    let mut data = Vec::with_capacity(len);
+    let mut index = [0; N];
+    for _ in 0..len {
+        data.push(f(index));
+        // Odometer step, the first axis turning fastest.
+        for (i, &d) in index.iter_mut().zip(&dims) {
+            *i += 1;
+            if *i < d {
+                break;
+            }
+            *i = 0;
+        }
+    }
+    data.into_boxed_slice()
+}
+
+/// Entrywise arithmetic shared by the tensor types, which differ only in
+/// their const parameters and the product bounding their length.
+macro_rules! elementwise_ops {
+    ($t:ident<$($d:ident),+>, $($len:tt)+) => {
+        impl<$(const $d: usize,)+ T: Scalar> $t<$($d,)+ T> where [(); $($len)+]: Sized {
+            /// Allocates storage and lets `kernel` fill it in.
+            fn from_kernel(kernel: impl FnOnce(&mut [T])) -> Self {
+                let mut data = vec![T::zero(); $($len)+].into_boxed_slice();
+                kernel(&mut data);
+                Self::from_boxed_slice(data)
+            }
+
+            /// Entrywise product.
+            pub fn hadamard(&self, other: &Self) -> Self {
+                Self::from_kernel(|out| kernels::mul(&self.data, &other.data, out))
+            }
+
+            pub fn map(&self, f: impl Fn(T) -> T) -> Self {
+                Self::from_kernel(|out| kernels::map(&self.data, f, out))
+            }
+
+            pub fn map_in_place(&mut self, f: impl Fn(T) -> T) {
+                kernels::map_assign(&mut self.data, f);
+            }
+
+            /// `self += alpha * x`
+            pub fn axpy(&mut self, alpha: T, x: &Self) {
+                kernels::axpy(&mut self.data, alpha, &x.data);
+            }
+
+            pub fn sum(&self) -> T {
+                kernels::sum(&self.data)
+            }
+        }
+
+        impl<$(const $d: usize,)+ T: Scalar> Add<&$t<$($d,)+ T>> for &$t<$($d,)+ T> where [(); $($len)+]: Sized {
+            type Output = $t<$($d,)+ T>;
+
+            fn add(self, rhs: &$t<$($d,)+ T>) -> Self::Output {
+                $t::from_kernel(|out| kernels::add(&self.data, &rhs.data, out))
+            }
+        }
+
+        impl<$(const $d: usize,)+ T: Scalar> Sub<&$t<$($d,)+ T>> for &$t<$($d,)+ T> where [(); $($len)+]: Sized {
+            type Output = $t<$($d,)+ T>;
+
+            fn sub(self, rhs: &$t<$($d,)+ T>) -> Self::Output {
+                $t::from_kernel(|out| kernels::sub(&self.data, &rhs.data, out))
+            }
+        }
+
+        impl<$(const $d: usize,)+ T: Scalar> Add<T> for &$t<$($d,)+ T> where [(); $($len)+]: Sized {
+            type Output = $t<$($d,)+ T>;
+
+            fn add(self, rhs: T) -> Self::Output {
+                $t::from_kernel(|out| kernels::offset(&self.data, rhs, out))
+            }
+        }
+
+        impl<$(const $d: usize,)+ T: Scalar> Sub<T> for &$t<$($d,)+ T> where [(); $($len)+]: Sized {
+            type Output = $t<$($d,)+ T>;
+
+            fn sub(self, rhs: T) -> Self::Output {
+                $t::from_kernel(|out| kernels::offset(&self.data, -rhs, out))
+            }
+        }
+
+        impl<$(const $d: usize,)+ T: Scalar> Mul<T> for &$t<$($d,)+ T> where [(); $($len)+]: Sized {
+            type Output = $t<$($d,)+ T>;
+
+            fn mul(self, rhs: T) -> Self::Output {
+                $t::from_kernel(|out| kernels::scale(&self.data, rhs, out))
+            }
+        }
+
+        impl<$(const $d: usize,)+ T: Scalar> Neg for &$t<$($d,)+ T> where [(); $($len)+]: Sized {
+            type Output = $t<$($d,)+ T>;
+
+            fn neg(self) -> Self::Output {
+                self * -T::one()
+            }
+        }
+
+        impl<$(const $d: usize,)+ T: Scalar> AddAssign<&$t<$($d,)+ T>> for $t<$($d,)+ T> where [(); $($len)+]: Sized {
+            fn add_assign(&mut self, rhs: &$t<$($d,)+ T>) {
+                kernels::add_assign(&mut self.data, &rhs.data);
+            }
+        }
+
+        impl<$(const $d: usize,)+ T: Scalar> SubAssign<&$t<$($d,)+ T>> for $t<$($d,)+ T> where [(); $($len)+]: Sized {
+            fn sub_assign(&mut self, rhs: &$t<$($d,)+ T>) {
+                kernels::axpy(&mut self.data, -T::one(), &rhs.data);
+            }
+        }
+
+        impl<$(const $d: usize,)+ T: Scalar> MulAssign<T> for $t<$($d,)+ T> where [(); $($len)+]: Sized {
+            fn mul_assign(&mut self, rhs: T) {
+                kernels::scale_assign(&mut self.data, rhs);
+            }
+        }
+
+        elementwise_ops!(@lhs $t<$($d),+>, [$($len)+], f32);
+        elementwise_ops!(@lhs $t<$($d),+>, [$($len)+], f64);
+        elementwise_ops!(@lhs $t<$($d),+>, [$($len)+], bf16);
+        elementwise_ops!(@lhs $t<$($d),+>, [$($len)+], f16);
+    };
+    (@lhs $t:ident<$($d:ident),+>, [$($len:tt)+], $s:ty) => {
+        impl<$(const $d: usize),+> Add<&$t<$($d,)+ $s>> for $s where [(); $($len)+]: Sized {
+            type Output = $t<$($d,)+ $s>;
+
+            fn add(self, rhs: &$t<$($d,)+ $s>) -> Self::Output {
+                rhs + self
+            }
+        }
+
+        impl<$(const $d: usize),+> Mul<&$t<$($d,)+ $s>> for $s where [(); $($len)+]: Sized {
+            type Output = $t<$($d,)+ $s>;
+
+            fn mul(self, rhs: &$t<$($d,)+ $s>) -> Self::Output {
+                rhs * self
+            }
+        }
+    };
+}
+
+use elementwise_ops;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::{Matrix, Vector};
+    use super::super::matrix::test_support::{tensor3, tensor4};
+
+    #[test]
+    fn storage_is_first_axis_fastest() {
+        let t = tensor3();
+        let v: Vector<24> = t.clone().into_vector();
+        assert_eq!((v[0], v[1], v[2], v[6]), (0., 1., 10., 100.));
+
+        // Unfolding along the first axis is `from_vector` on the same data.
+        let m: Matrix<2, 12> = t.clone().into_matrix();
+        assert_eq!(m, Matrix::from_vector(v));
+        assert_eq!(m[(1, 4)], t[(1, 1, 1)]);
+        assert_eq!(Tensor3::from_matrix(m), t);
+        assert_eq!(Tensor3::<2, 3, 4>::from_vector(Vector::from_pairs([(7, 2.)]))[(1, 0, 1)], 2.);
+    }
+
+    #[test]
+    fn reshape_keeps_storage_order() {
+        let t = tensor3();
+        let r: Tensor3<6, 2, 2> = t.clone().reshape();
+        assert_eq!(r[(5, 0, 0)], t[(1, 2, 0)]);
+        assert_eq!(r[(0, 1, 1)], t[(0, 0, 3)]);
+        let back: Tensor3<2, 3, 4> = r.reshape();
+        assert_eq!(back, t);
+
+        let q: Tensor4<2, 3, 2, 2> = Tensor4::from_vector(t.clone().into_vector());
+        assert_eq!(q[(1, 2, 1, 1)], t[(1, 2, 3)]);
+        assert_eq!(q.into_matrix::<6, 4>(), t.into_matrix());
+    }
+
+    #[test]
+    fn permute_moves_axes() {
+        let t = tensor3();
+        let p: Tensor3<4, 2, 3> = t.permute::<2, 0, 1>();
+        for (a, b, c) in [(0, 0, 0), (1, 2, 3), (1, 0, 2), (0, 1, 1)] {
+            assert_eq!(p[(c, a, b)], t[(a, b, c)]);
+        }
+        assert_eq!(p.permute::<1, 2, 0>(), t);
+
+        let q = tensor4();
+        let s: Tensor4<5, 3, 2, 4> = q.permute::<3, 1, 0, 2>();
+        assert_eq!(s[(4, 2, 1, 3)], q[(1, 2, 3, 4)]);
+    }
+
+    #[test]
+    fn select_and_slice_follow_indexing() {
+        let t = tensor3();
+        let m: Matrix<3, 4> = t.select::<0>(1);
+        assert_eq!(m[(2, 3)], t[(1, 2, 3)]);
+        let m: Matrix<2, 4> = t.select::<1>(2);
+        assert_eq!(m[(1, 3)], t[(1, 2, 3)]);
+        let m: Matrix<2, 3> = t.select::<2>(3);
+        assert_eq!(m[(1, 2)], t[(1, 2, 3)]);
+
+        let s: Tensor3<2, 2, 4> = t.slice::<1, 1, 2>();
+        assert_eq!(s[(1, 0, 3)], t[(1, 1, 3)]);
+        assert_eq!(s[(0, 1, 2)], t[(0, 2, 2)]);
+
+        let q = tensor4();
+        let u: Tensor3<2, 3, 5> = q.select::<2>(1);
+        assert_eq!(u[(1, 2, 4)], q[(1, 2, 1, 4)]);
+        let w: Tensor4<2, 3, 4, 2> = q.slice::<3, 3, 2>();
+        assert_eq!(w[(1, 1, 1, 1)], q[(1, 1, 1, 4)]);
+    }
+
+    #[test]
+    fn elementwise_ops_match_indexing() {
+        let t = tensor3();
+        let u = Tensor3::<2, 3, 4>::from_fun(|a, b, c| (a * b) as f32 - c as f32);
+        let at = (1, 2, 3);
+        assert_eq!((&t + &u)[at], t[at] + u[at]);
+        assert_eq!((&t - &u)[at], t[at] - u[at]);
+        assert_eq!(t.hadamard(&u)[at], t[at] * u[at]);
+        assert_eq!((2. * &t)[at], 2. * t[at]);
+        assert_eq!((&t + 1.5)[at], t[at] + 1.5);
+        assert_eq!((-&t)[at], -t[at]);
+        assert_eq!(t.map(|x| x * x)[at], t[at] * t[at]);
+
+        let mut v = t.clone();
+        v += &u;
+        v -= &t;
+        v *= 3.;
+        assert_eq!(v, &u * 3.);
+        let by_hand: f32 = gather([2, 3, 4], |[a, b, c]| u[(a, b, c)]).iter().sum();
+        assert_eq!(u.sum(), by_hand);
+    }
+}
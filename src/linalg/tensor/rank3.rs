@@ -0,0 +1,155 @@
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::linalg::{bf16, f16, kernels, Matrix, Scalar, Vector};
+
+use super::{dim3, gather, offset, permutation3, resized3, without3};
+use super::elementwise_ops;
+
+/// An `A × B × C` tensor, stored densely.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tensor3<const A: usize, const B: usize, const C: usize, T: Scalar = f32> where [(); A*B*C]: Sized {
+    pub(super) data: Box<[T]>,
+    size_marker: PhantomData<[[[T; A]; B]; C]>,
+}
+
+impl<const A: usize, const B: usize, const C: usize, T: Scalar> Tensor3<A, B, C, T> where [(); A*B*C]: Sized {
+    // constructor
+    pub(super) fn from_boxed_slice(slice: Box<[T]>) -> Self {
+        assert_eq!(slice.len(), A*B*C);
+        Tensor3 {
+            data: slice,
+            size_marker: PhantomData,
+        }
+    }
+
+    // constructor
+    pub fn constant(c: T) -> Self {
+        Self::from_boxed_slice(vec![c; A*B*C].into_boxed_slice())
+    }
+
+    // constructor
+    pub fn zero() -> Self {
+        Self::constant(T::zero())
+    }
+
+    // constructor
+    pub fn from_fun(f: impl Fn(usize, usize, usize) -> T) -> Self {
+        Self::from_boxed_slice(gather([A, B, C], |[a, b, c]| f(a, b, c)))
+    }
+
+    // constructor
+    /// Flat index `a + A*(b + B*c)` becomes `(a, b, c)`.
+    pub fn from_vector(v: Vector<{A*B*C}, T>) -> Self {
+        Self::from_boxed_slice(v.into_dense().data)
+    }
+
+    // constructor
+    /// Reads `m` in column-major order, so a `Matrix<A, {B*C}>` is the
+    /// tensor unfolded along its first axis.
+    pub fn from_matrix<const R: usize, const K: usize>(m: Matrix<R, K, T>) -> Self
+        where
+            [(); R*K]: Sized,
+            [(); R*K - A*B*C]: Sized,
+            [(); A*B*C - R*K]: Sized,
+    {
+        Self::from_boxed_slice(m.into_vector().into_dense().data)
+    }
+
+    /// The inverse of `from_vector`.
+    pub fn into_vector(self) -> Vector<{A*B*C}, T> {
+        Vector::from_boxed_slice(self.data)
+    }
+
+    /// The inverse of `from_matrix`.
+    pub fn into_matrix<const R: usize, const K: usize>(self) -> Matrix<R, K, T>
+        where
+            [(); R*K]: Sized,
+            [(); R*K - A*B*C]: Sized,
+            [(); A*B*C - R*K]: Sized,
+    {
+        Matrix::from_boxed_slice(self.data)
+    }
+
+    /// The same entries in the same storage order, under another shape of
+    /// the same size.
+    pub fn reshape<const A2: usize, const B2: usize, const C2: usize>(self) -> Tensor3<A2, B2, C2, T>
+        where
+            [(); A2*B2*C2]: Sized,
+            [(); A2*B2*C2 - A*B*C]: Sized,
+            [(); A*B*C - A2*B2*C2]: Sized,
+    {
+        Tensor3::from_boxed_slice(self.data)
+    }
+
+    /// Reorders the axes, so that axis `k` of the result is axis `Pk` of
+    /// `self`. Anything but a permutation of `0, 1, 2` does not compile.
+    pub fn permute<const P0: usize, const P1: usize, const P2: usize>(&self)
+        -> Tensor3<{dim3(A, B, C, P0)}, {dim3(A, B, C, P1)}, {dim3(A, B, C, P2)}, T>
+        where
+            [(); permutation3(P0, P1, P2)]: Sized,
+            [(); dim3(A, B, C, P0) * dim3(A, B, C, P1) * dim3(A, B, C, P2)]: Sized,
+    {
+        let dims = [dim3(A, B, C, P0), dim3(A, B, C, P1), dim3(A, B, C, P2)];
+        Tensor3::from_boxed_slice(gather(dims, |out| {
+            let mut index = [0; 3];
+            for (k, p) in [P0, P1, P2].into_iter().enumerate() {
+                index[p] = out[k];
+            }
+            self.data[offset([A, B, C], index)]
+        }))
+    }
+
+    /// The matrix at index `i` along `AXIS`, with the other two axes in
+    /// their original order.
+    pub fn select<const AXIS: usize>(&self, i: usize)
+        -> Matrix<{without3(A, B, C, AXIS, 0)}, {without3(A, B, C, AXIS, 1)}, T>
+        where [(); without3(A, B, C, AXIS, 0) * without3(A, B, C, AXIS, 1)]: Sized
+    {
+        assert!(i < dim3(A, B, C, AXIS));
+        let dims = [without3(A, B, C, AXIS, 0), without3(A, B, C, AXIS, 1)];
+        Matrix::from_boxed_slice(gather(dims, |[r, c]| {
+            let index = match AXIS {
+                0 => [i, r, c],
+                1 => [r, i, c],
+                _ => [r, c, i],
+            };
+            self.data[offset([A, B, C], index)]
+        }))
+    }
+
+    /// The `LEN` slices starting at `START` along `AXIS`. A slice reaching
+    /// past the end does not compile.
+    pub fn slice<const AXIS: usize, const START: usize, const LEN: usize>(&self)
+        -> Tensor3<{resized3(A, B, C, AXIS, LEN, 0)}, {resized3(A, B, C, AXIS, LEN, 1)}, {resized3(A, B, C, AXIS, LEN, 2)}, T>
+        where
+            [(); dim3(A, B, C, AXIS) - (START + LEN)]: Sized,
+            [(); resized3(A, B, C, AXIS, LEN, 0) * resized3(A, B, C, AXIS, LEN, 1) * resized3(A, B, C, AXIS, LEN, 2)]: Sized,
+    {
+        let dims = [0, 1, 2].map(|k| resized3(A, B, C, AXIS, LEN, k));
+        Tensor3::from_boxed_slice(gather(dims, |mut index| {
+            index[AXIS] += START;
+            self.data[offset([A, B, C], index)]
+        }))
+    }
+}
+
+elementwise_ops!(Tensor3<A, B, C>, A*B*C);
+
+impl<const A: usize, const B: usize, const C: usize, T: Scalar> Index<(usize, usize, usize)> for Tensor3<A, B, C, T>
+    where [(); A*B*C]: Sized
+{
+    type Output = T;
+
+    fn index(&self, (a, b, c): (usize, usize, usize)) -> &Self::Output {
+        &self.data[offset([A, B, C], [a, b, c])]
+    }
+}
+
+impl<const A: usize, const B: usize, const C: usize, T: Scalar> IndexMut<(usize, usize, usize)> for Tensor3<A, B, C, T>
+    where [(); A*B*C]: Sized
+{
+    fn index_mut(&mut self, (a, b, c): (usize, usize, usize)) -> &mut Self::Output {
+        &mut self.data[offset([A, B, C], [a, b, c])]
+    }
+}
@@ -0,0 +1,167 @@
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::linalg::{bf16, f16, kernels, Matrix, Scalar, Vector};
+
+use super::{dim4, gather, offset, permutation4, resized4, without4};
+use super::elementwise_ops;
+use super::rank3::Tensor3;
+
+/// An `A × B × C × D` tensor, stored densely. For a batch of images this is
+/// typically height, width, channel and batch, which keeps each channel of
+/// each image contiguous.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tensor4<const A: usize, const B: usize, const C: usize, const D: usize, T: Scalar = f32>
+    where [(); A*B*C*D]: Sized
+{
+    pub(super) data: Box<[T]>,
+    size_marker: PhantomData<[[[[T; A]; B]; C]; D]>,
+}
+
+impl<const A: usize, const B: usize, const C: usize, const D: usize, T: Scalar> Tensor4<A, B, C, D, T>
+    where [(); A*B*C*D]: Sized
+{
+    // constructor
+    pub(super) fn from_boxed_slice(slice: Box<[T]>) -> Self {
+        assert_eq!(slice.len(), A*B*C*D);
+        Tensor4 {
+            data: slice,
+            size_marker: PhantomData,
+        }
+    }
+
+    // constructor
+    pub fn constant(c: T) -> Self {
+        Self::from_boxed_slice(vec![c; A*B*C*D].into_boxed_slice())
+    }
+
+    // constructor
+    pub fn zero() -> Self {
+        Self::constant(T::zero())
+    }
+
+    // constructor
+    pub fn from_fun(f: impl Fn(usize, usize, usize, usize) -> T) -> Self {
+        Self::from_boxed_slice(gather([A, B, C, D], |[a, b, c, d]| f(a, b, c, d)))
+    }
+
+    // constructor
+    /// Flat index `a + A*(b + B*(c + C*d))` becomes `(a, b, c, d)`.
+    pub fn from_vector(v: Vector<{A*B*C*D}, T>) -> Self {
+        Self::from_boxed_slice(v.into_dense().data)
+    }
+
+    // constructor
+    /// Reads `m` in column-major order, like `Tensor3::from_matrix`.
+    pub fn from_matrix<const R: usize, const K: usize>(m: Matrix<R, K, T>) -> Self
+        where
+            [(); R*K]: Sized,
+            [(); R*K - A*B*C*D]: Sized,
+            [(); A*B*C*D - R*K]: Sized,
+    {
+        Self::from_boxed_slice(m.into_vector().into_dense().data)
+    }
+
+    /// The inverse of `from_vector`.
+    pub fn into_vector(self) -> Vector<{A*B*C*D}, T> {
+        Vector::from_boxed_slice(self.data)
+    }
+
+    /// The inverse of `from_matrix`.
+    pub fn into_matrix<const R: usize, const K: usize>(self) -> Matrix<R, K, T>
+        where
+            [(); R*K]: Sized,
+            [(); R*K - A*B*C*D]: Sized,
+            [(); A*B*C*D - R*K]: Sized,
+    {
+        Matrix::from_boxed_slice(self.data)
+    }
+
+    /// The same entries in the same storage order, under another shape of
+    /// the same size.
+    pub fn reshape<const A2: usize, const B2: usize, const C2: usize, const D2: usize>(self) -> Tensor4<A2, B2, C2, D2, T>
+        where
+            [(); A2*B2*C2*D2]: Sized,
+            [(); A2*B2*C2*D2 - A*B*C*D]: Sized,
+            [(); A*B*C*D - A2*B2*C2*D2]: Sized,
+    {
+        Tensor4::from_boxed_slice(self.data)
+    }
+
+    /// Reorders the axes, so that axis `k` of the result is axis `Pk` of
+    /// `self`. Anything but a permutation of `0, 1, 2, 3` does not compile.
+    pub fn permute<const P0: usize, const P1: usize, const P2: usize, const P3: usize>(&self)
+        -> Tensor4<{dim4(A, B, C, D, P0)}, {dim4(A, B, C, D, P1)}, {dim4(A, B, C, D, P2)}, {dim4(A, B, C, D, P3)}, T>
+        where
+            [(); permutation4(P0, P1, P2, P3)]: Sized,
+            [(); dim4(A, B, C, D, P0) * dim4(A, B, C, D, P1) * dim4(A, B, C, D, P2) * dim4(A, B, C, D, P3)]: Sized,
+    {
+        let dims = [P0, P1, P2, P3].map(|p| dim4(A, B, C, D, p));
+        Tensor4::from_boxed_slice(gather(dims, |out| {
+            let mut index = [0; 4];
+            for (k, p) in [P0, P1, P2, P3].into_iter().enumerate() {
+                index[p] = out[k];
+            }
+            self.data[offset([A, B, C, D], index)]
+        }))
+    }
+
+    /// The rank-3 tensor at index `i` along `AXIS`, with the other three
+    /// axes in their original order.
+    pub fn select<const AXIS: usize>(&self, i: usize)
+        -> Tensor3<{without4(A, B, C, D, AXIS, 0)}, {without4(A, B, C, D, AXIS, 1)}, {without4(A, B, C, D, AXIS, 2)}, T>
+        where [(); without4(A, B, C, D, AXIS, 0) * without4(A, B, C, D, AXIS, 1) * without4(A, B, C, D, AXIS, 2)]: Sized
+    {
+        assert!(i < dim4(A, B, C, D, AXIS));
+        let dims = [0, 1, 2].map(|k| without4(A, B, C, D, AXIS, k));
+        Tensor3::from_boxed_slice(gather(dims, |rest| {
+            let mut index = [i; 4];
+            for k in 0..3 {
+                index[if k < AXIS { k } else { k + 1 }] = rest[k];
+            }
+            self.data[offset([A, B, C, D], index)]
+        }))
+    }
+
+    /// The `LEN` slices starting at `START` along `AXIS`. A slice reaching
+    /// past the end does not compile.
+    pub fn slice<const AXIS: usize, const START: usize, const LEN: usize>(&self)
+        -> Tensor4<
+            {resized4(A, B, C, D, AXIS, LEN, 0)},
+            {resized4(A, B, C, D, AXIS, LEN, 1)},
+            {resized4(A, B, C, D, AXIS, LEN, 2)},
+            {resized4(A, B, C, D, AXIS, LEN, 3)},
+            T,
+        >
+        where
+            [(); dim4(A, B, C, D, AXIS) - (START + LEN)]: Sized,
+            [(); resized4(A, B, C, D, AXIS, LEN, 0) * resized4(A, B, C, D, AXIS, LEN, 1)
+                * resized4(A, B, C, D, AXIS, LEN, 2) * resized4(A, B, C, D, AXIS, LEN, 3)]: Sized,
+    {
+        let dims = [0, 1, 2, 3].map(|k| resized4(A, B, C, D, AXIS, LEN, k));
+        Tensor4::from_boxed_slice(gather(dims, |mut index| {
+            index[AXIS] += START;
+            self.data[offset([A, B, C, D], index)]
+        }))
+    }
+}
+
+elementwise_ops!(Tensor4<A, B, C, D>, A*B*C*D);
+
+impl<const A: usize, const B: usize, const C: usize, const D: usize, T: Scalar> Index<(usize, usize, usize, usize)> for Tensor4<A, B, C, D, T>
+    where [(); A*B*C*D]: Sized
+{
+    type Output = T;
+
+    fn index(&self, (a, b, c, d): (usize, usize, usize, usize)) -> &Self::Output {
+        &self.data[offset([A, B, C, D], [a, b, c, d])]
+    }
+}
+
+impl<const A: usize, const B: usize, const C: usize, const D: usize, T: Scalar> IndexMut<(usize, usize, usize, usize)> for Tensor4<A, B, C, D, T>
+    where [(); A*B*C*D]: Sized
+{
+    fn index_mut(&mut self, (a, b, c, d): (usize, usize, usize, usize)) -> &mut Self::Output {
+        &mut self.data[offset([A, B, C, D], [a, b, c, d])]
+    }
+}
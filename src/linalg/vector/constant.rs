@@ -1,270 +1,268 @@
 use std::ops::{Add, AddAssign, Index, Mul, Sub};
 
-use crate::linalg::matrix::DenseMatrix;
+use crate::linalg::Scalar;
+use crate::linalg::matrix::{ConstantMatrix, DenseMatrix, SparseMatrix, ZeroMatrix};
 
 use super::{DenseVector, OneHotVector, SparseVector, ZeroVector};
 use super::traits::{CanDotProduct, CanAppend, CanMap, CanOuterProduct};
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct ConstantVector<const D: usize>(
-    pub(super) f32,
+pub struct ConstantVector<const D: usize, T: Scalar = f32>(
+    pub(crate) T,
 );
 
-impl<const D: usize> ConstantVector<D> {
-    pub(super) fn sum(&self) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> ConstantVector<D, T> {
+    pub(super) fn sum(&self) -> T {
+        T::from_usize(D) * self.0
     }
 
-    pub(super) fn sum_of_squares(&self) -> f32 {
-        todo!()
+    pub(super) fn sum_of_squares(&self) -> T {
+        T::from_usize(D) * self.0 * self.0
     }
 }
 
-impl<const D: usize> CanAppend for ConstantVector<D> where [(); D+1]: Sized {
-    type Output = DenseVector<{D+1}>;
-    fn append(&self, _extra_val: f32) -> Self::Output {
-        todo!()
+impl<const D: usize, T: Scalar> CanAppend<T> for ConstantVector<D, T> where [(); D+1]: Sized {
+    type Output = DenseVector<{D+1}, T>;
+    fn append(&self, extra_val: T) -> Self::Output {
+        DenseVector::from_fun(|i| if i < D { self.0 } else { extra_val })
     }
 }
 
-impl<const D: usize> CanMap for ConstantVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> CanMap<T> for ConstantVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn map(&self, _f: impl Fn(f32) -> f32) -> Self::Output {
-        todo!()
+    fn map(&self, f: impl Fn(T) -> T) -> Self::Output {
+        let x = f(self.0);
+        DenseVector::from_fun(|_| x)
     }
 }
 
 //////////////////////////////
-/// CONSTANT VEC ADD IMPLS ///
+//  CONSTANT VEC ADD IMPLS  //
 //////////////////////////////
 
-impl<const D: usize> Add<f32> for &ConstantVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Add<T> for &ConstantVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn add(self, rhs: T) -> Self::Output {
+        ConstantVector(self.0 + rhs)
     }
 }
 
-impl<const D: usize> Add<&ConstantVector<D>> for &ConstantVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Add<&ConstantVector<D, T>> for &ConstantVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn add(self, _rhs: &ConstantVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantVector<D, T>) -> Self::Output {
+        ConstantVector(self.0 + rhs.0)
     }
 }
 
-impl<const D: usize> Add<&DenseVector<D>> for &ConstantVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&DenseVector<D, T>> for &ConstantVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &DenseVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DenseVector<D, T>) -> Self::Output {
+        rhs + self.0
     }
 }
 
-impl<const D: usize> Add<&OneHotVector<D>> for &ConstantVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&OneHotVector<D, T>> for &ConstantVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &OneHotVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &OneHotVector<D, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const D: usize> Add<&SparseVector<D>> for &ConstantVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&SparseVector<D, T>> for &ConstantVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &SparseVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &SparseVector<D, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const D: usize> Add<&ZeroVector<D>> for &ConstantVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Add<&ZeroVector<D, T>> for &ConstantVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn add(self, _rhs: &ZeroVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroVector<D, T>) -> Self::Output {
+        self.clone()
     }
 }
 
-impl<const D: usize> AddAssign<&ConstantVector<D>> for ConstantVector<D> {
-    fn add_assign(&mut self, rhs: &ConstantVector<D>) {
+impl<const D: usize, T: Scalar> AddAssign<&ConstantVector<D, T>> for ConstantVector<D, T> {
+    fn add_assign(&mut self, rhs: &ConstantVector<D, T>) {
         self.0 += rhs.0
     }
 }
 
-impl<const D: usize> AddAssign<f32> for ConstantVector<D> {
-    fn add_assign(&mut self, rhs: f32) {
+impl<const D: usize, T: Scalar> AddAssign<T> for ConstantVector<D, T> {
+    fn add_assign(&mut self, rhs: T) {
         self.0 += rhs
     }
 }
 
 //////////////////////////////
-/// CONSTANT VEC SUB IMPLS ///
+//  CONSTANT VEC SUB IMPLS  //
 //////////////////////////////
 
-impl<const D: usize> Sub<f32> for &ConstantVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Sub<T> for &ConstantVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        ConstantVector(self.0 - rhs)
     }
 }
 
-impl<const D: usize> Sub<&ConstantVector<D>> for &ConstantVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Sub<&ConstantVector<D, T>> for &ConstantVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn sub(self, _rhs: &ConstantVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &ConstantVector<D, T>) -> Self::Output {
+        ConstantVector(self.0 - rhs.0)
     }
 }
 
-impl<const D: usize> Sub<&DenseVector<D>> for &ConstantVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&DenseVector<D, T>> for &ConstantVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &DenseVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DenseVector<D, T>) -> Self::Output {
+        &(rhs * -T::one()) + self.0
     }
 }
 
-impl<const D: usize> Sub<&OneHotVector<D>> for &ConstantVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&OneHotVector<D, T>> for &ConstantVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &OneHotVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &OneHotVector<D, T>) -> Self::Output {
+        DenseVector::from_fun(|i| self.0 - rhs[i])
     }
 }
 
-impl<const D: usize> Sub<&SparseVector<D>> for &ConstantVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&SparseVector<D, T>> for &ConstantVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &SparseVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseVector<D, T>) -> Self::Output {
+        &(rhs * -T::one()) + self.0
     }
 }
 
-impl<const D: usize> Sub<&ZeroVector<D>> for &ConstantVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Sub<&ZeroVector<D, T>> for &ConstantVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn sub(self, _rhs: &ZeroVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroVector<D, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 //////////////////////////////////////
-/// CONSTANT VEC DOT PRODUCT IMPLS ///
+//  CONSTANT VEC DOT PRODUCT IMPLS  //
 //////////////////////////////////////
 
-impl<const D: usize> CanDotProduct<&ConstantVector<D>> for &ConstantVector<D> {
-    fn dot(&self, _other: &ConstantVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&ConstantVector<D, T>, T> for &ConstantVector<D, T> {
+    fn dot(&self, other: &ConstantVector<D, T>) -> T {
+        T::from_usize(D) * self.0 * other.0
     }
 }
 
-impl<const D: usize> CanDotProduct<&DenseVector<D>> for &ConstantVector<D> {
-    fn dot(&self, _other: &DenseVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&DenseVector<D, T>, T> for &ConstantVector<D, T> {
+    fn dot(&self, other: &DenseVector<D, T>) -> T {
+        self.0 * other.sum()
     }
 }
 
-impl<const D: usize> CanDotProduct<&OneHotVector<D>> for &ConstantVector<D> {
-    fn dot(&self, _other: &OneHotVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&OneHotVector<D, T>, T> for &ConstantVector<D, T> {
+    fn dot(&self, other: &OneHotVector<D, T>) -> T {
+        self.0 * other.one
     }
 }
 
-impl<const D: usize> CanDotProduct<&SparseVector<D>> for &ConstantVector<D> {
-    fn dot(&self, _other: &SparseVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&SparseVector<D, T>, T> for &ConstantVector<D, T> {
+    fn dot(&self, other: &SparseVector<D, T>) -> T {
+        self.0 * other.sum()
     }
 }
 
-impl<const D: usize> CanDotProduct<&ZeroVector<D>> for &ConstantVector<D> {
-    fn dot(&self, _other: &ZeroVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&ZeroVector<D, T>, T> for &ConstantVector<D, T> {
+    fn dot(&self, _other: &ZeroVector<D, T>) -> T {
+        T::zero()
     }
 }
 
-//////////////////////////////////////
-/// CONSTANT VEC DOT PRODUCT IMPLS ///
-//////////////////////////////////////
+////////////////////////////////////////
+//  CONSTANT VEC OUTER PRODUCT IMPLS  //
+////////////////////////////////////////
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&ConstantVector<D2>> for ConstantVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&ConstantVector<D2, T>> for ConstantVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = ConstantMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &ConstantVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &ConstantVector<D2, T>) -> Self::Output {
+        ConstantMatrix(self.0 * other.0)
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&DenseVector<D2>> for ConstantVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&DenseVector<D2, T>> for ConstantVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = DenseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &DenseVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &DenseVector<D2, T>) -> Self::Output {
+        // Every row is `other` scaled by the constant.
+        DenseVector::<D, T>::from_fun(|_| self.0).outer(other)
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&OneHotVector<D2>> for ConstantVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&OneHotVector<D2, T>> for ConstantVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &OneHotVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &OneHotVector<D2, T>) -> Self::Output {
+        SparseMatrix::from_triplets((0..D).map(|r| (r, other.index, self.0 * other.one)))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&SparseVector<D2>> for ConstantVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&SparseVector<D2, T>> for ConstantVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &SparseVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &SparseVector<D2, T>) -> Self::Output {
+        SparseMatrix::from_triplets(other.nonzeros()
+            .flat_map(|(c, y)| (0..D).map(move |r| (r, c, self.0 * y))))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&ZeroVector<D2>> for ConstantVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&ZeroVector<D2, T>> for ConstantVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = ZeroMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &ZeroVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, _other: &ZeroVector<D2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
 ////////////////////////////////
-/// CONSTANT VEC ARITH IMPLS ///
+//  CONSTANT VEC ARITH IMPLS  //
 ////////////////////////////////
 
-impl<const D: usize> Mul<f32> for &ConstantVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Mul<T> for &ConstantVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn mul(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: T) -> Self::Output {
+        ConstantVector(self.0 * rhs)
     }
 }
 
 //////////////////////////////////
-/// CONSTANT VEC UTILITY IMPLS ///
+//  CONSTANT VEC UTILITY IMPLS  //
 //////////////////////////////////
 
-impl<const D: usize> Index<usize> for ConstantVector<D> {
-    type Output = f32;
+impl<const D: usize, T: Scalar> Index<usize> for ConstantVector<D, T> {
+    type Output = T;
 
     fn index(&self, _index: usize) -> &Self::Output {
         &self.0
     }
 }
-
-impl<const D: usize> From<ConstantVector<D>> for f32 {
-    fn from(v: ConstantVector<D>) -> Self {
-        v.0
-    }
-}
\ No newline at end of file
@@ -1,25 +1,26 @@
 use std::{marker::PhantomData, ops::{Add, AddAssign, Index, IndexMut, Mul, Sub}};
 
-use crate::linalg::matrix::DenseMatrix;
+use crate::linalg::{kernels, Scalar};
+use crate::linalg::matrix::{DenseMatrix, SparseMatrix, ZeroMatrix};
 
 use super::{ConstantVector, OneHotVector, SparseVector, ZeroVector};
-use super::traits::{CanDotProduct, CanAppend, CanMap, CanOuterProduct};
+use super::traits::{CanDotProduct, CanAppend, CanMap, CanOuterProduct, CanStackVertically};
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct DenseVector<const D: usize> {
-    pub(crate) data: Box<[f32]>,
-    pub(crate) size_marker: PhantomData<[f32; D]>,
+pub struct DenseVector<const D: usize, T: Scalar = f32> {
+    pub(crate) data: Box<[T]>,
+    pub(crate) size_marker: PhantomData<[T; D]>,
 }
 
-impl<const D: usize> DenseVector<D> {
-    pub(crate) fn from_arr(arr: [f32; D]) -> Self {
+impl<const D: usize, T: Scalar> DenseVector<D, T> {
+    pub(crate) fn from_arr(arr: [T; D]) -> Self {
         Self {
             data: Box::new(arr),
             size_marker: PhantomData,
         }
     }
 
-    pub(crate) fn from_boxed_slice(slice: Box<[f32]>) -> Self {
+    pub(crate) fn from_boxed_slice(slice: Box<[T]>) -> Self {
         assert_eq!(slice.len(), D);
         Self {
             data: slice,
@@ -27,317 +28,323 @@ impl<const D: usize> DenseVector<D> {
         }
     }
 
-    pub fn from_fun(f: impl Fn(usize) -> f32) -> Self {
+    pub fn from_fun(f: impl Fn(usize) -> T) -> Self {
         let mut v = Vec::with_capacity(D);
         v.extend((0..D).map(f));
         DenseVector::from_boxed_slice(v.into_boxed_slice())
     }
 
-    pub(super) fn sum(&self) -> f32 {
-        self.data.iter().sum()
+    /// Allocates storage and lets `kernel` fill it in.
+    fn from_kernel(kernel: impl FnOnce(&mut [T])) -> Self {
+        let mut data = vec![T::zero(); D].into_boxed_slice();
+        kernel(&mut data);
+        DenseVector::from_boxed_slice(data)
     }
 
-    pub(super) fn sum_of_squares(&self) -> f32 {
-        self.data.iter().copied().map(|n| <f32>::powi(n, 2)).sum()
+    pub(super) fn hadamard(&self, other: &DenseVector<D, T>) -> Self {
+        DenseVector::from_kernel(|out| kernels::mul(&self.data, &other.data, out))
+    }
+
+    pub(super) fn sum(&self) -> T {
+        kernels::sum(&self.data)
+    }
+
+    pub(super) fn sum_of_squares(&self) -> T {
+        kernels::sum_of_squares(&self.data)
     }
 }
 
-impl<const D: usize> CanAppend for DenseVector<D> where [(); D+1]: Sized {
-    type Output = DenseVector<{D+1}>;
-    fn append(&self, _extra_val: f32) -> Self::Output {
-        todo!()
+impl<const D: usize, T: Scalar> CanAppend<T> for DenseVector<D, T> where [(); D+1]: Sized {
+    type Output = DenseVector<{D+1}, T>;
+    fn append(&self, extra_val: T) -> Self::Output {
+        DenseVector::from_fun(|i| if i < D { self.data[i] } else { extra_val })
     }
 }
 
-impl<const D: usize> CanMap for DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> CanMap<T> for DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn map(&self, f: impl Fn(f32) -> f32) -> Self::Output {
-        let mapped = self.data.iter().copied().map(f).collect::<Box<[f32]>>();
-        DenseVector {
-            data: mapped,
-            size_marker: PhantomData,
-        }
+    fn map(&self, f: impl Fn(T) -> T) -> Self::Output {
+        DenseVector::from_kernel(|out| kernels::map(&self.data, f, out))
     }
 }
 
 ///////////////////////////
-/// DENSE VEC ADD IMPLS ///
+//  DENSE VEC ADD IMPLS  //
 ///////////////////////////
 
-impl<const D: usize> Add<f32> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<T> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn add(self, rhs: T) -> Self::Output {
+        DenseVector::from_kernel(|out| kernels::offset(&self.data, rhs, out))
     }
 }
 
-impl<const D: usize> Add<&ConstantVector<D>> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&ConstantVector<D, T>> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &ConstantVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantVector<D, T>) -> Self::Output {
+        self + rhs.0
     }
 }
 
-impl<const D: usize> Add<&DenseVector<D>> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&DenseVector<D, T>> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, rhs: &DenseVector<D>) -> Self::Output {
-        let f = |(a, b)| a + b;
-        let us = self.data.iter().copied();
-        let them = rhs.data.iter().copied();
-        let mapped = us.zip(them).map(f).collect::<Box<[f32]>>();
-        DenseVector {
-            data: mapped,
-            size_marker: PhantomData,
-        }
+    fn add(self, rhs: &DenseVector<D, T>) -> Self::Output {
+        DenseVector::from_kernel(|out| kernels::add(&self.data, &rhs.data, out))
     }
 }
 
-impl<const D: usize> Add<&OneHotVector<D>> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&OneHotVector<D, T>> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &OneHotVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &OneHotVector<D, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const D: usize> Add<&SparseVector<D>> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&SparseVector<D, T>> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &SparseVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &SparseVector<D, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const D: usize> Add<&ZeroVector<D>> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&ZeroVector<D, T>> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &ZeroVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroVector<D, T>) -> Self::Output {
+        self.clone()
     }
 }
 
-impl<const D: usize> AddAssign<&ConstantVector<D>> for DenseVector<D> {
-    fn add_assign(&mut self, _rhs: &ConstantVector<D>) {
-        todo!()
+impl<const D: usize, T: Scalar> AddAssign<&ConstantVector<D, T>> for DenseVector<D, T> {
+    fn add_assign(&mut self, rhs: &ConstantVector<D, T>) {
+        *self += rhs.0;
     }
 }
 
-impl<const D: usize> AddAssign<&DenseVector<D>> for DenseVector<D> {
-    fn add_assign(&mut self, _rhs: &DenseVector<D>) {
-        todo!()
+impl<const D: usize, T: Scalar> AddAssign<&DenseVector<D, T>> for DenseVector<D, T> {
+    fn add_assign(&mut self, rhs: &DenseVector<D, T>) {
+        kernels::add_assign(&mut self.data, &rhs.data);
     }
 }
 
-impl<const D: usize> AddAssign<&OneHotVector<D>> for DenseVector<D> {
-    fn add_assign(&mut self, _rhs: &OneHotVector<D>) {
-        todo!()
+impl<const D: usize, T: Scalar> AddAssign<&OneHotVector<D, T>> for DenseVector<D, T> {
+    fn add_assign(&mut self, rhs: &OneHotVector<D, T>) {
+        self.data[rhs.index] += rhs.one;
     }
 }
 
-impl<const D: usize> AddAssign<&SparseVector<D>> for DenseVector<D> {
-    fn add_assign(&mut self, _rhs: &SparseVector<D>) {
-        todo!()
+impl<const D: usize, T: Scalar> AddAssign<&SparseVector<D, T>> for DenseVector<D, T> {
+    fn add_assign(&mut self, rhs: &SparseVector<D, T>) {
+        for (&i, &x) in &rhs.elems {
+            self.data[i] += x;
+        }
     }
 }
 
-impl<const D: usize> AddAssign<f32> for DenseVector<D> {
-    fn add_assign(&mut self, _rhs: f32) {
-        todo!()
+impl<const D: usize, T: Scalar> AddAssign<T> for DenseVector<D, T> {
+    fn add_assign(&mut self, rhs: T) {
+        kernels::offset_assign(&mut self.data, rhs);
     }
 }
 
 ///////////////////////////
-/// DENSE VEC SUB IMPLS ///
+//  DENSE VEC SUB IMPLS  //
 ///////////////////////////
 
-impl<const D: usize> Sub<f32> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<T> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        self + (-rhs)
     }
 }
 
-impl<const D: usize> Sub<&ConstantVector<D>> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&ConstantVector<D, T>> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &ConstantVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &ConstantVector<D, T>) -> Self::Output {
+        self + (-rhs.0)
     }
 }
 
-impl<const D: usize> Sub<&DenseVector<D>> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&DenseVector<D, T>> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, rhs: &DenseVector<D>) -> Self::Output {
-        let f = |(a, b)| a - b;
-        let us = self.data.iter().copied();
-        let them = rhs.data.iter().copied();
-        let mapped = us.zip(them).map(f).collect::<Box<[f32]>>();
-        DenseVector {
-            data: mapped,
-            size_marker: PhantomData,
-        }
+    fn sub(self, rhs: &DenseVector<D, T>) -> Self::Output {
+        DenseVector::from_kernel(|out| kernels::sub(&self.data, &rhs.data, out))
     }
 }
 
-impl<const D: usize> Sub<&OneHotVector<D>> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&OneHotVector<D, T>> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &OneHotVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &OneHotVector<D, T>) -> Self::Output {
+        let mut diff = self.clone();
+        diff.data[rhs.index] -= rhs.one;
+        diff
     }
 }
 
-impl<const D: usize> Sub<&SparseVector<D>> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&SparseVector<D, T>> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &SparseVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseVector<D, T>) -> Self::Output {
+        let mut diff = self.clone();
+        for (&i, &x) in &rhs.elems {
+            diff.data[i] -= x;
+        }
+        diff
     }
 }
 
-impl<const D: usize> Sub<&ZeroVector<D>> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&ZeroVector<D, T>> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &ZeroVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroVector<D, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 ///////////////////////////////////
-/// DENSE VEC DOT PRODUCT IMPLS ///
+//  DENSE VEC DOT PRODUCT IMPLS  //
 ///////////////////////////////////
 
-impl<const D: usize> CanDotProduct<&ConstantVector<D>> for &DenseVector<D> {
-    fn dot(&self, _other: &ConstantVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&ConstantVector<D, T>, T> for &DenseVector<D, T> {
+    fn dot(&self, other: &ConstantVector<D, T>) -> T {
+        self.sum() * other.0
     }
 }
 
-impl<const D: usize> CanDotProduct<&DenseVector<D>> for &DenseVector<D> {
-    fn dot(&self, _other: &DenseVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&DenseVector<D, T>, T> for &DenseVector<D, T> {
+    fn dot(&self, other: &DenseVector<D, T>) -> T {
+        kernels::dot(&self.data, &other.data)
     }
 }
 
-impl<const D: usize> CanDotProduct<&OneHotVector<D>> for &DenseVector<D> {
-    fn dot(&self, _other: &OneHotVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&OneHotVector<D, T>, T> for &DenseVector<D, T> {
+    fn dot(&self, other: &OneHotVector<D, T>) -> T {
+        self.data[other.index] * other.one
     }
 }
 
-impl<const D: usize> CanDotProduct<&SparseVector<D>> for &DenseVector<D> {
-    fn dot(&self, _other: &SparseVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&SparseVector<D, T>, T> for &DenseVector<D, T> {
+    fn dot(&self, other: &SparseVector<D, T>) -> T {
+        (&other).dot(*self)
     }
 }
 
-impl<const D: usize> CanDotProduct<&ZeroVector<D>> for &DenseVector<D> {
-    fn dot(&self, _other: &ZeroVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&ZeroVector<D, T>, T> for &DenseVector<D, T> {
+    fn dot(&self, _other: &ZeroVector<D, T>) -> T {
+        T::zero()
     }
 }
 
-//////////////////////////////////////
-/// CONSTANT VEC DOT PRODUCT IMPLS ///
-//////////////////////////////////////
+/////////////////////////////////////
+//  DENSE VEC OUTER PRODUCT IMPLS  //
+/////////////////////////////////////
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&ConstantVector<D2>> for DenseVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&ConstantVector<D2, T>> for DenseVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = DenseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &ConstantVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &ConstantVector<D2, T>) -> Self::Output {
+        // Every column is `self` scaled by the constant.
+        self.outer(&DenseVector::<D2, T>::from_fun(|_| other.0))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&DenseVector<D2>> for DenseVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&DenseVector<D2, T>> for DenseVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
-
-    fn outer(&self, other: &DenseVector<D2>) -> Self::Output {
-        let mut container = Vec::with_capacity(D*D2);
-        for col in 0..D2 {
-            let scalar = other[col];
-            let x = self.data.into_iter().map(|x| x * scalar);
-            container.extend(x);
-        }
-        DenseMatrix::from_boxed_slice(container.into_boxed_slice())
+    type Output = DenseMatrix<D, D2, T>;
+
+    fn outer(&self, other: &DenseVector<D2, T>) -> Self::Output {
+        let mut container = vec![T::zero(); D*D2].into_boxed_slice();
+        kernels::outer(&self.data, &other.data, &mut container);
+        DenseMatrix::from_boxed_slice(container)
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&OneHotVector<D2>> for DenseVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&OneHotVector<D2, T>> for DenseVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &OneHotVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &OneHotVector<D2, T>) -> Self::Output {
+        // A single nonzero column, e.g. the weight gradient for a one-hot input.
+        SparseMatrix::from_triplets(self.data.iter().enumerate()
+            .map(|(r, &x)| (r, other.index, x * other.one)))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&SparseVector<D2>> for DenseVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&SparseVector<D2, T>> for DenseVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &SparseVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &SparseVector<D2, T>) -> Self::Output {
+        // Only the columns picked out by the nonzeros of `other` are filled.
+        SparseMatrix::from_triplets(other.nonzeros()
+            .flat_map(|(c, y)| self.data.iter().enumerate().map(move |(r, &x)| (r, c, x * y))))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&ZeroVector<D2>> for DenseVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&ZeroVector<D2, T>> for DenseVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
-    
-    fn outer(&self, _other: &ZeroVector<D2>) -> Self::Output {
-        todo!()
+    type Output = ZeroMatrix<D, D2, T>;
+
+    fn outer(&self, _other: &ZeroVector<D2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
 /////////////////////////////
-/// DENSE VEC ARITH IMPLS ///
+//  DENSE VEC ARITH IMPLS  //
 /////////////////////////////
 
-impl<const D: usize> Mul<f32> for &DenseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Mul<T> for &DenseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn mul(self, rhs: f32) -> Self::Output {
-        let f = |x| x * rhs;
-        let us = self.data.iter().copied();
-        let mapped = us.map(f);
+    fn mul(self, rhs: T) -> Self::Output {
+        DenseVector::from_kernel(|out| kernels::scale(&self.data, rhs, out))
+    }
+}
 
-        let mut container = Vec::with_capacity(D);
-        container.extend(mapped);
+/////////////////////////////
+//  DENSE VEC STACK IMPLS  //
+/////////////////////////////
 
-        DenseVector {
-            data: container.into_boxed_slice(),
-            size_marker: PhantomData,
-        }
+impl<const D: usize, const M: usize, T: Scalar> CanStackVertically<&DenseVector<M, T>> for DenseVector<D, T>
+    where [(); D+M]: Sized
+{
+    type Output = DenseVector<{D+M}, T>;
+
+    fn vstack(&self, other: &DenseVector<M, T>) -> Self::Output {
+        DenseVector::from_boxed_slice([&self.data[..], &other.data[..]].concat().into_boxed_slice())
     }
 }
 
 ///////////////////////////////
-/// DENSE VEC UTILITY IMPLS ///
+//  DENSE VEC UTILITY IMPLS  //
 ///////////////////////////////
 
-impl<const D: usize> Index<usize> for DenseVector<D> {
-    type Output = f32;
+impl<const D: usize, T: Scalar> Index<usize> for DenseVector<D, T> {
+    type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
         &self.data[index]
     }
 }
 
-impl<const D: usize> IndexMut<usize> for DenseVector<D> {
+impl<const D: usize, T: Scalar> IndexMut<usize> for DenseVector<D, T> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         &mut self.data[index]
     }
@@ -8,13 +8,17 @@ use super::OldMatrixDoNotUse;
 #[allow(unused_imports)] pub(super) use onehot::OneHotVector;
 #[allow(unused_imports)] pub(super) use sparse::SparseVector;
 #[allow(unused_imports)] pub use wrapper::{Vector, VectorWrapperIterator};
+#[allow(unused_imports)] pub(super) use view::DenseVectorView;
+#[allow(unused_imports)] pub use view::VectorView;
 #[allow(unused_imports)] pub(super) use zero::ZeroVector;
 
 mod constant;
 mod dense;
 mod onehot;
+mod reduce;
 mod sparse;
 pub mod traits;
+mod view;
 mod wrapper;
 mod zero;
 
@@ -38,8 +42,8 @@ impl<const N: usize> OldVectorDoNotUse<N> {
 
     pub fn from_fun(f: impl Fn() -> f32) -> OldVectorDoNotUse<N> {
         let mut arr = [0f32; N];
-        for i in 0..N {
-            arr[i] = f();
+        for x in arr.iter_mut() {
+            *x = f();
         }
         Self(arr)
     }
@@ -59,7 +63,7 @@ impl<const N: usize> OldVectorDoNotUse<N> {
         Vectorizes `f` over the elements of `self`.
      */
     pub fn map(&self, f: impl Fn(f32) -> f32) -> Self {
-        Self(self.0.clone().map(f))
+        Self(self.0.map(f))
     }
 
     pub fn sum(&self) -> f32 {
@@ -93,7 +97,7 @@ impl<const N: usize> OldVectorDoNotUse<N> {
 }
 
 /////////////////////////
-/// &Vector + &Vector ///
+//  &Vector + &Vector  //
 /////////////////////////
 
 impl<const N: usize> Add for &OldVectorDoNotUse<N> {
@@ -111,7 +115,7 @@ impl<const N: usize> Add for &OldVectorDoNotUse<N> {
 }
 
 /////////////////////
-/// f32 + &Vector ///
+//  f32 + &Vector  //
 /////////////////////
 
 impl<const N: usize> Add<&OldVectorDoNotUse<N>> for f32 {
@@ -127,7 +131,7 @@ impl<const N: usize> Add<&OldVectorDoNotUse<N>> for f32 {
 }
 
 /////////////////////
-/// &Vector + f32 ///
+//  &Vector + f32  //
 /////////////////////
 
 impl<const N: usize> Add<f32> for &OldVectorDoNotUse<N> {
@@ -143,7 +147,7 @@ impl<const N: usize> Add<f32> for &OldVectorDoNotUse<N> {
 }
 
 /////////////////////////
-/// &Vector - &Vector ///
+//  &Vector - &Vector  //
 /////////////////////////
 
 impl<const N: usize> Sub for &OldVectorDoNotUse<N> {
@@ -161,7 +165,7 @@ impl<const N: usize> Sub for &OldVectorDoNotUse<N> {
 }
 
 /////////////////////
-/// &Vector - f32 ///
+//  &Vector - f32  //
 /////////////////////
 
 impl<const N: usize> Sub<f32> for &OldVectorDoNotUse<N> {
@@ -173,7 +177,7 @@ impl<const N: usize> Sub<f32> for &OldVectorDoNotUse<N> {
 }
 
 /////////////////////////
-/// &Vector * &Vector ///
+//  &Vector * &Vector  //
 /////////////////////////
 
 impl<const N: usize> Mul for &OldVectorDoNotUse<N> {
@@ -185,7 +189,7 @@ impl<const N: usize> Mul for &OldVectorDoNotUse<N> {
 }
 
 /////////////////////
-/// f32 * &Vector ///
+//  f32 * &Vector  //
 /////////////////////
 
 impl<const N: usize> Mul<&OldVectorDoNotUse<N>> for f32 {
@@ -201,7 +205,7 @@ impl<const N: usize> Mul<&OldVectorDoNotUse<N>> for f32 {
 }
 
 /////////////////////
-/// &Vector * f32 ///
+//  &Vector * f32  //
 /////////////////////
 
 impl<const N: usize> Mul<f32> for &OldVectorDoNotUse<N> {
@@ -217,7 +221,7 @@ impl<const N: usize> Mul<f32> for &OldVectorDoNotUse<N> {
 }
 
 ////////////////////
-/// -1 * &Vector ///
+//  -1 * &Vector  //
 ////////////////////
 
 impl<const N: usize> Neg for &OldVectorDoNotUse<N> {
@@ -229,7 +233,7 @@ impl<const N: usize> Neg for &OldVectorDoNotUse<N> {
 }
 
 /////////////////////
-/// UTILITY IMPLS ///
+//  UTILITY IMPLS  //
 /////////////////////
 
 impl<const N: usize> Display for OldVectorDoNotUse<N> {
@@ -1,264 +1,317 @@
 use std::ops::{Add, Index, Mul, Sub};
 
-use crate::linalg::matrix::DenseMatrix;
+use crate::linalg::Scalar;
+use crate::linalg::matrix::{SparseMatrix, ZeroMatrix};
 
 use super::{ConstantVector, DenseVector, SparseVector, ZeroVector};
 use super::traits::{CanDotProduct, CanAppend, CanMap, CanOuterProduct};
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct OneHotVector<const D: usize> {
-    pub(super) zero: f32,      // Index impls must return a ref to a sentinel value
-    pub(super) one: f32,       // as above
-    pub(super) index: usize,   // the non-zero index
+pub struct OneHotVector<const D: usize, T: Scalar = f32> {
+    pub(crate) zero: T,      // Index impls must return a ref to a sentinel value
+    pub(crate) one: T,       // as above
+    pub(crate) index: usize,   // the non-zero index
 }
 
-impl<const D: usize> OneHotVector<D> {
+impl<const D: usize, T: Scalar> OneHotVector<D, T> {
     // constructor
     pub fn at_index(index: usize) -> Self {
         assert!(index < D);
         OneHotVector {
-            zero: 0f32,
-            one: 1f32,
+            zero: T::zero(),
+            one: T::one(),
             index,
         }
     }
 
-    pub(super) fn sum(&self) -> f32 {
-        1f32
+    pub(super) fn sum(&self) -> T {
+        self.one
     }
 
-    pub(super) fn sum_of_squares(&self) -> f32 {
-        1f32
+    pub(super) fn sum_of_squares(&self) -> T {
+        self.one * self.one
     }
 }
 
-impl<const D: usize> CanAppend for OneHotVector<D> where [(); D+1]: Sized {
-    type Output = SparseVector<{D+1}>;
-    fn append(&self, _extra_val: f32) -> Self::Output {
-        todo!()
+impl<const D: usize, T: Scalar> CanAppend<T> for OneHotVector<D, T> where [(); D+1]: Sized {
+    type Output = SparseVector<{D+1}, T>;
+    fn append(&self, extra_val: T) -> Self::Output {
+        SparseVector::from_pairs([(self.index, self.one), (D, extra_val)])
     }
 }
 
-impl<const D: usize> CanMap for OneHotVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> CanMap<T> for OneHotVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn map(&self, _f: impl Fn(f32) -> f32) -> Self::Output {
-        todo!()
+    fn map(&self, f: impl Fn(T) -> T) -> Self::Output {
+        // Only two distinct values to evaluate `f` at.
+        let (zero, one) = (f(self.zero), f(self.one));
+        DenseVector::from_fun(|i| if i == self.index { one } else { zero })
     }
 }
 
 ////////////////////////////
-/// ONEHOT VEC ADD IMPLS ///
+//  ONEHOT VEC ADD IMPLS  //
 ////////////////////////////
 
-impl<const D: usize> Add<f32> for &OneHotVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<T> for &OneHotVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn add(self, rhs: T) -> Self::Output {
+        DenseVector::from_fun(|i| self[i] + rhs)
     }
 }
 
-impl<const D: usize> Add<&ConstantVector<D>> for &OneHotVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&ConstantVector<D, T>> for &OneHotVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &ConstantVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantVector<D, T>) -> Self::Output {
+        self + rhs.0
     }
 }
 
-impl<const D: usize> Add<&DenseVector<D>> for &OneHotVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&DenseVector<D, T>> for &OneHotVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &DenseVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DenseVector<D, T>) -> Self::Output {
+        let mut sum = rhs.clone();
+        sum[self.index] += self.one;
+        sum
     }
 }
 
-impl<const D: usize> Add<&OneHotVector<D>> for &OneHotVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Add<&OneHotVector<D, T>> for &OneHotVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn add(self, _rhs: &OneHotVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &OneHotVector<D, T>) -> Self::Output {
+        SparseVector::from_pairs([(self.index, self.one), (rhs.index, rhs.one)])
     }
 }
 
-impl<const D: usize> Add<&SparseVector<D>> for &OneHotVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Add<&SparseVector<D, T>> for &OneHotVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn add(self, _rhs: &SparseVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &SparseVector<D, T>) -> Self::Output {
+        rhs + self
     }
 }
 
-impl<const D: usize> Add<&ZeroVector<D>> for &OneHotVector<D> {
-    type Output = OneHotVector<D>;
+impl<const D: usize, T: Scalar> Add<&ZeroVector<D, T>> for &OneHotVector<D, T> {
+    type Output = OneHotVector<D, T>;
 
-    fn add(self, _rhs: &ZeroVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroVector<D, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 ////////////////////////////
-/// ONEHOT VEC SUB IMPLS ///
+//  ONEHOT VEC SUB IMPLS  //
 ////////////////////////////
 
-impl<const D: usize> Sub<f32> for &OneHotVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<T> for &OneHotVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        self + (-rhs)
     }
 }
 
-impl<const D: usize> Sub<&ConstantVector<D>> for &OneHotVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&ConstantVector<D, T>> for &OneHotVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &ConstantVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &ConstantVector<D, T>) -> Self::Output {
+        self + (-rhs.0)
     }
 }
 
-impl<const D: usize> Sub<&DenseVector<D>> for &OneHotVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&DenseVector<D, T>> for &OneHotVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &DenseVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DenseVector<D, T>) -> Self::Output {
+        let mut diff = rhs * -T::one();
+        diff[self.index] += self.one;
+        diff
     }
 }
 
-impl<const D: usize> Sub<&OneHotVector<D>> for &OneHotVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&OneHotVector<D, T>> for &OneHotVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn sub(self, _rhs: &OneHotVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &OneHotVector<D, T>) -> Self::Output {
+        SparseVector::from_pairs([(self.index, self.one), (rhs.index, -rhs.one)])
     }
 }
 
-impl<const D: usize> Sub<&SparseVector<D>> for &OneHotVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&SparseVector<D, T>> for &OneHotVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn sub(self, _rhs: &SparseVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseVector<D, T>) -> Self::Output {
+        let mut diff = rhs * -T::one();
+        diff += self;
+        diff
     }
 }
 
-impl<const D: usize> Sub<&ZeroVector<D>> for &OneHotVector<D> {
-    type Output = OneHotVector<D>;
+impl<const D: usize, T: Scalar> Sub<&ZeroVector<D, T>> for &OneHotVector<D, T> {
+    type Output = OneHotVector<D, T>;
 
-    fn sub(self, _rhs: &ZeroVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroVector<D, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 ////////////////////////////////////
-/// ONEHOT VEC DOT PRODUCT IMPLS ///
+//  ONEHOT VEC DOT PRODUCT IMPLS  //
 ////////////////////////////////////
 
-impl<const D: usize> CanDotProduct<&ConstantVector<D>> for &OneHotVector<D> {
-    fn dot(&self, _other: &ConstantVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&ConstantVector<D, T>, T> for &OneHotVector<D, T> {
+    fn dot(&self, other: &ConstantVector<D, T>) -> T {
+        self.one * other.0
     }
 }
 
-impl<const D: usize> CanDotProduct<&DenseVector<D>> for &OneHotVector<D> {
-    fn dot(&self, _other: &DenseVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&DenseVector<D, T>, T> for &OneHotVector<D, T> {
+    fn dot(&self, other: &DenseVector<D, T>) -> T {
+        self.one * other[self.index]
     }
 }
 
-impl<const D: usize> CanDotProduct<&OneHotVector<D>> for &OneHotVector<D> {
-    fn dot(&self, _other: &OneHotVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&OneHotVector<D, T>, T> for &OneHotVector<D, T> {
+    fn dot(&self, other: &OneHotVector<D, T>) -> T {
+        self.one * other[self.index]
     }
 }
 
-impl<const D: usize> CanDotProduct<&SparseVector<D>> for &OneHotVector<D> {
-    fn dot(&self, _other: &SparseVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&SparseVector<D, T>, T> for &OneHotVector<D, T> {
+    fn dot(&self, other: &SparseVector<D, T>) -> T {
+        self.one * other[self.index]
     }
 }
 
-impl<const D: usize> CanDotProduct<&ZeroVector<D>> for &OneHotVector<D> {
-    fn dot(&self, _other: &ZeroVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&ZeroVector<D, T>, T> for &OneHotVector<D, T> {
+    fn dot(&self, _other: &ZeroVector<D, T>) -> T {
+        T::zero()
     }
 }
 
-////////////////////////////////////////
-/// CONSTANT VEC OUTER PRODUCT IMPLS ///
-////////////////////////////////////////
+//////////////////////////////////////
+//  ONEHOT VEC OUTER PRODUCT IMPLS  //
+//////////////////////////////////////
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&ConstantVector<D2>> for OneHotVector<D>
+// Every outer product with a one-hot left side has a single nonzero row.
+
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&ConstantVector<D2, T>> for OneHotVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &ConstantVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &ConstantVector<D2, T>) -> Self::Output {
+        SparseMatrix::from_triplets((0..D2).map(|c| (self.index, c, self.one * other.0)))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&DenseVector<D2>> for OneHotVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&DenseVector<D2, T>> for OneHotVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &DenseVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &DenseVector<D2, T>) -> Self::Output {
+        SparseMatrix::from_triplets(other.data.iter().enumerate()
+            .map(|(c, &y)| (self.index, c, self.one * y)))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&OneHotVector<D2>> for OneHotVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&OneHotVector<D2, T>> for OneHotVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &OneHotVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &OneHotVector<D2, T>) -> Self::Output {
+        SparseMatrix::from_triplets([(self.index, other.index, self.one * other.one)])
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&SparseVector<D2>> for OneHotVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&SparseVector<D2, T>> for OneHotVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &SparseVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &SparseVector<D2, T>) -> Self::Output {
+        SparseMatrix::from_triplets(other.nonzeros()
+            .map(|(c, y)| (self.index, c, self.one * y)))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&ZeroVector<D2>> for OneHotVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&ZeroVector<D2, T>> for OneHotVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
-    
-    fn outer(&self, _other: &ZeroVector<D2>) -> Self::Output {
-        todo!()
+    type Output = ZeroMatrix<D, D2, T>;
+
+    fn outer(&self, _other: &ZeroVector<D2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
 //////////////////////////////
-/// ONEHOT VEC ARITH IMPLS ///
+//  ONEHOT VEC ARITH IMPLS  //
 //////////////////////////////
 
-impl<const D: usize> Mul<f32> for &OneHotVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Mul<T> for &OneHotVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn mul(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: T) -> Self::Output {
+        SparseVector::from_pairs([(self.index, self.one * rhs)])
     }
 }
 
 ////////////////////////////////
-/// ONEHOT VEC UTILITY IMPLS ///
+//  ONEHOT VEC UTILITY IMPLS  //
 ////////////////////////////////
 
-impl<const D: usize> Index<usize> for OneHotVector<D> {
-    type Output = f32;
+impl<const D: usize, T: Scalar> Index<usize> for OneHotVector<D, T> {
+    type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
         if index == self.index { &self.one } else { &self.zero }
     }
 }
+
+impl<const D: usize, T: Scalar> From<&OneHotVector<D, T>> for DenseVector<D, T> {
+    fn from(one_hot: &OneHotVector<D, T>) -> Self {
+        DenseVector::from_fun(|i| one_hot[i])
+    }
+}
+
+mod tests {
+    #[test]
+    fn one_hot_lookups_and_arithmetic() {
+        use super::{DenseVector, OneHotVector, SparseVector};
+        use crate::linalg::vector::traits::{CanDotProduct, CanMap};
+
+        let e = OneHotVector::<4>::at_index(2);
+        let f = OneHotVector::<4>::at_index(0);
+        let dense = DenseVector::from_arr([1., 2., 3., 4.]);
+
+        assert_eq!((&e).dot(&dense), 3.);
+        assert_eq!((&e).dot(&f), 0.);
+        assert_eq!((&e).dot(&e), 1.);
+        assert_eq!(&e + &dense, DenseVector::from_arr([1., 2., 4., 4.]));
+        assert_eq!(&e - &dense, DenseVector::from_arr([-1., -2., -2., -4.]));
+        assert_eq!(&e - &f, SparseVector::from_pairs([(2, 1.), (0, -1.)]));
+        assert_eq!((&e - &e).elems.len(), 0);
+        assert_eq!(e.map(|x| x + 1.), DenseVector::from_arr([1., 1., 2., 1.]));
+    }
+
+    #[test]
+    fn one_hot_outer_products_are_a_single_line() {
+        use super::{DenseVector, OneHotVector};
+        use crate::linalg::matrix::SparseMatrix;
+        use crate::linalg::vector::traits::CanOuterProduct;
+
+        let e = OneHotVector::<2>::at_index(1);
+        let dense = DenseVector::<3>::from_arr([1., 0., 2.]);
+
+        assert_eq!(dense.outer(&e), SparseMatrix::from_triplets([(0, 1, 1.), (2, 1, 2.)]));
+        assert_eq!(e.outer(&dense), SparseMatrix::from_triplets([(1, 0, 1.), (1, 2, 2.)]));
+    }
+}
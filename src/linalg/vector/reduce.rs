@@ -0,0 +1,94 @@
+use crate::linalg::Scalar;
+
+use super::wrapper::Vector;
+
+/// Reductions beyond `sum` and `sum_of_squares`. The structured flavors only
+/// look at their stored entries, and account for the implicit zeros in bulk.
+impl<const D: usize, T: Scalar> Vector<D, T> {
+    pub fn mean(&self) -> T {
+        self.sum() / T::from_usize(D)
+    }
+
+    /// Population variance, i.e. divided by `D`.
+    pub fn variance(&self) -> T {
+        use Vector as V;
+        let mean = self.mean();
+        match self {
+            V::Constant(_) | V::Zero(_) => T::zero(),
+            V::Dense(v) => v.data.iter().map(|&x| (x - mean) * (x - mean)).sum::<T>() / T::from_usize(D),
+            _ => {
+                let (mut count, mut sum) = (0, T::zero());
+                for (_, x) in self.nonzeros() {
+                    count += 1;
+                    sum += (x - mean) * (x - mean);
+                }
+                (sum + T::from_usize(D - count) * mean * mean) / T::from_usize(D)
+            },
+        }
+    }
+
+    pub fn norm_l1(&self) -> T {
+        match self {
+            Vector::Constant(v) => T::from_usize(D) * v.0.abs(),
+            _ => self.nonzeros().map(|(_, x)| x.abs()).sum(),
+        }
+    }
+
+    pub fn norm_l2(&self) -> T {
+        self.sum_of_squares().sqrt()
+    }
+
+    pub fn norm_inf(&self) -> T {
+        match self {
+            Vector::Constant(v) => v.0.abs(),
+            _ => self.nonzeros().map(|(_, x)| x.abs()).fold(T::zero(), T::max),
+        }
+    }
+
+    pub fn max(&self) -> T {
+        self[self.argmax()]
+    }
+
+    pub fn min(&self) -> T {
+        self[self.argmin()]
+    }
+
+    /// The first index holding the largest entry.
+    pub fn argmax(&self) -> usize {
+        self.arg_extremum(|a, b| a > b)
+    }
+
+    /// The first index holding the smallest entry.
+    pub fn argmin(&self) -> usize {
+        self.arg_extremum(|a, b| a < b)
+    }
+
+    /// The first index whose entry no other entry `wins` against.
+    fn arg_extremum(&self, wins: impl Fn(T, T) -> bool) -> usize {
+        use Vector as V;
+        assert!(D > 0, "empty vector has no extremum");
+        match self {
+            V::Constant(_) | V::Zero(_) => 0,
+            V::OneHot(v) if D == 1 || wins(v.one, T::zero()) => v.index,
+            V::Dense(v) => (1..D).fold(0, |best, i| if wins(v.data[i], v.data[best]) { i } else { best }),
+            _ => {
+                let mut count = 0;
+                let mut best: Option<(usize, T)> = None;
+                for (i, x) in self.nonzeros() {
+                    count += 1;
+                    if best.is_none_or(|(j, y)| wins(x, y) || (x == y && i < j)) {
+                        best = Some((i, x));
+                    }
+                }
+                // All that matters about the implicit zeros is where the first one is.
+                let zero = (count < D).then(|| (0..D).find(|&i| self[i] == T::zero()).unwrap());
+                match (best, zero) {
+                    (Some((i, x)), Some(z)) if wins(T::zero(), x) || (!wins(x, T::zero()) && z < i) => z,
+                    (Some((i, _)), _) => i,
+                    (None, Some(z)) => z,
+                    (None, None) => unreachable!(),
+                }
+            },
+        }
+    }
+}
@@ -2,266 +2,412 @@ use std::{collections::HashMap, ops::{Add, AddAssign, Index, Mul, Sub}};
 
 use ahash::RandomState;
 
-use crate::linalg::matrix::DenseMatrix;
+use crate::linalg::Scalar;
+use crate::linalg::matrix::{SparseMatrix, ZeroMatrix};
 
 use super::{ConstantVector, DenseVector, OneHotVector, ZeroVector};
-use super::traits::{CanDotProduct, CanAppend, CanMap, CanOuterProduct};
+use super::traits::{CanDotProduct, CanAppend, CanMap, CanOuterProduct, CanStackVertically};
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct SparseVector<const D: usize> {
-    pub(super) elems: HashMap<usize, f32, RandomState>,
-    pub(super) zero: f32,
+pub struct SparseVector<const D: usize, T: Scalar = f32> {
+    pub(crate) elems: HashMap<usize, T, RandomState>,
+    pub(crate) zero: T,
 }
 
-impl<const D: usize> SparseVector<D> {
-    pub(super) fn sum(&self) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> SparseVector<D, T> {
+    // constructor
+    /// Repeated indices are summed and explicit zeros dropped.
+    pub(crate) fn from_pairs(pairs: impl IntoIterator<Item = (usize, T)>) -> Self {
+        let mut elems: HashMap<usize, T, RandomState> = HashMap::default();
+        for (i, x) in pairs {
+            assert!(i < D);
+            *elems.entry(i).or_insert(T::zero()) += x;
+        }
+        elems.retain(|_, x| *x != T::zero());
+        SparseVector { elems, zero: T::zero() }
     }
 
-    pub(super) fn sum_of_squares(&self) -> f32 {
-        todo!()
+    /// Iterates over `(index, value)` of the stored entries by increasing index,
+    /// so that anything accumulated from them does not depend on hashing.
+    pub(crate) fn nonzeros(&self) -> impl Iterator<Item = (usize, T)> {
+        let mut entries: Vec<(usize, T)> = self.elems.iter().map(|(&i, &x)| (i, x)).collect();
+        entries.sort_unstable_by_key(|&(i, _)| i);
+        entries.into_iter()
+    }
+
+    /// Adds `x` at index `i`, forgetting the entry if it cancels out.
+    pub(super) fn accumulate(&mut self, i: usize, x: T) {
+        assert!(i < D);
+        let entry = self.elems.entry(i).or_insert(T::zero());
+        *entry += x;
+        if *entry == T::zero() {
+            self.elems.remove(&i);
+        }
+    }
+
+    fn to_dense(&self) -> DenseVector<D, T> {
+        let mut dense = DenseVector::from_fun(|_| T::zero());
+        for (&i, &x) in &self.elems {
+            dense[i] = x;
+        }
+        dense
+    }
+
+    pub(super) fn sum(&self) -> T {
+        self.nonzeros().map(|(_, x)| x).sum()
+    }
+
+    pub(super) fn sum_of_squares(&self) -> T {
+        self.nonzeros().map(|(_, x)| x * x).sum()
     }
 }
 
-impl<const D: usize> CanAppend for SparseVector<D> where [(); D+1]: Sized {
-    type Output = SparseVector<{D+1}>;
-    fn append(&self, _extra_val: f32) -> Self::Output {
-        todo!()
+impl<const D: usize, T: Scalar> CanAppend<T> for SparseVector<D, T> where [(); D+1]: Sized {
+    type Output = SparseVector<{D+1}, T>;
+    fn append(&self, extra_val: T) -> Self::Output {
+        let extra = std::iter::once((D, extra_val));
+        SparseVector::from_pairs(self.elems.iter().map(|(&i, &x)| (i, x)).chain(extra))
     }
 }
 
-impl<const D: usize> CanMap for SparseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> CanMap<T> for SparseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn map(&self, _f: impl Fn(f32) -> f32) -> Self::Output {
-        todo!()
+    fn map(&self, f: impl Fn(T) -> T) -> Self::Output {
+        // `f` need not map zero to zero, so every entry gets visited.
+        DenseVector::from_fun(|i| f(self[i]))
     }
 }
 
 ////////////////////////////
-/// SPARSE VEC ADD IMPLS ///
+//  SPARSE VEC ADD IMPLS  //
 ////////////////////////////
 
-impl<const D: usize> Add<f32> for &SparseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<T> for &SparseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn add(self, rhs: T) -> Self::Output {
+        DenseVector::from_fun(|i| self[i] + rhs)
     }
 }
 
-impl<const D: usize> Add<&ConstantVector<D>> for &SparseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&ConstantVector<D, T>> for &SparseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &ConstantVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantVector<D, T>) -> Self::Output {
+        self + rhs.0
     }
 }
 
-impl<const D: usize> Add<&DenseVector<D>> for &SparseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&DenseVector<D, T>> for &SparseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &DenseVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DenseVector<D, T>) -> Self::Output {
+        let mut sum = rhs.clone();
+        for (&i, &x) in &self.elems {
+            sum[i] += x;
+        }
+        sum
     }
 }
 
-impl<const D: usize> Add<&OneHotVector<D>> for &SparseVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Add<&OneHotVector<D, T>> for &SparseVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn add(self, _rhs: &OneHotVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &OneHotVector<D, T>) -> Self::Output {
+        let mut sum = self.clone();
+        sum += rhs;
+        sum
     }
 }
 
-impl<const D: usize> Add<&SparseVector<D>> for &SparseVector<D> {
-    type Output = SparseVector<D>;
-
-    fn add(self, _rhs: &SparseVector<D>) -> Self::Output {
-        todo!()
+impl<const D: usize, T: Scalar> Add<&SparseVector<D, T>> for &SparseVector<D, T> {
+    type Output = SparseVector<D, T>;
+
+    fn add(self, rhs: &SparseVector<D, T>) -> Self::Output {
+        // Clone the bigger one and merge the smaller one into it.
+        let (mut sum, other) = if self.elems.len() >= rhs.elems.len() {
+            (self.clone(), rhs)
+        } else {
+            (rhs.clone(), self)
+        };
+        sum += other;
+        sum
     }
 }
 
-impl<const D: usize> Add<&ZeroVector<D>> for &SparseVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Add<&ZeroVector<D, T>> for &SparseVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn add(self, _rhs: &ZeroVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroVector<D, T>) -> Self::Output {
+        self.clone()
     }
 }
 
-impl<const D: usize> AddAssign<&OneHotVector<D>> for SparseVector<D> {
-    fn add_assign(&mut self, _rhs: &OneHotVector<D>) {
-        todo!()
+impl<const D: usize, T: Scalar> AddAssign<&OneHotVector<D, T>> for SparseVector<D, T> {
+    fn add_assign(&mut self, rhs: &OneHotVector<D, T>) {
+        self.accumulate(rhs.index, rhs.one);
     }
 }
 
-impl<const D: usize> AddAssign<&SparseVector<D>> for SparseVector<D> {
-    fn add_assign(&mut self, _rhs: &SparseVector<D>) {
-        todo!()
+impl<const D: usize, T: Scalar> AddAssign<&SparseVector<D, T>> for SparseVector<D, T> {
+    fn add_assign(&mut self, rhs: &SparseVector<D, T>) {
+        for (&i, &x) in &rhs.elems {
+            self.accumulate(i, x);
+        }
     }
 }
 
 ////////////////////////////
-/// SPARSE VEC SUB IMPLS ///
+//  SPARSE VEC SUB IMPLS  //
 ////////////////////////////
 
-impl<const D: usize> Sub<f32> for &SparseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<T> for &SparseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        self + (-rhs)
     }
 }
 
-impl<const D: usize> Sub<&ConstantVector<D>> for &SparseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&ConstantVector<D, T>> for &SparseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &ConstantVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &ConstantVector<D, T>) -> Self::Output {
+        self + (-rhs.0)
     }
 }
 
-impl<const D: usize> Sub<&DenseVector<D>> for &SparseVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&DenseVector<D, T>> for &SparseVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &DenseVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DenseVector<D, T>) -> Self::Output {
+        let mut diff = rhs * -T::one();
+        for (&i, &x) in &self.elems {
+            diff[i] += x;
+        }
+        diff
     }
 }
 
-impl<const D: usize> Sub<&OneHotVector<D>> for &SparseVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&OneHotVector<D, T>> for &SparseVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn sub(self, _rhs: &OneHotVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &OneHotVector<D, T>) -> Self::Output {
+        let mut diff = self.clone();
+        diff.accumulate(rhs.index, -rhs.one);
+        diff
     }
 }
 
-impl<const D: usize> Sub<&SparseVector<D>> for &SparseVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&SparseVector<D, T>> for &SparseVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn sub(self, _rhs: &SparseVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseVector<D, T>) -> Self::Output {
+        let mut diff = self.clone();
+        for (&i, &x) in &rhs.elems {
+            diff.accumulate(i, -x);
+        }
+        diff
     }
 }
 
-impl<const D: usize> Sub<&ZeroVector<D>> for &SparseVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&ZeroVector<D, T>> for &SparseVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn sub(self, _rhs: &ZeroVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroVector<D, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 ////////////////////////////////////
-/// SPARSE VEC DOT PRODUCT IMPLS ///
+//  SPARSE VEC DOT PRODUCT IMPLS  //
 ////////////////////////////////////
 
-impl<const D: usize> CanDotProduct<&ConstantVector<D>> for &SparseVector<D> {
-    fn dot(&self, _other: &ConstantVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&ConstantVector<D, T>, T> for &SparseVector<D, T> {
+    fn dot(&self, other: &ConstantVector<D, T>) -> T {
+        self.sum() * other.0
     }
 }
 
-impl<const D: usize> CanDotProduct<&DenseVector<D>> for &SparseVector<D> {
-    fn dot(&self, _other: &DenseVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&DenseVector<D, T>, T> for &SparseVector<D, T> {
+    fn dot(&self, other: &DenseVector<D, T>) -> T {
+        self.nonzeros().map(|(i, x)| x * other[i]).sum()
     }
 }
 
-impl<const D: usize> CanDotProduct<&OneHotVector<D>> for &SparseVector<D> {
-    fn dot(&self, _other: &OneHotVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&OneHotVector<D, T>, T> for &SparseVector<D, T> {
+    fn dot(&self, other: &OneHotVector<D, T>) -> T {
+        self[other.index] * other.one
     }
 }
 
-impl<const D: usize> CanDotProduct<&SparseVector<D>> for &SparseVector<D> {
-    fn dot(&self, _other: &SparseVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&SparseVector<D, T>, T> for &SparseVector<D, T> {
+    fn dot(&self, other: &SparseVector<D, T>) -> T {
+        // Walk the shorter one, look up in the longer one.
+        let this: &SparseVector<D, T> = self;
+        let (short, long) = if this.elems.len() <= other.elems.len() {
+            (this, other)
+        } else {
+            (other, this)
+        };
+        short.nonzeros()
+            .filter_map(|(i, x)| long.elems.get(&i).map(|&y| x * y))
+            .sum()
     }
 }
 
-impl<const D: usize> CanDotProduct<&ZeroVector<D>> for &SparseVector<D> {
-    fn dot(&self, _other: &ZeroVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&ZeroVector<D, T>, T> for &SparseVector<D, T> {
+    fn dot(&self, _other: &ZeroVector<D, T>) -> T {
+        T::zero()
     }
 }
 
 //////////////////////////////////////
-/// CONSTANT VEC DOT PRODUCT IMPLS ///
+//  SPARSE VEC OUTER PRODUCT IMPLS  //
 //////////////////////////////////////
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&ConstantVector<D2>> for SparseVector<D>
+// Only the rows picked out by the nonzeros of `self` can be nonzero,
+// so every outer product with a sparse left side stays sparse.
+
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&ConstantVector<D2, T>> for SparseVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &ConstantVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &ConstantVector<D2, T>) -> Self::Output {
+        SparseMatrix::from_triplets(self.nonzeros()
+            .flat_map(|(r, x)| (0..D2).map(move |c| (r, c, x * other.0))))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&DenseVector<D2>> for SparseVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&DenseVector<D2, T>> for SparseVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &DenseVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &DenseVector<D2, T>) -> Self::Output {
+        SparseMatrix::from_triplets(self.nonzeros()
+            .flat_map(|(r, x)| other.data.iter().enumerate().map(move |(c, &y)| (r, c, x * y))))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&OneHotVector<D2>> for SparseVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&OneHotVector<D2, T>> for SparseVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &OneHotVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &OneHotVector<D2, T>) -> Self::Output {
+        SparseMatrix::from_triplets(self.nonzeros()
+            .map(|(r, x)| (r, other.index, x * other.one)))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&SparseVector<D2>> for SparseVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&SparseVector<D2, T>> for SparseVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = SparseMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &SparseVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, other: &SparseVector<D2, T>) -> Self::Output {
+        let cols: Vec<(usize, T)> = other.nonzeros().collect();
+        SparseMatrix::from_triplets(self.nonzeros()
+            .flat_map(|(r, x)| cols.iter().map(move |&(c, y)| (r, c, x * y))))
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&ZeroVector<D2>> for SparseVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&ZeroVector<D2, T>> for SparseVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
-    
-    fn outer(&self, _other: &ZeroVector<D2>) -> Self::Output {
-        todo!()
+    type Output = ZeroMatrix<D, D2, T>;
+
+    fn outer(&self, _other: &ZeroVector<D2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
 //////////////////////////////
-/// SPARSE VEC ARITH IMPLS ///
+//  SPARSE VEC ARITH IMPLS  //
 //////////////////////////////
 
-impl<const D: usize> Mul<f32> for &SparseVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Mul<T> for &SparseVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn mul(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn mul(self, rhs: T) -> Self::Output {
+        SparseVector::from_pairs(self.elems.iter().map(|(&i, &x)| (i, x * rhs)))
+    }
+}
+
+//////////////////////////////
+//  SPARSE VEC STACK IMPLS  //
+//////////////////////////////
+
+impl<const D: usize, const M: usize, T: Scalar> CanStackVertically<&SparseVector<M, T>> for SparseVector<D, T>
+    where [(); D+M]: Sized
+{
+    type Output = SparseVector<{D+M}, T>;
+
+    fn vstack(&self, other: &SparseVector<M, T>) -> Self::Output {
+        let theirs = other.elems.iter().map(|(&i, &x)| (i + D, x));
+        SparseVector::from_pairs(self.elems.iter().map(|(&i, &x)| (i, x)).chain(theirs))
     }
 }
 
 ////////////////////////////////
-/// SPARSE VEC UTILITY IMPLS ///
+//  SPARSE VEC UTILITY IMPLS  //
 ////////////////////////////////
 
-impl<const D: usize> Index<usize> for SparseVector<D> {
-    type Output = f32;
+impl<const D: usize, T: Scalar> Index<usize> for SparseVector<D, T> {
+    type Output = T;
 
     fn index(&self, index: usize) -> &Self::Output {
         self.elems.get(&index).unwrap_or(&self.zero)
     }
 }
+
+impl<const D: usize, T: Scalar> From<&SparseVector<D, T>> for DenseVector<D, T> {
+    fn from(sparse: &SparseVector<D, T>) -> Self {
+        sparse.to_dense()
+    }
+}
+
+mod tests {
+    #[test]
+    fn sparse_vector_arithmetic_stays_sparse() {
+        use super::{DenseVector, OneHotVector, SparseVector};
+        use crate::linalg::vector::traits::CanDotProduct;
+
+        let u = SparseVector::<5>::from_pairs([(0, 2.), (3, -1.), (0, 1.)]);
+        let v = SparseVector::<5>::from_pairs([(3, 1.), (4, 2.)]);
+        let e = OneHotVector::<5>::at_index(4);
+
+        assert_eq!(u.sum(), 2.);
+        assert_eq!(u.sum_of_squares(), 10.);
+
+        // The entry at 3 cancels and must not linger as an explicit zero.
+        let sum = &u + &v;
+        assert_eq!(sum, SparseVector::from_pairs([(0, 3.), (4, 2.)]));
+        assert_eq!(sum.elems.len(), 2);
+        assert_eq!(&sum - &e, SparseVector::from_pairs([(0, 3.), (4, 1.)]));
+
+        let dense = DenseVector::from_arr([1., 2., 3., 4., 5.]);
+        assert_eq!((&u).dot(&v), -1.);
+        assert_eq!((&u).dot(&dense), -1.);
+        assert_eq!((&u).dot(&e), 0.);
+        assert_eq!(&u - &dense, DenseVector::from_arr([2., -2., -3., -5., -5.]));
+    }
+
+    #[test]
+    fn sparse_vector_outer_products() {
+        use super::{DenseVector, SparseVector};
+        use crate::linalg::matrix::SparseMatrix;
+        use crate::linalg::vector::traits::CanOuterProduct;
+
+        let u = SparseVector::<3>::from_pairs([(1, 2.)]);
+        let v = DenseVector::<2>::from_arr([3., -1.]);
+        assert_eq!(u.outer(&v), SparseMatrix::from_triplets([(1, 0, 6.), (1, 1, -2.)]));
+
+        let w = SparseVector::<2>::from_pairs([(0, 4.)]);
+        let x = DenseVector::<3>::from_arr([1., 0., 2.]);
+        assert_eq!(x.outer(&w), SparseMatrix::from_triplets([(0, 0, 4.), (2, 0, 8.)]));
+    }
+}
@@ -1,16 +1,17 @@
-pub(crate) trait CanAppend {
+pub(crate) trait CanAppend<T> {
     type Output;
-    fn append(&self, extra_val: f32) -> Self::Output; // TODO: fix self
+    fn append(&self, extra_val: T) -> Self::Output; // TODO: fix self
 }
 
-pub(crate) trait CanDotProduct<V> {
-    fn dot(&self, other: V) -> f32; // TODO: fix self
+pub(crate) trait CanDotProduct<V, T> {
+    fn dot(&self, other: V) -> T; // TODO: fix self
 }
 
-pub(crate) trait CanMap {
+pub(crate) trait CanMap<T> {
     type Output;
-    fn map(&self, f: impl Fn(f32) -> f32) -> Self::Output; // TODO: fix self
-    // TODO: map_self, also via <<= ?
+    fn map(&self, f: impl Fn(T) -> T) -> Self::Output; // TODO: fix self
+    // Mapping in place may change the flavor, so it lives on `Vector` as
+    // `map_in_place` rather than here.
 }
 
 pub(crate) trait CanOuterProduct<V> {
@@ -18,14 +19,12 @@ pub(crate) trait CanOuterProduct<V> {
     fn outer(&self, other: V) -> Self::Output; // TODO: fix self
 }
 
-pub(crate) trait CanStackHorizontally<T> {
+pub(crate) trait CanStackHorizontally<M> {
     type Output;
-    fn hstack(&mut self, other: T) -> Self::Output;
-    // TODO: hstack mut
+    fn hstack(&self, other: M) -> Self::Output; // TODO: fix self
 }
 
-pub(crate) trait CanStackVertically<T> {
+pub(crate) trait CanStackVertically<V> {
     type Output;
-    fn vstack(&mut self, other: T) -> Self::Output;
-    // TODO: vstack mut
+    fn vstack(&self, other: V) -> Self::Output; // TODO: fix self
 }
@@ -0,0 +1,75 @@
+use std::marker::PhantomData;
+use std::ops::Index;
+
+use crate::linalg::Scalar;
+
+use super::dense::DenseVector;
+use super::wrapper::Vector;
+
+/// `D` entries read in place from the storage of a dense vector or matrix,
+/// entry `i` at `offset + i*stride`.
+#[derive(Clone, Copy, Debug)]
+pub struct DenseVectorView<'a, const D: usize, T: Scalar = f32> {
+    pub(crate) data: &'a [T],
+    pub(crate) offset: usize,
+    pub(crate) stride: usize,
+    pub(crate) size_marker: PhantomData<[T; D]>,
+}
+
+impl<'a, const D: usize, T: Scalar> DenseVectorView<'a, D, T> {
+    pub(crate) fn new(data: &'a [T], offset: usize, stride: usize) -> Self {
+        debug_assert!(D == 0 || offset + (D - 1)*stride < data.len());
+        Self { data, offset, stride, size_marker: PhantomData }
+    }
+
+    /// The entries as one slice of storage, when they are next to each other.
+    pub(crate) fn as_contiguous(&self) -> Option<&'a [T]> {
+        (self.stride == 1 || D <= 1).then(|| &self.data[self.offset..self.offset + D])
+    }
+
+    pub(crate) fn to_dense(self) -> DenseVector<D, T> {
+        match self.as_contiguous() {
+            Some(run) => DenseVector::from_boxed_slice(run.into()),
+            None => DenseVector::from_fun(|i| self[i]),
+        }
+    }
+}
+
+impl<const D: usize, T: Scalar> Index<usize> for DenseVectorView<'_, D, T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        assert!(i < D);
+        &self.data[self.offset + i*self.stride]
+    }
+}
+
+/// What `Vector::slice`, `Matrix::row` and `Matrix::col` return. Dense
+/// storage is borrowed rather than copied; the other flavors are cheap to
+/// build and come out as a vector of the matching flavor.
+#[derive(Clone, Debug)]
+pub enum VectorView<'a, const D: usize, T: Scalar = f32> {
+    Borrowed(DenseVectorView<'a, D, T>),
+    Owned(Vector<D, T>),
+}
+
+impl<const D: usize, T: Scalar> VectorView<'_, D, T> {
+    /// Copies the entries out into a vector of their own.
+    pub fn to_vector(&self) -> Vector<D, T> {
+        match self {
+            VectorView::Borrowed(v) => Vector::Dense(v.to_dense()),
+            VectorView::Owned(v) => v.clone(),
+        }
+    }
+}
+
+impl<const D: usize, T: Scalar> Index<usize> for VectorView<'_, D, T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &Self::Output {
+        match self {
+            VectorView::Borrowed(v) => &v[i],
+            VectorView::Owned(v) => &v[i],
+        }
+    }
+}
@@ -1,37 +1,39 @@
 use std::collections::HashMap;
-use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Sub};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Sub, SubAssign};
 
-use crate::linalg::Matrix;
+use crate::linalg::{bf16, density, f16, Matrix, Scalar};
+use crate::linalg::kernels;
 
-use super::traits::{CanDotProduct, CanAppend, CanMap, CanOuterProduct};
+use super::traits::{CanDotProduct, CanAppend, CanMap, CanOuterProduct, CanStackVertically};
 use super::constant::ConstantVector;
 use super::dense::DenseVector;
 use super::onehot::OneHotVector;
 use super::sparse::SparseVector;
+use super::view::{DenseVectorView, VectorView};
 use super::zero::ZeroVector;
 
 #[derive(Clone, Debug)]
-pub enum Vector<const D: usize> {
-    Constant(ConstantVector<D>),
-    Dense(DenseVector<D>),
-    OneHot(OneHotVector<D>),
-    Sparse(SparseVector<D>),
-    Zero(ZeroVector<D>),
+pub enum Vector<const D: usize, T: Scalar = f32> {
+    Constant(ConstantVector<D, T>),
+    Dense(DenseVector<D, T>),
+    OneHot(OneHotVector<D, T>),
+    Sparse(SparseVector<D, T>),
+    Zero(ZeroVector<D, T>),
 }
 
-impl<const D: usize> Vector<D> {
+impl<const D: usize, T: Scalar> Vector<D, T> {
     // constructor
-    pub fn from_arr(arr: [f32; D]) -> Self {
+    pub fn from_arr(arr: [T; D]) -> Self {
         Self::Dense(DenseVector::from_arr(arr))
     }
 
     // constructor
-    pub(crate) fn from_boxed_slice(slice: Box<[f32]>) -> Self {
+    pub(crate) fn from_boxed_slice(slice: Box<[T]>) -> Self {
         Self::Dense(DenseVector::from_boxed_slice(slice))
     }
 
     // constructor
-    pub fn from_fun(f: impl Fn(usize) -> f32) -> Self {
+    pub fn from_fun(f: impl Fn(usize) -> T) -> Self {
         Self::Dense(DenseVector::from_fun(f))
     }
 
@@ -42,15 +44,22 @@ impl<const D: usize> Vector<D> {
 
     // constructor
     pub fn sparse() -> Self {
-        Self::Sparse(SparseVector { elems: HashMap::default(), zero: 0f32 })
+        Self::Sparse(SparseVector { elems: HashMap::default(), zero: T::zero() })
+    }
+
+    // constructor
+    /// Builds a sparse vector out of `(index, value)` pairs, e.g. word counts
+    /// of a bag-of-words. Repeated indices are summed.
+    pub fn from_pairs(pairs: impl IntoIterator<Item = (usize, T)>) -> Self {
+        Self::Sparse(SparseVector::from_pairs(pairs))
     }
 
     // constructor
     pub fn zero() -> Self {
-        Self::Zero(ZeroVector(0f32))
+        Self::Zero(ZeroVector(T::zero()))
     }
 
-    pub fn dot(&self, other: &Vector<D>) -> f32 {
+    pub fn dot(&self, other: &Vector<D, T>) -> T {
         use Vector as V;
         match (self, other) {
             (V::Constant(v1), V::Constant(v2)) => v1.dot(v2),
@@ -85,7 +94,22 @@ impl<const D: usize> Vector<D> {
         }
     }
 
-    pub fn extend(&self, extra_val: f32) -> Vector<{D+1}> {
+    /// The entrywise product. An entry that is zero on either side stays
+    /// zero, so the result is never denser than the sparser operand.
+    pub fn hadamard(&self, other: &Vector<D, T>) -> Vector<D, T> {
+        use Vector as V;
+        match (self, other) {
+            (V::Zero(_), _) | (_, V::Zero(_)) => V::zero(),
+            (V::Constant(v), x) | (x, V::Constant(v)) => x * v.0,
+            (V::OneHot(v), x) | (x, V::OneHot(v)) => {
+                V::OneHot(OneHotVector { zero: T::zero(), one: v.one * x[v.index], index: v.index })
+            },
+            (V::Sparse(v), x) | (x, V::Sparse(v)) => V::from_pairs(v.nonzeros().map(|(i, y)| (i, y * x[i]))),
+            (V::Dense(v1), V::Dense(v2)) => V::Dense(v1.hadamard(v2)),
+        }
+    }
+
+    pub fn extend(&self, extra_val: T) -> Vector<{D+1}, T> {
         use Vector as V;
         match self {
             V::Constant(v) => V::Dense(v.append(extra_val)),
@@ -96,7 +120,25 @@ impl<const D: usize> Vector<D> {
         }
     }
 
-    pub fn map(&self, f: impl Fn(f32) -> f32) -> Vector<D> {
+    /// `self` followed by `other`. Sparse flavors stay sparse, and a single
+    /// one-hot next to zeros stays one-hot.
+    pub fn concat<const M: usize>(&self, other: &Vector<M, T>) -> Vector<{D+M}, T> {
+        use Vector as V;
+        match (self, other) {
+            (V::Constant(v1), V::Constant(v2)) if v1.0 == v2.0 => V::Constant(ConstantVector(v1.0)),
+            (V::Dense(v1), V::Dense(v2)) => V::Dense(v1.vstack(v2)),
+            (V::Sparse(v1), V::Sparse(v2)) => V::Sparse(v1.vstack(v2)),
+            (V::Zero(v1), V::Zero(v2)) => V::Zero(v1.vstack(v2)),
+            (V::OneHot(v), V::Zero(_)) => V::OneHot(OneHotVector { zero: T::zero(), one: v.one, index: v.index }),
+            (V::Zero(_), V::OneHot(v)) => V::OneHot(OneHotVector { zero: T::zero(), one: v.one, index: D + v.index }),
+            (V::Constant(_) | V::Dense(_), _) | (_, V::Constant(_) | V::Dense(_)) => {
+                V::from_fun(|i| if i < D { self[i] } else { other[i - D] })
+            },
+            _ => V::from_pairs(self.nonzeros().chain(other.nonzeros().map(|(i, x)| (i + D, x)))),
+        }
+    }
+
+    pub fn map(&self, f: impl Fn(T) -> T) -> Vector<D, T> {
         use Vector as V;
         match self {
             V::Constant(v) => V::Dense(v.map(f)),
@@ -107,43 +149,55 @@ impl<const D: usize> Vector<D> {
         }
     }
 
-    pub fn outer<const D2: usize>(&self, other: &Vector<D2>) -> Matrix<D, D2> where [(); D*D2]: Sized {
+    /// Iterates over `(index, value)` of the nonzero entries by increasing index.
+    /// Sparse flavors only visit what they store.
+    pub fn nonzeros(&self) -> Box<dyn Iterator<Item = (usize, T)> + '_> {
+        use Vector as V;
+        match self {
+            V::OneHot(v) => Box::new(std::iter::once((v.index, v.one)).filter(|&(_, x)| x != T::zero())),
+            V::Sparse(v) => Box::new(v.nonzeros()),
+            V::Zero(_) => Box::new(std::iter::empty()),
+            _ => Box::new(self.into_iter().enumerate().filter(|&(_, x)| x != T::zero())),
+        }
+    }
+
+    pub fn outer<const D2: usize>(&self, other: &Vector<D2, T>) -> Matrix<D, D2, T> where [(); D*D2]: Sized {
         use Matrix as M;
         use Vector as V;
         match (self, other) {
-            (V::Constant(v1), V::Constant(v2)) => M::Dense(v1.outer(v2)),
+            (V::Constant(v1), V::Constant(v2)) => M::Constant(v1.outer(v2)),
             (V::Constant(v1), V::Dense(v2)) => M::Dense(v1.outer(v2)),
-            (V::Constant(v1), V::OneHot(v2)) => M::Dense(v1.outer(v2)),
-            (V::Constant(v1), V::Sparse(v2)) => M::Dense(v1.outer(v2)),
-            (V::Constant(v1), V::Zero(v2)) => M::Dense(v1.outer(v2)),
+            (V::Constant(v1), V::OneHot(v2)) => M::Sparse(v1.outer(v2)),
+            (V::Constant(v1), V::Sparse(v2)) => M::Sparse(v1.outer(v2)),
+            (V::Constant(v1), V::Zero(v2)) => M::Zero(v1.outer(v2)),
 
             (V::Dense(v1), V::Constant(v2)) => M::Dense(v1.outer(v2)),
             (V::Dense(v1), V::Dense(v2)) => M::Dense(v1.outer(v2)),
-            (V::Dense(v1), V::OneHot(v2)) => M::Dense(v1.outer(v2)),
-            (V::Dense(v1), V::Sparse(v2)) => M::Dense(v1.outer(v2)),
-            (V::Dense(v1), V::Zero(v2)) => M::Dense(v1.outer(v2)),
-
-            (V::OneHot(v1), V::Constant(v2)) => M::Dense(v1.outer(v2)),
-            (V::OneHot(v1), V::Dense(v2)) => M::Dense(v1.outer(v2)),
-            (V::OneHot(v1), V::OneHot(v2)) => M::Dense(v1.outer(v2)),
-            (V::OneHot(v1), V::Sparse(v2)) => M::Dense(v1.outer(v2)),
-            (V::OneHot(v1), V::Zero(v2)) => M::Dense(v1.outer(v2)),
-
-            (V::Sparse(v1), V::Constant(v2)) => M::Dense(v1.outer(v2)),
-            (V::Sparse(v1), V::Dense(v2)) => M::Dense(v1.outer(v2)),
-            (V::Sparse(v1), V::OneHot(v2)) => M::Dense(v1.outer(v2)),
-            (V::Sparse(v1), V::Sparse(v2)) => M::Dense(v1.outer(v2)),
-            (V::Sparse(v1), V::Zero(v2)) => M::Dense(v1.outer(v2)),
-
-            (V::Zero(v1), V::Constant(v2)) => M::Dense(v1.outer(v2)),
-            (V::Zero(v1), V::Dense(v2)) => M::Dense(v1.outer(v2)),
-            (V::Zero(v1), V::OneHot(v2)) => M::Dense(v1.outer(v2)),
-            (V::Zero(v1), V::Sparse(v2)) => M::Dense(v1.outer(v2)),
-            (V::Zero(v1), V::Zero(v2)) => M::Dense(v1.outer(v2)),
+            (V::Dense(v1), V::OneHot(v2)) => M::Sparse(v1.outer(v2)),
+            (V::Dense(v1), V::Sparse(v2)) => M::Sparse(v1.outer(v2)),
+            (V::Dense(v1), V::Zero(v2)) => M::Zero(v1.outer(v2)),
+
+            (V::OneHot(v1), V::Constant(v2)) => M::Sparse(v1.outer(v2)),
+            (V::OneHot(v1), V::Dense(v2)) => M::Sparse(v1.outer(v2)),
+            (V::OneHot(v1), V::OneHot(v2)) => M::Sparse(v1.outer(v2)),
+            (V::OneHot(v1), V::Sparse(v2)) => M::Sparse(v1.outer(v2)),
+            (V::OneHot(v1), V::Zero(v2)) => M::Zero(v1.outer(v2)),
+
+            (V::Sparse(v1), V::Constant(v2)) => M::Sparse(v1.outer(v2)),
+            (V::Sparse(v1), V::Dense(v2)) => M::Sparse(v1.outer(v2)),
+            (V::Sparse(v1), V::OneHot(v2)) => M::Sparse(v1.outer(v2)),
+            (V::Sparse(v1), V::Sparse(v2)) => M::Sparse(v1.outer(v2)),
+            (V::Sparse(v1), V::Zero(v2)) => M::Zero(v1.outer(v2)),
+
+            (V::Zero(v1), V::Constant(v2)) => M::Zero(v1.outer(v2)),
+            (V::Zero(v1), V::Dense(v2)) => M::Zero(v1.outer(v2)),
+            (V::Zero(v1), V::OneHot(v2)) => M::Zero(v1.outer(v2)),
+            (V::Zero(v1), V::Sparse(v2)) => M::Zero(v1.outer(v2)),
+            (V::Zero(v1), V::Zero(v2)) => M::Zero(v1.outer(v2)),
         }
     }
 
-    pub fn sum(&self) -> f32 {
+    pub fn sum(&self) -> T {
         use Vector as V;
         match self {
             V::Constant(v) => v.sum(),
@@ -154,7 +208,7 @@ impl<const D: usize> Vector<D> {
         }
     }
 
-    pub fn sum_of_squares(&self) -> f32 {
+    pub fn sum_of_squares(&self) -> T {
         use Vector as V;
         match self {
             V::Constant(v) => v.sum_of_squares(),
@@ -165,24 +219,169 @@ impl<const D: usize> Vector<D> {
         }
     }
 
-    pub(crate) fn to_dense(self) -> DenseVector<D> {
+    /// The `LEN` entries starting at `START`. A slice reaching past the end
+    /// does not compile.
+    ///
+    /// Dense vectors are borrowed in place; the other flavors are cheap to
+    /// slice and come out in the flavor of `self`.
+    pub fn slice<const START: usize, const LEN: usize>(&self) -> VectorView<'_, LEN, T> where [(); D - (START + LEN)]: Sized {
+        match self {
+            Vector::Dense(v) => VectorView::Borrowed(DenseVectorView::new(&v.data, START, 1)),
+            _ => VectorView::Owned(self.slice_owned::<START, LEN>()),
+        }
+    }
+
+    /// Copies out the `LEN` entries starting at `START`, in the flavor of `self`.
+    pub fn slice_owned<const START: usize, const LEN: usize>(&self) -> Vector<LEN, T> where [(); D - (START + LEN)]: Sized {
         use Vector as V;
+        let range = START..START + LEN;
         match self {
-            V::Constant(_v) => todo!(),
+            V::Constant(v) => V::Constant(ConstantVector(v.0)),
+            V::Dense(v) => V::Dense(DenseVector::from_boxed_slice(v.data[range].into())),
+            V::OneHot(v) if range.contains(&v.index) => {
+                V::OneHot(OneHotVector { zero: T::zero(), one: v.one, index: v.index - START })
+            },
+            V::Sparse(v) => V::Sparse(SparseVector::from_pairs(v.elems.iter()
+                .filter(|(i, _)| range.contains(i))
+                .map(|(&i, &x)| (i - START, x)))),
+            V::OneHot(_) | V::Zero(_) => V::zero(),
+        }
+    }
+
+    pub(crate) fn into_dense(self) -> DenseVector<D, T> {
+        use Vector as V;
+        match self {
+            V::Constant(v) => DenseVector::from_fun(|_| v.0),
             V::Dense(v) => v,
-            V::OneHot(_v) => todo!(),
-            V::Sparse(_v) => todo!(),
-            V::Zero(_v) => todo!(),
+            V::OneHot(v) => (&v).into(),
+            V::Sparse(v) => (&v).into(),
+            V::Zero(_) => DenseVector::from_fun(|_| T::zero()),
+        }
+    }
+
+    /// Switches `self` over to the dense flavor, so it can be mutated entrywise.
+    pub(crate) fn make_dense(&mut self) -> &mut DenseVector<D, T> {
+        if !matches!(self, Vector::Dense(_)) {
+            let v = std::mem::replace(self, Vector::zero());
+            *self = Vector::Dense(v.into_dense());
+        }
+        match self {
+            Vector::Dense(v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Applies `f` to every entry without allocating, once `self` is dense.
+    pub fn map_in_place(&mut self, f: impl Fn(T) -> T) {
+        kernels::map_assign(&mut self.make_dense().data, f);
+    }
+
+    /// The fraction of entries that are nonzero.
+    pub fn density(&self) -> f32 {
+        if D == 0 { 0. } else { self.nnz() as f32 / D as f32 }
+    }
+
+    fn nnz(&self) -> usize {
+        use Vector as V;
+        match self {
+            V::Constant(v) if v.0 != T::zero() => D,
+            V::Dense(v) => v.data.iter().filter(|&&x| x != T::zero()).count(),
+            V::OneHot(v) if v.one != T::zero() => 1,
+            V::Sparse(v) => v.elems.len(),
+            V::Constant(_) | V::OneHot(_) | V::Zero(_) => 0,
+        }
+    }
+
+    /// The same vector, stored densely.
+    pub fn densify(self) -> Self {
+        Vector::Dense(self.into_dense())
+    }
+
+    /// `self` as a sparse vector, leaving out entries of magnitude `eps`
+    /// or less.
+    pub fn sparsify(self, eps: T) -> Self {
+        Vector::Sparse(SparseVector::from_pairs(self.nonzeros().filter(|(_, x)| x.abs() > eps)))
+    }
+
+    /// Moves sparse storage to dense once more than `threshold` of the
+    /// entries are nonzero, and dense storage to sparse once at most half
+    /// that many are. Other flavors are left alone.
+    pub fn rebalance(self, threshold: f32) -> Self {
+        use Vector as V;
+        match self {
+            V::Sparse(_) if !density::prefers_sparse(self.nnz(), D, threshold, true) => self.densify(),
+            V::Dense(_) if density::prefers_sparse(self.nnz(), D, threshold, false) => self.sparsify(T::zero()),
+            _ => self,
+        }
+    }
+
+    /// Rebalances the result of an op under the global `density::threshold`,
+    /// provided one is set and some operand was sparse.
+    pub(crate) fn settle(self, sparse_operands: bool) -> Self {
+        match density::threshold() {
+            Some(threshold) if sparse_operands => self.rebalance(threshold),
+            _ => self,
+        }
+    }
+
+    fn settle_in_place(&mut self, sparse_operands: bool) {
+        if sparse_operands && density::threshold().is_some() {
+            let v = std::mem::replace(self, Vector::zero());
+            *self = v.settle(true);
+        }
+    }
+
+    pub(crate) fn is_sparse(&self) -> bool {
+        matches!(self, Vector::OneHot(_) | Vector::Sparse(_))
+    }
+
+    /// `self += alpha * x` without materializing `alpha * x`. Sparse updates
+    /// of a sparse vector stay sparse, everything else ends up dense, unless
+    /// a `density::threshold` is set.
+    pub fn axpy(&mut self, alpha: T, x: &Vector<D, T>) {
+        use Vector as V;
+        let sparse_operands = self.is_sparse() || x.is_sparse();
+        match (&mut *self, x) {
+            (_, V::Zero(_)) => {},
+            (V::Sparse(y), V::OneHot(x)) => y.accumulate(x.index, alpha * x.one),
+            (V::Sparse(y), V::Sparse(x)) => for (&i, &z) in &x.elems {
+                y.accumulate(i, alpha * z);
+            },
+            _ => x.axpy_into(alpha, &mut self.make_dense().data),
+        }
+        self.settle_in_place(sparse_operands);
+    }
+
+    /// `out += alpha * self`, touching only the nonzeros of sparse flavors.
+    pub(crate) fn axpy_into(&self, alpha: T, out: &mut [T]) {
+        self.axpy_range_into(alpha, 0, out);
+    }
+
+    /// `out += alpha * self[at..at + out.len()]`, for callers that work
+    /// through the entries of `self` a chunk at a time.
+    pub(crate) fn axpy_range_into(&self, alpha: T, at: usize, out: &mut [T]) {
+        use Vector as V;
+        let range = at..at + out.len();
+        match self {
+            V::Constant(v) => kernels::offset_assign(out, alpha * v.0),
+            V::Dense(v) => kernels::axpy(out, alpha, &v.data[range]),
+            V::OneHot(v) if range.contains(&v.index) => out[v.index - at] += alpha * v.one,
+            V::Sparse(v) => for (&i, &x) in &v.elems {
+                if range.contains(&i) {
+                    out[i - at] += alpha * x;
+                }
+            },
+            V::OneHot(_) | V::Zero(_) => {},
         }
     }
 }
 
-impl<const D: usize> Add<&Vector<D>> for &Vector<D> {
-    type Output = Vector<D>;
+impl<const D: usize, T: Scalar> Add<&Vector<D, T>> for &Vector<D, T> {
+    type Output = Vector<D, T>;
 
-    fn add(self, rhs: &Vector<D>) -> Self::Output {
+    fn add(self, rhs: &Vector<D, T>) -> Self::Output {
         use Vector as V;
-        match (self, rhs) {
+        let sum = match (self, rhs) {
             (V::Constant(v1), V::Constant(v2)) => V::Constant(v1 + v2),
             (V::Constant(v1), V::Dense(v2)) => V::Dense(v1 + v2),
             (V::Constant(v1), V::OneHot(v2)) => V::Dense(v1 + v2),
@@ -212,29 +411,15 @@ impl<const D: usize> Add<&Vector<D>> for &Vector<D> {
             (V::Zero(v1), V::OneHot(v2)) => V::OneHot(v1 + v2),
             (V::Zero(v1), V::Sparse(v2)) => V::Sparse(v1 + v2),
             (V::Zero(v1), V::Zero(v2)) => V::Zero(v1 + v2),
-        }
+        };
+        sum.settle(self.is_sparse() || rhs.is_sparse())
     }
 }
 
-impl<const D: usize> Add<&Vector<D>> for f32 {
-    type Output = Vector<D>;
+impl<const D: usize, T: Scalar> Add<T> for &Vector<D, T> {
+    type Output = Vector<D, T>;
 
-    fn add(self, rhs: &Vector<D>) -> Self::Output {
-        use Vector as V;
-        match rhs {
-            V::Constant(v) => V::Constant(v + self),
-            V::Dense(v) => V::Dense(v + self),
-            V::OneHot(v) => V::Dense(v + self),
-            V::Sparse(v) => V::Dense(v + self),
-            V::Zero(v) => V::Constant(v + self),
-        }
-    }
-}
-
-impl<const D: usize> Add<f32> for &Vector<D> {
-    type Output = Vector<D>;
-
-    fn add(self, rhs: f32) -> Self::Output {
+    fn add(self, rhs: T) -> Self::Output {
         use Vector as V;
         match self {
             V::Constant(v) => V::Constant(v + rhs),
@@ -246,9 +431,10 @@ impl<const D: usize> Add<f32> for &Vector<D> {
     }
 }
 
-impl<const D: usize> AddAssign<&Vector<D>> for Vector<D> {
-    fn add_assign(&mut self, rhs: &Vector<D>) {
+impl<const D: usize, T: Scalar> AddAssign<&Vector<D, T>> for Vector<D, T> {
+    fn add_assign(&mut self, rhs: &Vector<D, T>) {
         use Vector as V;
+        let sparse_operands = self.is_sparse() || rhs.is_sparse();
         match (&mut *self, rhs) {
             (V::Constant(ref mut v1), V::Constant(v2)) => *v1 += v2,
             (V::Constant(v1), V::Dense(v2)) => *self = V::Dense(&*v1 + v2),
@@ -280,11 +466,12 @@ impl<const D: usize> AddAssign<&Vector<D>> for Vector<D> {
             (V::Zero(_), rhs @ V::Sparse(_)) => *self = rhs.clone(),
             (V::Zero(_), V::Zero(_)) => {}, // no-op
         }
+        self.settle_in_place(sparse_operands);
     }
 }
 
-impl<const D: usize> AddAssign<f32> for Vector<D> {
-    fn add_assign(&mut self, rhs: f32) {
+impl<const D: usize, T: Scalar> AddAssign<T> for Vector<D, T> {
+    fn add_assign(&mut self, rhs: T) {
         use Vector as V;
         match self {
             V::Constant(ref mut v) => *v += rhs,
@@ -296,12 +483,29 @@ impl<const D: usize> AddAssign<f32> for Vector<D> {
     }
 }
 
-impl<const D: usize> Sub<&Vector<D>> for &Vector<D> {
-    type Output = Vector<D>;
+impl<const D: usize, T: Scalar> SubAssign<&Vector<D, T>> for Vector<D, T> {
+    fn sub_assign(&mut self, rhs: &Vector<D, T>) {
+        use Vector as V;
+        match (&mut *self, rhs) {
+            (V::Constant(ref mut v1), V::Constant(v2)) => v1.0 -= v2.0,
+            (V::Zero(_), V::Constant(v2)) => *self = V::Constant(ConstantVector(-v2.0)),
+            _ => self.axpy(-T::one(), rhs),
+        }
+    }
+}
 
-    fn sub(self, rhs: &Vector<D>) -> Self::Output {
+impl<const D: usize, T: Scalar> SubAssign<T> for Vector<D, T> {
+    fn sub_assign(&mut self, rhs: T) {
+        *self += -rhs;
+    }
+}
+
+impl<const D: usize, T: Scalar> Sub<&Vector<D, T>> for &Vector<D, T> {
+    type Output = Vector<D, T>;
+
+    fn sub(self, rhs: &Vector<D, T>) -> Self::Output {
         use Vector as V;
-        match (self, rhs) {
+        let difference = match (self, rhs) {
             (V::Constant(v1), V::Constant(v2)) => V::Constant(v1 - v2),
             (V::Constant(v1), V::Dense(v2)) => V::Dense(v1 - v2),
             (V::Constant(v1), V::OneHot(v2)) => V::Dense(v1 - v2),
@@ -331,81 +535,126 @@ impl<const D: usize> Sub<&Vector<D>> for &Vector<D> {
             (V::Zero(v1), V::OneHot(v2)) => V::Sparse(v1 - v2),
             (V::Zero(v1), V::Sparse(v2)) => V::Sparse(v1 - v2),
             (V::Zero(v1), V::Zero(v2)) => V::Zero(v1 - v2),
-        }
+        };
+        difference.settle(self.is_sparse() || rhs.is_sparse())
     }
 }
 
-impl<const D: usize> Sub<&Vector<D>> for f32 {
-    type Output = Vector<D>;
+impl<const D: usize, T: Scalar> Sub<T> for &Vector<D, T> {
+    type Output = Vector<D, T>;
 
-    fn sub(self, rhs: &Vector<D>) -> Self::Output {
+    fn sub(self, rhs: T) -> Self::Output {
         use Vector as V;
-        match rhs {
-            V::Constant(v) => V::Constant(v - self),
-            V::Dense(v) => V::Dense(v - self),
-            V::OneHot(v) => V::Dense(v - self),
-            V::Sparse(v) => V::Dense(v - self),
-            V::Zero(v) => V::Constant(v - self),
+        match self {
+            V::Constant(v) => V::Constant(v - rhs),
+            V::Dense(v) => V::Dense(v - rhs),
+            V::OneHot(v) => V::Dense(v - rhs),
+            V::Sparse(v) => V::Dense(v - rhs),
+            V::Zero(v) => V::Constant(v - rhs),
         }
     }
 }
 
-impl<const D: usize> Sub<f32> for &Vector<D> {
-    type Output = Vector<D>;
+impl<const D: usize, T: Scalar> Mul<T> for &Vector<D, T> {
+    type Output = Vector<D, T>;
 
-    fn sub(self, rhs: f32) -> Self::Output {
+    fn mul(self, rhs: T) -> Self::Output {
         use Vector as V;
         match self {
-            V::Constant(v) => V::Constant(v - rhs),
-            V::Dense(v) => V::Dense(v - rhs),
-            V::OneHot(v) => V::Dense(v - rhs),
-            V::Sparse(v) => V::Dense(v - rhs),
-            V::Zero(v) => V::Constant(v - rhs),
+            V::Constant(v) => V::Constant(v * rhs),
+            V::Dense(v) => V::Dense(v * rhs),
+            V::OneHot(v) => V::Sparse(v * rhs),
+            V::Sparse(v) => V::Sparse(v * rhs),
+            V::Zero(v) => V::Zero(v * rhs),
         }
     }
 }
 
-impl<const D: usize> Mul<&Vector<D>> for f32 {
-    type Output = Vector<D>;
+impl<const D: usize, T: Scalar> MulAssign<T> for Vector<D, T> {
+    fn mul_assign(&mut self, rhs: T) {
+        use Vector as V;
+        match self {
+            V::Constant(ref mut v) => v.0 *= rhs,
+            V::Dense(ref mut v) => kernels::scale_assign(&mut v.data, rhs),
+            V::OneHot(v) => *self = V::Sparse(&*v * rhs),
+            V::Sparse(ref mut v) if rhs == T::zero() => v.elems.clear(),
+            V::Sparse(ref mut v) => v.elems.values_mut().for_each(|x| *x *= rhs),
+            V::Zero(_) => {}, // no-op
+        }
+    }
+}
 
-    fn mul(self, rhs: &Vector<D>) -> Self::Output {
+impl<const D: usize, T: Scalar> Vector<D, T> {
+    /// `x - self`, for the scalar-on-the-left impls below. Constant and zero
+    /// vectors stay constant; the others are negated, then offset by `x`.
+    fn subtracted_from(&self, x: T) -> Self {
         use Vector as V;
-        match rhs {
-            V::Constant(v) => V::Constant(v * self),
-            V::Dense(v) => V::Dense(v * self),
-            V::OneHot(v) => V::Sparse(v * self),
-            V::Sparse(v) => V::Sparse(v * self),
-            V::Zero(v) => V::Zero(v * self),
+        match self {
+            V::Constant(v) => V::Constant(ConstantVector(x - v.0)),
+            V::Zero(_) => V::Constant(ConstantVector(x)),
+            _ => &(self * -T::one()) + x,
         }
     }
 }
 
+/// A blanket `impl<T: Scalar> Add<&Vector<D, T>> for T` breaks the orphan
+/// rule, so the scalar-on-the-left ops are spelled out per entry type.
+macro_rules! scalar_lhs_ops {
+    ($($t:ty),*) => {$(
+        impl<const D: usize> Add<&Vector<D, $t>> for $t {
+            type Output = Vector<D, $t>;
+
+            fn add(self, rhs: &Vector<D, $t>) -> Self::Output {
+                rhs + self
+            }
+        }
+
+        impl<const D: usize> Sub<&Vector<D, $t>> for $t {
+            type Output = Vector<D, $t>;
+
+            fn sub(self, rhs: &Vector<D, $t>) -> Self::Output {
+                rhs.subtracted_from(self)
+            }
+        }
+
+        impl<const D: usize> Mul<&Vector<D, $t>> for $t {
+            type Output = Vector<D, $t>;
+
+            fn mul(self, rhs: &Vector<D, $t>) -> Self::Output {
+                rhs * self
+            }
+        }
+    )*};
+}
+
+scalar_lhs_ops!(f32, f64, bf16, f16);
+
 ///////////////////
-/// VECTOR ITER ///
+//  VECTOR ITER  //
 ///////////////////
 
-impl<'a, const D: usize> IntoIterator for &'a Vector<D> {
-    type Item = f32;
-    type IntoIter = VectorWrapperIterator<'a, D>;
+impl<'a, const D: usize, T: Scalar> IntoIterator for &'a Vector<D, T> {
+    type Item = T;
+    type IntoIter = VectorWrapperIterator<'a, D, T>;
 
     fn into_iter(self) -> Self::IntoIter {
         VectorWrapperIterator::new(self)
     }
 }
 
-pub struct VectorWrapperIterator<'a, const D: usize> {
+pub struct VectorWrapperIterator<'a, const D: usize, T: Scalar = f32> {
     pos: usize,
-    vec: &'a Vector<D>,
+    vec: &'a Vector<D, T>,
 }
 
-impl<'a, const D: usize> VectorWrapperIterator<'a, D> {
-    fn new(vec: &'a Vector<D>) -> Self {
+impl<'a, const D: usize, T: Scalar> VectorWrapperIterator<'a, D, T> {
+    fn new(vec: &'a Vector<D, T>) -> Self {
         VectorWrapperIterator { pos: 0, vec }
     }
 }
 
-impl<'a, const D: usize> Iterator for VectorWrapperIterator<'a, D> {
-    type Item = f32;
+impl<'a, const D: usize, T: Scalar> Iterator for VectorWrapperIterator<'a, D, T> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.pos == D {
@@ -426,11 +675,11 @@ impl<'a, const D: usize> Iterator for VectorWrapperIterator<'a, D> {
 }
 
 ////////////////////////////
-/// VECTOR UTILITY IMPLS ///
+//  VECTOR UTILITY IMPLS  //
 ////////////////////////////
 
-impl<const D: usize> Index<usize> for Vector<D> {
-    type Output = f32;
+impl<const D: usize, T: Scalar> Index<usize> for Vector<D, T> {
+    type Output = T;
 
     fn index(&self, i: usize) -> &Self::Output {
         use Vector as V;
@@ -444,20 +693,15 @@ impl<const D: usize> Index<usize> for Vector<D> {
     }
 }
 
-impl<const D: usize> IndexMut<usize> for Vector<D> {
+impl<const D: usize, T: Scalar> IndexMut<usize> for Vector<D, T> {
+    /// Writing through an index needs real storage, so other flavors are
+    /// first promoted to dense.
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        use Vector as V;
-        match self {
-            V::Constant(_v) => todo!(),
-            V::Dense(v) => &mut v[index],
-            V::OneHot(_v) => todo!(),
-            V::Sparse(_v) => todo!(),
-            V::Zero(_v) => todo!(),
-        }
+        &mut self.make_dense()[index]
     }
 }
 
-impl<const D: usize> PartialEq for Vector<D> {
+impl<const D: usize, T: Scalar> PartialEq for Vector<D, T> {
     fn eq(&self, other: &Self) -> bool {
         use Vector as V;
         match (self, other) {
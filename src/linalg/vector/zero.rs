@@ -1,250 +1,266 @@
 use std::ops::{Add, Index, Mul, Sub};
 
-use crate::linalg::matrix::DenseMatrix;
+use crate::linalg::Scalar;
+use crate::linalg::matrix::ZeroMatrix;
 
 use super::{ConstantVector, DenseVector, OneHotVector, SparseVector};
-use super::traits::{CanDotProduct, CanAppend, CanMap, CanOuterProduct};
+use super::traits::{CanDotProduct, CanAppend, CanMap, CanOuterProduct, CanStackVertically};
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct ZeroVector<const D: usize>(
-    pub(super) f32,
+pub struct ZeroVector<const D: usize, T: Scalar = f32>(
+    pub(crate) T,
 );
 
-impl<const D: usize> ZeroVector<D> {
-    pub(super) fn sum(&self) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> ZeroVector<D, T> {
+    pub(super) fn sum(&self) -> T {
+        T::zero()
     }
 
-    pub(super) fn sum_of_squares(&self) -> f32 {
-        todo!()
+    pub(super) fn sum_of_squares(&self) -> T {
+        T::zero()
     }
 }
 
-impl<const D: usize> CanAppend for ZeroVector<D> where [(); D+1]: Sized {
-    type Output = SparseVector<{D+1}>;
-    fn append(&self, _extra_val: f32) -> Self::Output {
-        todo!()
+impl<const D: usize, T: Scalar> CanAppend<T> for ZeroVector<D, T> where [(); D+1]: Sized {
+    type Output = SparseVector<{D+1}, T>;
+    fn append(&self, extra_val: T) -> Self::Output {
+        SparseVector::from_pairs([(D, extra_val)])
     }
 }
 
-impl<const D: usize> CanMap for ZeroVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> CanMap<T> for ZeroVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn map(&self, _f: impl Fn(f32) -> f32) -> Self::Output {
-        todo!()
+    fn map(&self, f: impl Fn(T) -> T) -> Self::Output {
+        let x = f(T::zero());
+        DenseVector::from_fun(|_| x)
     }
 }
 
 //////////////////////////
-/// ZERO VEC ADD IMPLS ///
+//  ZERO VEC ADD IMPLS  //
 //////////////////////////
 
-impl<const D: usize> Add<f32> for &ZeroVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Add<T> for &ZeroVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn add(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn add(self, rhs: T) -> Self::Output {
+        ConstantVector(rhs)
     }
 }
 
-impl<const D: usize> Add<&ConstantVector<D>> for &ZeroVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Add<&ConstantVector<D, T>> for &ZeroVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn add(self, _rhs: &ConstantVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &ConstantVector<D, T>) -> Self::Output {
+        rhs.clone()
     }
 }
 
-impl<const D: usize> Add<&DenseVector<D>> for &ZeroVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Add<&DenseVector<D, T>> for &ZeroVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn add(self, _rhs: &DenseVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &DenseVector<D, T>) -> Self::Output {
+        rhs.clone()
     }
 }
 
-impl<const D: usize> Add<&OneHotVector<D>> for &ZeroVector<D> {
-    type Output = OneHotVector<D>;
+impl<const D: usize, T: Scalar> Add<&OneHotVector<D, T>> for &ZeroVector<D, T> {
+    type Output = OneHotVector<D, T>;
 
-    fn add(self, _rhs: &OneHotVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &OneHotVector<D, T>) -> Self::Output {
+        rhs.clone()
     }
 }
 
-impl<const D: usize> Add<&SparseVector<D>> for &ZeroVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Add<&SparseVector<D, T>> for &ZeroVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn add(self, _rhs: &SparseVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, rhs: &SparseVector<D, T>) -> Self::Output {
+        rhs.clone()
     }
 }
 
-impl<const D: usize> Add<&ZeroVector<D>> for &ZeroVector<D> {
-    type Output = ZeroVector<D>;
+impl<const D: usize, T: Scalar> Add<&ZeroVector<D, T>> for &ZeroVector<D, T> {
+    type Output = ZeroVector<D, T>;
 
-    fn add(self, _rhs: &ZeroVector<D>) -> Self::Output {
-        todo!()
+    fn add(self, _rhs: &ZeroVector<D, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 //////////////////////////
-/// ZERO VEC SUB IMPLS ///
+//  ZERO VEC SUB IMPLS  //
 //////////////////////////
 
-impl<const D: usize> Sub<f32> for &ZeroVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Sub<T> for &ZeroVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn sub(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: T) -> Self::Output {
+        ConstantVector(-rhs)
     }
 }
 
-impl<const D: usize> Sub<&ConstantVector<D>> for &ZeroVector<D> {
-    type Output = ConstantVector<D>;
+impl<const D: usize, T: Scalar> Sub<&ConstantVector<D, T>> for &ZeroVector<D, T> {
+    type Output = ConstantVector<D, T>;
 
-    fn sub(self, _rhs: &ConstantVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &ConstantVector<D, T>) -> Self::Output {
+        ConstantVector(-rhs.0)
     }
 }
 
-impl<const D: usize> Sub<&DenseVector<D>> for &ZeroVector<D> {
-    type Output = DenseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&DenseVector<D, T>> for &ZeroVector<D, T> {
+    type Output = DenseVector<D, T>;
 
-    fn sub(self, _rhs: &DenseVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &DenseVector<D, T>) -> Self::Output {
+        rhs * -T::one()
     }
 }
 
-impl<const D: usize> Sub<&OneHotVector<D>> for &ZeroVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&OneHotVector<D, T>> for &ZeroVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn sub(self, _rhs: &OneHotVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &OneHotVector<D, T>) -> Self::Output {
+        rhs * -T::one()
     }
 }
 
-impl<const D: usize> Sub<&SparseVector<D>> for &ZeroVector<D> {
-    type Output = SparseVector<D>;
+impl<const D: usize, T: Scalar> Sub<&SparseVector<D, T>> for &ZeroVector<D, T> {
+    type Output = SparseVector<D, T>;
 
-    fn sub(self, _rhs: &SparseVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, rhs: &SparseVector<D, T>) -> Self::Output {
+        rhs * -T::one()
     }
 }
 
-impl<const D: usize> Sub<&ZeroVector<D>> for &ZeroVector<D> {
-    type Output = ZeroVector<D>;
+impl<const D: usize, T: Scalar> Sub<&ZeroVector<D, T>> for &ZeroVector<D, T> {
+    type Output = ZeroVector<D, T>;
 
-    fn sub(self, _rhs: &ZeroVector<D>) -> Self::Output {
-        todo!()
+    fn sub(self, _rhs: &ZeroVector<D, T>) -> Self::Output {
+        self.clone()
     }
 }
 
 //////////////////////////////////
-/// ZERO VEC DOT PRODUCT IMPLS ///
+//  ZERO VEC DOT PRODUCT IMPLS  //
 //////////////////////////////////
 
-impl<const D: usize> CanDotProduct<&ConstantVector<D>> for &ZeroVector<D> {
-    fn dot(&self, _other: &ConstantVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&ConstantVector<D, T>, T> for &ZeroVector<D, T> {
+    fn dot(&self, _other: &ConstantVector<D, T>) -> T {
+        T::zero()
     }
 }
 
-impl<const D: usize> CanDotProduct<&DenseVector<D>> for &ZeroVector<D> {
-    fn dot(&self, _other: &DenseVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&DenseVector<D, T>, T> for &ZeroVector<D, T> {
+    fn dot(&self, _other: &DenseVector<D, T>) -> T {
+        T::zero()
     }
 }
 
-impl<const D: usize> CanDotProduct<&OneHotVector<D>> for &ZeroVector<D> {
-    fn dot(&self, _other: &OneHotVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&OneHotVector<D, T>, T> for &ZeroVector<D, T> {
+    fn dot(&self, _other: &OneHotVector<D, T>) -> T {
+        T::zero()
     }
 }
 
-impl<const D: usize> CanDotProduct<&SparseVector<D>> for &ZeroVector<D> {
-    fn dot(&self, _other: &SparseVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&SparseVector<D, T>, T> for &ZeroVector<D, T> {
+    fn dot(&self, _other: &SparseVector<D, T>) -> T {
+        T::zero()
     }
 }
 
-impl<const D: usize> CanDotProduct<&ZeroVector<D>> for &ZeroVector<D> {
-    fn dot(&self, _other: &ZeroVector<D>) -> f32 {
-        todo!()
+impl<const D: usize, T: Scalar> CanDotProduct<&ZeroVector<D, T>, T> for &ZeroVector<D, T> {
+    fn dot(&self, _other: &ZeroVector<D, T>) -> T {
+        T::zero()
     }
 }
 
-//////////////////////////////////////
-/// CONSTANT VEC DOT PRODUCT IMPLS ///
-//////////////////////////////////////
+////////////////////////////////////
+//  ZERO VEC OUTER PRODUCT IMPLS  //
+////////////////////////////////////
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&ConstantVector<D2>> for ZeroVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&ConstantVector<D2, T>> for ZeroVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = ZeroMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &ConstantVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, _other: &ConstantVector<D2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&DenseVector<D2>> for ZeroVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&DenseVector<D2, T>> for ZeroVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = ZeroMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &DenseVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, _other: &DenseVector<D2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&OneHotVector<D2>> for ZeroVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&OneHotVector<D2, T>> for ZeroVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = ZeroMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &OneHotVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, _other: &OneHotVector<D2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&SparseVector<D2>> for ZeroVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&SparseVector<D2, T>> for ZeroVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
+    type Output = ZeroMatrix<D, D2, T>;
 
-    fn outer(&self, _other: &SparseVector<D2>) -> Self::Output {
-        todo!()
+    fn outer(&self, _other: &SparseVector<D2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
-impl<const D: usize, const D2: usize> CanOuterProduct<&ZeroVector<D2>> for ZeroVector<D>
+impl<const D: usize, const D2: usize, T: Scalar> CanOuterProduct<&ZeroVector<D2, T>> for ZeroVector<D, T>
     where [(); D*D2]: Sized
 {
-    type Output = DenseMatrix<D, D2>;
-    
-    fn outer(&self, _other: &ZeroVector<D2>) -> Self::Output {
-        todo!()
+    type Output = ZeroMatrix<D, D2, T>;
+
+    fn outer(&self, _other: &ZeroVector<D2, T>) -> Self::Output {
+        ZeroMatrix(T::zero())
     }
 }
 
 ////////////////////////////
-/// ZERO VEC ARITH IMPLS ///
+//  ZERO VEC ARITH IMPLS  //
 ////////////////////////////
 
-impl<const D: usize> Mul<f32> for &ZeroVector<D> {
-    type Output = ZeroVector<D>;
+impl<const D: usize, T: Scalar> Mul<T> for &ZeroVector<D, T> {
+    type Output = ZeroVector<D, T>;
+
+    fn mul(self, _rhs: T) -> Self::Output {
+        self.clone()
+    }
+}
+
+////////////////////////////
+//  ZERO VEC STACK IMPLS  //
+////////////////////////////
+
+impl<const D: usize, const M: usize, T: Scalar> CanStackVertically<&ZeroVector<M, T>> for ZeroVector<D, T>
+    where [(); D+M]: Sized
+{
+    type Output = ZeroVector<{D+M}, T>;
 
-    fn mul(self, _rhs: f32) -> Self::Output {
-        todo!()
+    fn vstack(&self, _other: &ZeroVector<M, T>) -> Self::Output {
+        ZeroVector(T::zero())
     }
 }
 
 //////////////////////////////
-/// ZERO VEC UTILITY IMPLS ///
+//  ZERO VEC UTILITY IMPLS  //
 //////////////////////////////
 
-impl<const D: usize> Index<usize> for ZeroVector<D> {
-    type Output = f32;
+impl<const D: usize, T: Scalar> Index<usize> for ZeroVector<D, T> {
+    type Output = T;
 
     fn index(&self, _index: usize) -> &Self::Output {
         &self.0
@@ -1,17 +1,56 @@
+use crate::linalg::Scalar;
+
+/// Slopes are given in `f32` and converted once, when the closure is built,
+/// so the same activation serves every entry type.
 pub trait ActivationFunction {
-    fn get_f(&self) -> impl Fn(f32) -> f32 + 'static;
-    fn get_df(&self) -> impl Fn(f32) -> f32 + 'static;
+    fn get_f<S: Scalar>(&self) -> impl Fn(S) -> S + Sync + 'static;
+    fn get_df<S: Scalar>(&self) -> impl Fn(S) -> S + Sync + 'static;
+
+    /// The factor that keeps the mean square of a signal symmetric around
+    /// zero unchanged through `f`, i.e. `1 / √E[f(x)²]` for unit `E[x²]`.
+    /// Weight initializations scale by it. Defaults to 1, which leaves them
+    /// unscaled.
+    fn gain(&self) -> f32 {
+        1.
+    }
+
+    /// What checkpoints call this function. Loading checks it against the
+    /// activation of the layer being restored. Defaults to the type name,
+    /// which may change between compiler versions, so functions meant for
+    /// long-lived checkpoints should name themselves.
+    fn name() -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// The parameters checkpoints store, e.g. the slopes. Defaults to none.
+    fn params(&self) -> Vec<f32> {
+        vec![]
+    }
+
+    /// Whether `params` could have come from `params`. Defaults to taking
+    /// none.
+    fn accepts_params(params: &[f32]) -> bool {
+        params.is_empty()
+    }
+
+    /// Takes over `params`, which `accepts_params` has let through. Defaults
+    /// to ignoring them, for functions without parameters.
+    fn set_params(&mut self, _params: &[f32]) {}
 }
 
 pub struct Identity;
 
 impl ActivationFunction for Identity {
-    fn get_f(&self) -> impl Fn(f32) -> f32 + 'static {
+    fn get_f<S: Scalar>(&self) -> impl Fn(S) -> S + Sync + 'static {
         |x| x
     }
 
-    fn get_df(&self) -> impl Fn(f32) -> f32 + 'static {
-        |_| 1f32
+    fn get_df<S: Scalar>(&self) -> impl Fn(S) -> S + Sync + 'static {
+        |_| S::one()
+    }
+
+    fn name() -> &'static str {
+        "identity"
     }
 }
 
@@ -21,14 +60,37 @@ pub struct LeakyReLU {
 }
 
 impl ActivationFunction for LeakyReLU {
-    fn get_f(&self) -> impl Fn(f32) -> f32 + 'static {
-        let (lt0, gte0) = (self.slope_lt0, self.slope_gte0);
-        move |x| if x < 0f32 { lt0 * x } else { gte0 * x }
+    fn get_f<S: Scalar>(&self) -> impl Fn(S) -> S + Sync + 'static {
+        let (lt0, gte0) = (S::from_f32(self.slope_lt0), S::from_f32(self.slope_gte0));
+        move |x| if x < S::zero() { lt0 * x } else { gte0 * x }
+    }
+
+    fn get_df<S: Scalar>(&self) -> impl Fn(S) -> S + Sync + 'static {
+        let (lt0, gte0) = (S::from_f32(self.slope_lt0), S::from_f32(self.slope_gte0));
+        move |x| if x < S::zero() { lt0 } else { gte0 }
+    }
+
+    /// Each half of the input keeps the square of its slope, so the gain is
+    /// `√(2 / (slope_lt0² + slope_gte0²))`, which is He et al.'s `√(2 / (1 + a²))`
+    /// for the usual `slope_gte0 = 1`.
+    fn gain(&self) -> f32 {
+        (2. / (self.slope_lt0.powi(2) + self.slope_gte0.powi(2))).sqrt()
     }
 
-    fn get_df(&self) -> impl Fn(f32) -> f32 + 'static {
-        let (lt0, gte0) = (self.slope_lt0, self.slope_gte0);
-        move |x| if x < 0f32 { lt0 } else { gte0 }
+    fn name() -> &'static str {
+        "leaky_relu"
+    }
+
+    fn params(&self) -> Vec<f32> {
+        vec![self.slope_lt0, self.slope_gte0]
+    }
+
+    fn accepts_params(params: &[f32]) -> bool {
+        params.len() == 2
+    }
+
+    fn set_params(&mut self, params: &[f32]) {
+        [self.slope_lt0, self.slope_gte0] = params.try_into().unwrap();
     }
 }
 
@@ -37,13 +99,33 @@ pub struct ReLU {
 }
 
 impl ActivationFunction for ReLU {
-    fn get_f(&self) -> impl Fn(f32) -> f32 + 'static {
-        let gte0 = self.slope_gte0;
-        move |x| if x < 0f32 { 0f32 } else { gte0 * x }
+    fn get_f<S: Scalar>(&self) -> impl Fn(S) -> S + Sync + 'static {
+        let gte0 = S::from_f32(self.slope_gte0);
+        move |x| if x < S::zero() { S::zero() } else { gte0 * x }
+    }
+
+    fn get_df<S: Scalar>(&self) -> impl Fn(S) -> S + Sync + 'static {
+        let gte0 = S::from_f32(self.slope_gte0);
+        move |x| if x < S::zero() { S::zero() } else { gte0 }
+    }
+
+    fn gain(&self) -> f32 {
+        2f32.sqrt() / self.slope_gte0
+    }
+
+    fn name() -> &'static str {
+        "relu"
+    }
+
+    fn params(&self) -> Vec<f32> {
+        vec![self.slope_gte0]
+    }
+
+    fn accepts_params(params: &[f32]) -> bool {
+        params.len() == 1
     }
 
-    fn get_df(&self) -> impl Fn(f32) -> f32 + 'static {
-        let gte0 = self.slope_gte0;
-        move |x| if x < 0f32 { 0f32 } else { gte0 }
+    fn set_params(&mut self, params: &[f32]) {
+        [self.slope_gte0] = params.try_into().unwrap();
     }
 }
@@ -0,0 +1,348 @@
+//! Saving the layers of a model and loading them back.
+//!
+//! A checkpoint is one little-endian byte stream:
+//!
+//! ```text
+//! magic       8 bytes          b"mlmodel\0"
+//! version     u32              VERSION
+//! layers      u32
+//! per layer, input side first:
+//!   inputs    u32
+//!   outputs   u32
+//!   name      u8 length, UTF-8 ActivationFunction::name
+//!   params    u8 count, f32s   ActivationFunction::params
+//!   weights   f64s             outputs × inputs, column-major
+//!   biases    f64s             outputs
+//! checksum    u32              CRC-32 of everything before it
+//! ```
+//!
+//! Entries are widened to `f64` whatever the scalar of the model, so a
+//! checkpoint loads at any precision. Quantized or sparse weights are saved
+//! by their values and come back dense.
+
+use std::fmt::Display;
+use std::io::{self, Read, Write};
+
+use num_traits::NumCast;
+
+use crate::linalg::{Matrix, Scalar, Vector};
+use crate::model::activation::ActivationFunction;
+
+const MAGIC: [u8; 8] = *b"mlmodel\0";
+
+/// Bumped whenever the layout changes. Readers reject every other version
+/// rather than guess.
+pub const VERSION: u32 = 1;
+
+/// Why a checkpoint could not be loaded.
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    /// The stream doesn't start with the checkpoint magic.
+    NotACheckpoint,
+    UnsupportedVersion { found: u32 },
+    /// The contents don't hash to the stored checksum, so the stream was
+    /// corrupted or cut short.
+    ChecksumMismatch { stored: u32, computed: u32 },
+    /// The checksum holds but the contents don't follow the layout.
+    Malformed(&'static str),
+    LayerCount { expected: usize, found: usize },
+    /// Layer `layer` maps `found.0` inputs to `found.1` outputs in the
+    /// checkpoint, but `expected.0` to `expected.1` in the model.
+    Shape { layer: usize, expected: (usize, usize), found: (usize, usize) },
+    Activation { layer: usize, expected: &'static str, found: String },
+    /// The activation of layer `layer` doesn't take these parameters.
+    ActivationParams { layer: usize, params: Vec<f32> },
+    /// Layer `layer` of the model doesn't take part in checkpoints.
+    NotCheckpointed { layer: usize },
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointError::Io(e) => write!(f, "checkpoint i/o failed: {e}"),
+            CheckpointError::NotACheckpoint => write!(f, "not a checkpoint"),
+            CheckpointError::UnsupportedVersion { found } => {
+                write!(f, "checkpoint version {found} is not supported (expected {VERSION})")
+            },
+            CheckpointError::ChecksumMismatch { stored, computed } => {
+                write!(f, "checkpoint is corrupted (checksum {computed:08x}, stored {stored:08x})")
+            },
+            CheckpointError::Malformed(what) => write!(f, "malformed checkpoint: {what}"),
+            CheckpointError::LayerCount { expected, found } => {
+                write!(f, "checkpoint has {found} layers, the model {expected}")
+            },
+            CheckpointError::Shape { layer, expected, found } => write!(
+                f,
+                "layer {layer} is {} → {} in the checkpoint but {} → {} in the model",
+                found.0, found.1, expected.0, expected.1,
+            ),
+            CheckpointError::Activation { layer, expected, found } => {
+                write!(f, "layer {layer} uses {found} in the checkpoint but {expected} in the model")
+            },
+            CheckpointError::ActivationParams { layer, params } => {
+                write!(f, "layer {layer} has invalid activation parameters {params:?}")
+            },
+            CheckpointError::NotCheckpointed { layer } => write!(f, "layer {layer} doesn't take part in checkpoints"),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CheckpointError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CheckpointError {
+    fn from(e: io::Error) -> Self {
+        CheckpointError::Io(e)
+    }
+}
+
+/// One layer as a checkpoint stores it, free of the types of the model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerRecord {
+    inputs: usize,
+    outputs: usize,
+    activation: String,
+    params: Vec<f32>,
+    /// `outputs × inputs`, column-major.
+    weights: Box<[f64]>,
+    biases: Box<[f64]>,
+}
+
+fn widen<S: Scalar>(x: S) -> f64 {
+    x.to_f64().unwrap()
+}
+
+fn narrow<S: Scalar>(x: f64) -> S {
+    <S as NumCast>::from(x).unwrap()
+}
+
+impl LayerRecord {
+    pub fn new<const IN: usize, const OUT: usize, S: Scalar, A: ActivationFunction>(
+        W: &Matrix<OUT, IN, S>,
+        b: &Vector<OUT, S>,
+        activation: &A,
+    ) -> Self
+        where [(); OUT*IN]: Sized
+    {
+        LayerRecord {
+            inputs: IN,
+            outputs: OUT,
+            activation: A::name().to_string(),
+            params: activation.params(),
+            weights: (0..OUT*IN).map(|i| widen(W.get(i % OUT, i / OUT))).collect(),
+            biases: (0..OUT).map(|i| widen(b[i])).collect(),
+        }
+    }
+
+    /// Checks that this record fits layer number `layer` of a model, one
+    /// that maps `IN` to `OUT` through `A`.
+    pub fn check<const IN: usize, const OUT: usize, A: ActivationFunction>(&self, layer: usize) -> Result<(), CheckpointError> {
+        if (self.inputs, self.outputs) != (IN, OUT) {
+            return Err(CheckpointError::Shape { layer, expected: (IN, OUT), found: (self.inputs, self.outputs) });
+        }
+        if self.activation != A::name() {
+            return Err(CheckpointError::Activation { layer, expected: A::name(), found: self.activation.clone() });
+        }
+        if !A::accepts_params(&self.params) {
+            return Err(CheckpointError::ActivationParams { layer, params: self.params.clone() });
+        }
+        Ok(())
+    }
+
+    /// The parameters of the activation, for `ActivationFunction::set_params`.
+    pub fn activation_params(&self) -> &[f32] {
+        &self.params
+    }
+
+    /// The weights, which have to be `OUT × IN`, see `check`.
+    pub fn W<const IN: usize, const OUT: usize, S: Scalar>(&self) -> Matrix<OUT, IN, S> where [(); OUT*IN]: Sized {
+        assert_eq!((self.inputs, self.outputs), (IN, OUT));
+        Matrix::from_boxed_slice(self.weights.iter().map(|&x| narrow(x)).collect())
+    }
+
+    pub fn b<const OUT: usize, S: Scalar>(&self) -> Vector<OUT, S> {
+        assert_eq!(self.outputs, OUT);
+        Vector::from_boxed_slice(self.biases.iter().map(|&x| narrow(x)).collect())
+    }
+}
+
+/// The reflected CRC-32 of zlib and PNG, one byte at a time.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &byte| CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+fn put_u32(bytes: &mut Vec<u8>, n: usize) {
+    bytes.extend(u32::try_from(n).unwrap().to_le_bytes());
+}
+
+fn encode(layers: &[LayerRecord]) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(VERSION.to_le_bytes());
+    put_u32(&mut bytes, layers.len());
+    for layer in layers {
+        put_u32(&mut bytes, layer.inputs);
+        put_u32(&mut bytes, layer.outputs);
+        bytes.push(u8::try_from(layer.activation.len()).unwrap());
+        bytes.extend(layer.activation.as_bytes());
+        bytes.push(u8::try_from(layer.params.len()).unwrap());
+        bytes.extend(layer.params.iter().flat_map(|x| x.to_le_bytes()));
+        bytes.extend(layer.weights.iter().chain(layer.biases.iter()).flat_map(|x| x.to_le_bytes()));
+    }
+    let checksum = crc32(&bytes);
+    bytes.extend(checksum.to_le_bytes());
+    bytes
+}
+
+/// The unread rest of a checkpoint.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], CheckpointError> {
+        let (head, rest) = self.0.split_first_chunk().ok_or(CheckpointError::Malformed("ends inside a layer"))?;
+        self.0 = rest;
+        Ok(*head)
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], CheckpointError> {
+        let (head, rest) = self.0.split_at_checked(n).ok_or(CheckpointError::Malformed("ends inside a layer"))?;
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<usize, CheckpointError> {
+        self.take::<1>().map(|[n]| n.into())
+    }
+
+    fn u32(&mut self) -> Result<usize, CheckpointError> {
+        self.take().map(|b| u32::from_le_bytes(b) as usize)
+    }
+
+    fn f64s(&mut self, n: usize) -> Result<Box<[f64]>, CheckpointError> {
+        (0..n).map(|_| self.take().map(f64::from_le_bytes)).collect()
+    }
+}
+
+fn decode(bytes: &[u8]) -> Result<Vec<LayerRecord>, CheckpointError> {
+    if !bytes.starts_with(&MAGIC) {
+        return Err(CheckpointError::NotACheckpoint);
+    }
+    let (contents, stored) = bytes.split_last_chunk()
+        .filter(|(contents, _)| contents.len() >= MAGIC.len() + 4)
+        .ok_or(CheckpointError::Malformed("no checksum"))?;
+    let (stored, computed) = (u32::from_le_bytes(*stored), crc32(contents));
+    if stored != computed {
+        return Err(CheckpointError::ChecksumMismatch { stored, computed });
+    }
+
+    let (version, body) = contents[MAGIC.len()..].split_first_chunk().ok_or(CheckpointError::Malformed("no header"))?;
+    let version = u32::from_le_bytes(*version);
+    if version != VERSION {
+        return Err(CheckpointError::UnsupportedVersion { found: version });
+    }
+
+    let mut reader = Reader(body);
+    let layers = (0..reader.u32()?).map(|_| {
+        let (inputs, outputs) = (reader.u32()?, reader.u32()?);
+        let len = reader.u8()?;
+        let activation = std::str::from_utf8(reader.bytes(len)?)
+            .map_err(|_| CheckpointError::Malformed("activation name is not UTF-8"))?
+            .to_string();
+        let count = reader.u8()?;
+        let params = (0..count).map(|_| reader.take().map(f32::from_le_bytes)).collect::<Result<_, _>>()?;
+        let weights = reader.f64s(outputs * inputs)?;
+        let biases = reader.f64s(outputs)?;
+        Ok::<_, CheckpointError>(LayerRecord { inputs, outputs, activation, params, weights, biases })
+    }).collect::<Result<Vec<_>, _>>()?;
+    if !reader.0.is_empty() {
+        return Err(CheckpointError::Malformed("bytes after the last layer"));
+    }
+    Ok(layers)
+}
+
+/// Writes `layers` to `out` as a checkpoint.
+pub fn write(layers: &[LayerRecord], mut out: impl Write) -> io::Result<()> {
+    out.write_all(&encode(layers))
+}
+
+/// Reads `input` to the end and parses it as a checkpoint. The checksum is
+/// verified before anything else is trusted.
+pub fn read(mut input: impl Read) -> Result<Vec<LayerRecord>, CheckpointError> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+    decode(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::activation::{Identity, LeakyReLU};
+
+    #[test]
+    fn crc_matches_the_reference() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let W = Matrix::<2, 3>::from_cols(&[[1., -2.], [0.5, 0.], [3., 0.25]]);
+        let layers = vec![
+            LayerRecord::new(&W, &Vector::from_arr([0.1, -0.1]), &LeakyReLU { slope_lt0: 0.1, slope_gte0: 1. }),
+            LayerRecord::new(&W.T(), &Vector::<3>::zero(), &Identity),
+        ];
+        let bytes = encode(&layers);
+        assert_eq!(decode(&bytes).unwrap(), layers);
+        assert_eq!(decode(&encode(&[])).unwrap(), []);
+
+        let W: Matrix<2, 3> = layers[0].W();
+        assert_eq!(W[(0, 2)], 3.);
+        assert!(matches!(layers[1].check::<3, 2, Identity>(1), Err(CheckpointError::Shape { layer: 1, .. })));
+        assert!(matches!(layers[1].check::<2, 3, LeakyReLU>(1), Err(CheckpointError::Activation { .. })));
+        assert!(layers[1].check::<2, 3, Identity>(1).is_ok());
+    }
+
+    #[test]
+    fn damage_is_reported() {
+        let bytes = encode(&[LayerRecord::new(&Matrix::<4, 3>::constant(0.5), &Vector::<4>::zero(), &Identity)]);
+        for at in [30, bytes.len() / 2, bytes.len() - 1] {
+            let mut flipped = bytes.clone();
+            flipped[at] ^= 0x10;
+            assert!(matches!(decode(&flipped), Err(CheckpointError::ChecksumMismatch { .. })), "flipped byte {at}");
+        }
+        assert!(matches!(decode(&bytes[..bytes.len() - 9]), Err(CheckpointError::ChecksumMismatch { .. })));
+        assert!(matches!(decode(&bytes[..10]), Err(CheckpointError::Malformed(_))));
+        assert!(matches!(decode(b"PK\x03\x04 and so on"), Err(CheckpointError::NotACheckpoint)));
+
+        // A flipped version byte is damage like any other; a newer writer
+        // also updates the checksum.
+        let mut future = bytes.clone();
+        future[8] = 2;
+        assert!(matches!(decode(&future), Err(CheckpointError::ChecksumMismatch { .. })));
+        future.truncate(future.len() - 4);
+        future.extend(crc32(&future).to_le_bytes());
+        assert!(matches!(decode(&future), Err(CheckpointError::UnsupportedVersion { found: 2 })));
+    }
+}
@@ -1,25 +1,25 @@
-use crate::linalg::Vector;
+use crate::linalg::{Scalar, Vector};
 
-type V<const N: usize> = Vector<N>;
+type V<const N: usize, S> = Vector<N, S>;
 
 pub trait LossFunction: Copy {
-    fn get_L<const DIM: usize>(&self) -> impl Fn(&V<DIM>, &V<DIM>) -> (f32, V<DIM>) + 'static;
-    fn get_dL_da<const DIM: usize>(&self) -> impl Fn(&V<DIM>, &V<DIM>) -> V<DIM> + 'static;
+    fn get_L<const DIM: usize, S: Scalar>(&self) -> impl Fn(&V<DIM, S>, &V<DIM, S>) -> (S, V<DIM, S>) + 'static;
+    fn get_dL_da<const DIM: usize, S: Scalar>(&self) -> impl Fn(&V<DIM, S>, &V<DIM, S>) -> V<DIM, S> + 'static;
 }
 
 #[derive(Clone, Copy)]
 pub struct MeanSquaredErrorLoss;
 
 impl LossFunction for MeanSquaredErrorLoss {
-    fn get_L<const DIM: usize>(&self) -> impl Fn(&V<DIM>, &V<DIM>) -> (f32, V<DIM>) + 'static {
-        |target: &V<DIM>, output: &V<DIM>| {
+    fn get_L<const DIM: usize, S: Scalar>(&self) -> impl Fn(&V<DIM, S>, &V<DIM, S>) -> (S, V<DIM, S>) + 'static {
+        |target: &V<DIM, S>, output: &V<DIM, S>| {
             let errors = target - output;
             (errors.sum_of_squares(), errors)
         }
     }
 
-    fn get_dL_da<const DIM: usize>(&self) -> impl Fn(&V<DIM>, &V<DIM>) -> V<DIM> + 'static {
-        |target: &V<DIM>, output: &V<DIM>| -2f32 * &(target - output)
+    fn get_dL_da<const DIM: usize, S: Scalar>(&self) -> impl Fn(&V<DIM, S>, &V<DIM, S>) -> V<DIM, S> + 'static {
+        |target: &V<DIM, S>, output: &V<DIM, S>| &(target - output) * S::from_f32(-2.)
     }
 }
 
@@ -27,10 +27,10 @@ impl LossFunction for MeanSquaredErrorLoss {
 pub struct SoftmaxCrossEntropyLoss;
 
 impl LossFunction for SoftmaxCrossEntropyLoss {
-    fn get_L<const DIM: usize>(&self) -> impl Fn(&V<DIM>, &V<DIM>) -> (f32, V<DIM>) + 'static {
-        |target: &V<DIM>, output: &V<DIM>| {
-            let sum_exp = output.map(<f32>::exp).sum();
-            let mut entropy = 0f32;
+    fn get_L<const DIM: usize, S: Scalar>(&self) -> impl Fn(&V<DIM, S>, &V<DIM, S>) -> (S, V<DIM, S>) + 'static {
+        |target: &V<DIM, S>, output: &V<DIM, S>| {
+            let sum_exp = output.map(S::exp).sum();
+            let mut entropy = S::zero();
             for i in 0..DIM {
                 // Note minus: cross-entropy is -sum over i of { p(i) * log(q(i)) }.
                 entropy -= target[i] * (output[i].exp() / sum_exp).ln();
@@ -63,10 +63,10 @@ impl LossFunction for SoftmaxCrossEntropyLoss {
             \end{array}
         $$
     */
-    fn get_dL_da<const DIM: usize>(&self) -> impl Fn(&V<DIM>, &V<DIM>) -> V<DIM> + 'static {
-        |target: &V<DIM>, output: &V<DIM>| {
+    fn get_dL_da<const DIM: usize, S: Scalar>(&self) -> impl Fn(&V<DIM, S>, &V<DIM, S>) -> V<DIM, S> + 'static {
+        |target: &V<DIM, S>, output: &V<DIM, S>| {
             let sum_pj = target.sum(); // Normally sum(p) = 1;
-            let sum_exp_qj = output.map(<f32>::exp).sum();
+            let sum_exp_qj = output.map(S::exp).sum();
             let ratio = sum_pj / sum_exp_qj; // If sum(p) = 1, this is just the softmax denominator.
             let mut dL_da = V::zero();
             for i in 0..DIM {
@@ -1,53 +1,84 @@
 pub mod activation;
+pub mod checkpoint;
 pub mod loss;
 pub mod manual;
 pub mod weights;
 
+use std::io::{self, Read, Write};
 use std::marker::PhantomData;
 
-use crate::linalg::Vector;
+use crate::linalg::{Scalar, Vector};
 use crate::layer::ModelLayerChain;
+use checkpoint::CheckpointError;
 use loss::LossFunction;
 
-pub struct ModelOutput<const DIM: usize> {
-    pub loss: f32,
-    pub errors: Vector<DIM>,
-    pub output: Vector<DIM>,
+pub struct ModelOutput<const DIM: usize, S: Scalar = f32> {
+    pub loss: S,
+    pub errors: Vector<DIM, S>,
+    pub output: Vector<DIM, S>,
 }
 
-pub struct Model<const IN: usize, const OUT: usize, T, L: ModelLayerChain<IN, OUT, T>, LF: LossFunction> {
+pub struct Model<const IN: usize, const OUT: usize, T, L: ModelLayerChain<IN, OUT, T, S>, LF: LossFunction, S: Scalar = f32> {
     pub layers: L,
-    pub last_input: Vector<IN>,
-    pub last_output: Vector<OUT>,
-    pub errors: Vector<OUT>,
-    pub loss: f32,
+    pub last_input: Vector<IN, S>,
+    pub last_output: Vector<OUT, S>,
+    pub errors: Vector<OUT, S>,
+    pub loss: S,
     pub loss_function: LF,
 
     _ph: PhantomData<T>, // dummy field denoting hard-to-inscribe type T
 }
 
-impl<const IN: usize, const OUT: usize, T, L: ModelLayerChain<IN, OUT, T>, LF: LossFunction> Model<IN, OUT, T, L, LF> {
+impl<const IN: usize, const OUT: usize, T, L: ModelLayerChain<IN, OUT, T, S>, LF: LossFunction, S: Scalar> Model<IN, OUT, T, L, LF, S> {
     pub fn new(layers: L, loss_function: LF) -> Self {
         Model {
             layers,
             last_input: Vector::zero(),
             last_output: Vector::zero(),
             errors: Vector::zero(),
-            loss: 0f32,
+            loss: S::zero(),
             loss_function,
             _ph: PhantomData::<T>,
         }
     }
 
-    pub fn train_single(&mut self, input: &Vector<IN>, target: &Vector<OUT>) {
+    pub fn train_single(&mut self, input: &Vector<IN, S>, target: &Vector<OUT, S>) {
         let ModelOutput { loss, errors, output } = self.layers.train_single(
             (input, target),
             self.loss_function,
-            0.01,
+            S::from_f32(0.01),
         );
         self.last_input = input.clone();
         self.last_output = output;
         self.loss = loss;
         self.errors = errors;
     }
+
+    /// Runs `input` through the layers without touching the weights, e.g.
+    /// after swapping them for quantized ones.
+    pub fn predict(&mut self, input: &Vector<IN, S>) -> Vector<OUT, S> {
+        self.layers.predict(input)
+    }
+
+    /// Data-driven initialization from a batch of typical inputs, best
+    /// started from `Weights::orthogonal`. See `weights::lsuv`.
+    pub fn lsuv(&mut self, inputs: &[Vector<IN, S>], tolerance: S) {
+        self.layers.lsuv(inputs, tolerance);
+    }
+
+    /// Writes the weights, biases and activations of every layer to `out`,
+    /// in the format of `checkpoint`. Fails with `ErrorKind::Unsupported`
+    /// if a layer doesn't take part in checkpoints.
+    pub fn save(&self, out: impl Write) -> io::Result<()> {
+        let records = self.layers.records().map_err(|e| io::Error::new(io::ErrorKind::Unsupported, e))?;
+        checkpoint::write(&records, out)
+    }
+
+    /// Replaces the weights, biases and activations of every layer with
+    /// those of the checkpoint in `input`. Unless the checkpoint is intact
+    /// and every layer in it fits this model, the model is left as it was.
+    pub fn load(&mut self, input: impl Read) -> Result<(), CheckpointError> {
+        let records = checkpoint::read(input)?;
+        self.layers.restore(&records)
+    }
 }
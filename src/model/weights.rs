@@ -1,28 +1,39 @@
-use num_traits::cast::AsPrimitive;
-use rand::thread_rng;
+use rand::Rng;
 use rand::distributions::{Distribution, Uniform};
 
+use crate::linalg::Scalar;
+use crate::random;
 use crate::linalg::vector::Vector;
 use crate::linalg::matrix::Matrix;
+use crate::layer::ModelLayer;
+use crate::model::activation::ActivationFunction;
 
-pub enum Biases<const DIM: usize> {
+pub enum Biases<const DIM: usize, S: Scalar = f32> {
     Zeros,
-    UniformlyRandom { lo: f32, hi: f32 },
+    UniformlyRandom { lo: S, hi: S },
 }
 
-impl<const DIM: usize> Biases<DIM> {
+impl<const DIM: usize, S: Scalar> Biases<DIM, S> {
     pub fn zeros() -> Self {
         Self::Zeros
     }
 
-    pub fn uniformly_random(lo: f32, hi: f32) -> Self {
+    pub fn uniformly_random(lo: S, hi: S) -> Self {
         Self::UniformlyRandom { lo, hi }
     }
 
-    fn lazy_uniform_random(lo: f32, hi: f32) -> Vector<DIM> {
-        let rng = thread_rng();
-        let mut uniform = Uniform::new(lo, hi).sample_iter(rng);
-        let f = |_| uniform.next().unwrap();
+    /// Draws the entries from `rng`. `into()` does the same with the
+    /// generator of the current thread, which `with_seed` controls.
+    pub fn sample(self, rng: &mut impl Rng) -> Vector<DIM, S> {
+        match self {
+            Self::Zeros => Vector::zero(),
+            Self::UniformlyRandom { lo, hi } => Biases::lazy_uniform_random(lo, hi, rng),
+        }
+    }
+
+    fn lazy_uniform_random(lo: S, hi: S, rng: &mut impl Rng) -> Vector<DIM, S> {
+        let mut uniform = Uniform::new(lo.to_f32(), hi.to_f32()).sample_iter(rng);
+        let f = |_| S::from_f32(uniform.next().unwrap());
 
         let mut container = Vec::with_capacity(DIM);
         container.extend((0..DIM).map(f));
@@ -32,60 +43,219 @@ impl<const DIM: usize> Biases<DIM> {
 }
 
 // No From impl, this is a one-way conversion.
-impl<const DIM: usize> Into<Vector<DIM>> for Biases<DIM> {
-    fn into(self) -> Vector<DIM> {
-        match self {
-            Self::Zeros => Vector::zero(),
-            Self::UniformlyRandom { lo, hi } => Biases::lazy_uniform_random(lo, hi),
-        }
+#[allow(clippy::from_over_into)]
+impl<const DIM: usize, S: Scalar> Into<Vector<DIM, S>> for Biases<DIM, S> {
+    fn into(self) -> Vector<DIM, S> {
+        random::with_rng(|rng| self.sample(rng))
     }
 }
 
-impl<const DIM: usize> Default for Biases<DIM> {
+impl<const DIM: usize, S: Scalar> Default for Biases<DIM, S> {
     /// For biases vector of dim N, set elements uniformly at random in \[-1/√N, 1/√N\]
     fn default() -> Self {
-        let n: f32 = <usize as AsPrimitive<f32>>::as_(DIM);
-        Self::UniformlyRandom { lo: -1f32 / n.sqrt(), hi: 1f32 / n.sqrt() }
+        let n = S::from_usize(DIM);
+        Self::UniformlyRandom { lo: -S::one() / n.sqrt(), hi: S::one() / n.sqrt() }
     }
 }
 
-pub enum Weights<const IN: usize, const OUT: usize> where [(); OUT*IN]: Sized {
+/// How to fill a weight matrix. Apart from the fixed ranges, every scheme
+/// scales with the fan-in `IN` and/or the fan-out `OUT`, so the size of the
+/// signal stays put from layer to layer.
+pub enum Weights<const IN: usize, const OUT: usize, S: Scalar = f32> where [(); OUT*IN]: Sized {
     Zeros,
-    UniformlyRandom { lo: f32, hi: f32 },
+    UniformlyRandom { lo: S, hi: S },
+    /// Glorot & Bengio: variance `2 / (IN + OUT)`, which suits activations
+    /// symmetric around zero.
+    XavierUniform,
+    XavierNormal,
+    /// He et al.: variance `gain² / IN`. The gain makes up for what the
+    /// activation takes out of the signal, see `ActivationFunction::gain`.
+    HeUniform { gain: S },
+    HeNormal { gain: S },
+    /// A normal distribution, with draws further than two standard
+    /// deviations out thrown away.
+    TruncatedNormal { mean: S, std: S },
+    /// Saxe et al.: orthonormal rows or columns, whichever there are fewer
+    /// of, times `gain`.
+    Orthogonal { gain: S },
 }
 
-impl<const IN: usize, const OUT: usize> Weights<IN, OUT> where [(); OUT*IN]: Sized {
+impl<const IN: usize, const OUT: usize, S: Scalar> Weights<IN, OUT, S> where [(); OUT*IN]: Sized {
     pub fn zeros() -> Self {
         Self::Zeros
     }
 
-    pub fn uniformly_random(lo: f32, hi: f32) -> Self {
+    pub fn uniformly_random(lo: S, hi: S) -> Self {
         Self::UniformlyRandom { lo, hi }
     }
 
-    fn lazy_uniform_random(lo: f32, hi: f32) -> Matrix<OUT, IN> {
-        let rng = thread_rng();
-        let mut uniform = Uniform::<f32>::new(lo, hi).sample_iter(rng);
-        let f = |_| uniform.next().unwrap();
+    pub fn xavier_uniform() -> Self {
+        Self::XavierUniform
+    }
+
+    pub fn xavier_normal() -> Self {
+        Self::XavierNormal
+    }
+
+    /// He initialization for layers followed by `activation`.
+    pub fn he_uniform(activation: &impl ActivationFunction) -> Self {
+        Self::HeUniform { gain: S::from_f32(activation.gain()) }
+    }
+
+    pub fn he_normal(activation: &impl ActivationFunction) -> Self {
+        Self::HeNormal { gain: S::from_f32(activation.gain()) }
+    }
+
+    pub fn truncated_normal(mean: S, std: S) -> Self {
+        Self::TruncatedNormal { mean, std }
+    }
+
+    pub fn orthogonal(gain: S) -> Self {
+        Self::Orthogonal { gain }
+    }
+
+    /// Draws the entries from `rng`, like `Biases::sample`.
+    pub fn sample(self, rng: &mut impl Rng) -> Matrix<OUT, IN, S>
+        where
+            [(); IN*OUT]: Sized,
+            [(); IN*IN]: Sized,
+            [(); OUT*OUT]: Sized,
+    {
+        let (fan_in, fan_out) = (IN as f32, OUT as f32);
+        match self {
+            Self::Zeros => Matrix::zero(),
+            Self::UniformlyRandom { lo, hi } => Weights::lazy_uniform_random(lo, hi, rng),
+            Self::XavierUniform => Weights::uniform_with_std((2. / (fan_in + fan_out)).sqrt(), rng),
+            Self::XavierNormal => Weights::normal(0., (2. / (fan_in + fan_out)).sqrt(), rng),
+            Self::HeUniform { gain } => Weights::uniform_with_std(gain.to_f32() / fan_in.sqrt(), rng),
+            Self::HeNormal { gain } => Weights::normal(0., gain.to_f32() / fan_in.sqrt(), rng),
+            Self::TruncatedNormal { mean, std } => {
+                let (mean, std) = (mean.to_f32(), std.to_f32());
+                let draw = |rng: &mut _| loop {
+                    let z = standard_normal(rng);
+                    if z.abs() <= 2. {
+                        return mean + std * z;
+                    }
+                };
+                Matrix::from_boxed_slice((0..OUT*IN).map(|_| S::from_f32(draw(rng))).collect())
+            },
+            Self::Orthogonal { gain } => Weights::orthogonal_factor(gain, rng),
+        }
+    }
+
+    fn lazy_uniform_random(lo: S, hi: S, rng: &mut impl Rng) -> Matrix<OUT, IN, S> {
+        let mut uniform = Uniform::<f32>::new(lo.to_f32(), hi.to_f32()).sample_iter(rng);
+        let f = |_| S::from_f32(uniform.next().unwrap());
         let data = (0..OUT*IN).map(f).collect();
         Matrix::from_boxed_slice(data)
     }
+
+    /// A uniform distribution around zero with standard deviation `std`,
+    /// i.e. on `[-√3 std, √3 std]`.
+    fn uniform_with_std(std: f32, rng: &mut impl Rng) -> Matrix<OUT, IN, S> {
+        let bound = S::from_f32(3f32.sqrt() * std);
+        Weights::lazy_uniform_random(-bound, bound, rng)
+    }
+
+    fn normal(mean: f32, std: f32, rng: &mut impl Rng) -> Matrix<OUT, IN, S> {
+        Matrix::from_boxed_slice((0..OUT*IN).map(|_| S::from_f32(mean + std * standard_normal(rng))).collect())
+    }
+
+    /// The `Q` of a Gaussian matrix, cut down to `OUT × IN`. Flipping each
+    /// column of `Q` to match the sign of the diagonal of `R` makes the
+    /// result uniformly distributed over the orthogonal matrices, rather
+    /// than biased by the sign choices of the factorization.
+    fn orthogonal_factor(gain: S, rng: &mut impl Rng) -> Matrix<OUT, IN, S>
+        where
+            [(); IN*OUT]: Sized,
+            [(); IN*IN]: Sized,
+            [(); OUT*OUT]: Sized,
+    {
+        let gaussian = |rng: &mut _| S::from_f32(standard_normal(rng));
+        let sign = |x: S| if x < S::zero() { -gain } else { gain };
+        let data = if OUT >= IN {
+            let a = Matrix::<OUT, IN, S>::from_boxed_slice((0..OUT*IN).map(|_| gaussian(rng)).collect());
+            let qr = a.qr();
+            let (q, r) = (qr.Q(), qr.R());
+            (0..OUT*IN).map(|i| q[(i % OUT, i / OUT)] * sign(r[(i / OUT, i / OUT)])).collect()
+        } else {
+            let a = Matrix::<IN, OUT, S>::from_boxed_slice((0..IN*OUT).map(|_| gaussian(rng)).collect());
+            let qr = a.qr();
+            let (q, r) = (qr.Q(), qr.R());
+            (0..OUT*IN).map(|i| q[(i / OUT, i % OUT)] * sign(r[(i % OUT, i % OUT)])).collect()
+        };
+        Matrix::from_boxed_slice(data)
+    }
+}
+
+/// A draw from `N(0, 1)`, by the Box-Muller transform.
+fn standard_normal(rng: &mut impl Rng) -> f32 {
+    // `gen` is in [0, 1), so `1 - gen` keeps the logarithm finite.
+    let (u1, u2): (f32, f32) = (1. - rng.gen::<f32>(), rng.gen());
+    (-2. * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
 }
 
-impl<const IN: usize, const OUT: usize> Default for Weights<IN, OUT> where [(); OUT*IN]: Sized {
+impl<const IN: usize, const OUT: usize, S: Scalar> Default for Weights<IN, OUT, S> where [(); OUT*IN]: Sized {
     /// For weights matrix of R×C, set elements uniformly at random in \[-1/√C, 1/√C\]
     fn default() -> Self {
-        let n: f32 = <usize as AsPrimitive<f32>>::as_(IN);
-        Self::UniformlyRandom { lo: -1f32 / n.sqrt(), hi: 1f32 / n.sqrt() }
+        let n = S::from_usize(IN);
+        Self::UniformlyRandom { lo: -S::one() / n.sqrt(), hi: S::one() / n.sqrt() }
     }
 }
 
 // No From impl, this is a one-way conversion.
-impl<const IN: usize, const OUT: usize> Into<Matrix<OUT, IN>> for Weights<IN, OUT> where [(); OUT*IN]: Sized {
-    fn into(self) -> Matrix<OUT, IN> {
-        match self {
-            Self::Zeros => Matrix::zero(),
-            Self::UniformlyRandom { lo, hi } => Weights::lazy_uniform_random(lo, hi),
+#[allow(clippy::from_over_into)]
+impl<const IN: usize, const OUT: usize, S: Scalar> Into<Matrix<OUT, IN, S>> for Weights<IN, OUT, S>
+    where
+        [(); OUT*IN]: Sized,
+        [(); IN*OUT]: Sized,
+        [(); IN*IN]: Sized,
+        [(); OUT*OUT]: Sized,
+{
+    fn into(self) -> Matrix<OUT, IN, S> {
+        random::with_rng(|rng| self.sample(rng))
+    }
+}
+
+/// How many times `lsuv` rescales a layer before settling for what it has.
+const LSUV_MAX_ROUNDS: usize = 10;
+
+/// Layer-sequential unit-variance initialization (Mishkin & Matas): runs
+/// `inputs` through `layer` and rescales its weights until the linear
+/// outputs have a variance within `tolerance` of one. The biases are left
+/// alone, which is why it can take more than one round. Returns the
+/// nonlinear outputs, which are the inputs of the next layer.
+///
+/// Panics if `inputs` is empty. Weights whose outputs have no finite,
+/// positive variance are left as they are.
+pub fn lsuv<const IN: usize, const OUT: usize, S: Scalar>(
+    layer: &mut impl ModelLayer<IN, OUT, S>,
+    inputs: &[Vector<IN, S>],
+    tolerance: S,
+) -> Vec<Vector<OUT, S>>
+    where
+        [(); IN*OUT]: Sized,
+        [(); OUT*IN]: Sized,
+        [(); OUT*OUT]: Sized,
+{
+    assert!(!inputs.is_empty(), "lsuv needs at least one input to measure variance on");
+    let n = S::from_usize(inputs.len() * OUT);
+    for _ in 0..LSUV_MAX_ROUNDS {
+        let (mut sum, mut sum_of_squares) = (S::zero(), S::zero());
+        for x in inputs {
+            layer.forward(x);
+            sum += layer.linear_output().sum();
+            sum_of_squares += layer.linear_output().sum_of_squares();
+        }
+        let mean = sum / n;
+        let variance = sum_of_squares / n - mean * mean;
+        if !variance.is_finite() || variance <= S::zero() || (variance - S::one()).abs() <= tolerance {
+            break;
         }
+        layer.scale_weights(S::one() / variance.sqrt());
     }
+    inputs.iter().map(|x| {
+        layer.forward(x);
+        layer.nonlinear_output().clone()
+    }).collect()
 }
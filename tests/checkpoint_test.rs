@@ -0,0 +1,122 @@
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+
+use mylittlemodel::layer::connected::FullyConnectedLayer;
+use mylittlemodel::linalg::Vector;
+use mylittlemodel::linalg::Scalar;
+use mylittlemodel::model::activation::{ActivationFunction, Identity, LeakyReLU};
+use mylittlemodel::model::checkpoint::CheckpointError;
+use mylittlemodel::model::loss::MeanSquaredErrorLoss;
+use mylittlemodel::model::weights::{Biases, Weights};
+use mylittlemodel::model::Model;
+use mylittlemodel::with_seed;
+
+fn leaky(slope_lt0: f32) -> LeakyReLU {
+    LeakyReLU { slope_lt0, slope_gte0: 1. }
+}
+
+#[test]
+fn trained_models_survive_a_restart() {
+    let chain = with_seed(7, || (
+        FullyConnectedLayer::with(Weights::<3, 16>::default(), Biases::default(), leaky(0.2)),
+        FullyConnectedLayer::with(Weights::<16, 16>::default(), Biases::default(), leaky(0.1)),
+        FullyConnectedLayer::with(Weights::<16, 2>::default(), Biases::default(), Identity),
+    ));
+    let mut trained = Model::new(chain, MeanSquaredErrorLoss);
+    let (input, target) = (Vector::from_arr([0.5, -1., 2.]), Vector::from_arr([1., -0.5]));
+    for _ in 0..100 {
+        trained.train_single(&input, &target);
+    }
+    let mut saved = Vec::new();
+    trained.save(&mut saved).unwrap();
+
+    // A fresh process builds the same architecture with other weights and slopes.
+    let chain = (
+        FullyConnectedLayer::with(Weights::<3, 16>::default(), Biases::default(), leaky(0.)),
+        FullyConnectedLayer::with(Weights::<16, 16>::default(), Biases::default(), leaky(0.)),
+        FullyConnectedLayer::with(Weights::<16, 2>::default(), Biases::default(), Identity),
+    );
+    let mut restored = Model::new(chain, MeanSquaredErrorLoss);
+    restored.load(&saved[..]).unwrap();
+    assert_eq!(restored.layers.0.activation_function.slope_lt0, 0.2);
+    assert_eq!(restored.layers.1.activation_function.slope_lt0, 0.1);
+    for x in [input, Vector::from_arr([-1., 0.25, 0.])] {
+        assert_eq!(restored.predict(&x), trained.predict(&x));
+    }
+}
+
+#[test]
+fn mismatches_are_typed_and_leave_the_model_alone() {
+    let chain = || (
+        FullyConnectedLayer::with(Weights::<3, 8>::default(), Biases::default(), leaky(0.1)),
+        FullyConnectedLayer::with(Weights::<8, 8>::default(), Biases::default(), leaky(0.1)),
+        FullyConnectedLayer::with(Weights::<8, 2>::default(), Biases::default(), leaky(0.1)),
+    );
+    let mut saved = Vec::new();
+    Model::new(chain(), MeanSquaredErrorLoss).save(&mut saved).unwrap();
+
+    let mut wider = Model::new((
+        FullyConnectedLayer::with(Weights::<3, 8>::default(), Biases::default(), leaky(0.1)),
+        FullyConnectedLayer::with(Weights::<8, 9>::default(), Biases::default(), leaky(0.1)),
+        FullyConnectedLayer::with(Weights::<9, 2>::default(), Biases::default(), leaky(0.1)),
+    ), MeanSquaredErrorLoss);
+    let before = wider.layers.0.W.clone();
+    let error = wider.load(&saved[..]).unwrap_err();
+    assert!(matches!(error, CheckpointError::Shape { layer: 1, expected: (8, 9), found: (8, 8) }), "{error}");
+    assert_eq!(wider.layers.0.W, before, "layer 0 fits but must not be half-loaded");
+
+    let mut linear = Model::new((
+        FullyConnectedLayer::with(Weights::<3, 8>::default(), Biases::default(), leaky(0.1)),
+        FullyConnectedLayer::with(Weights::<8, 8>::default(), Biases::default(), leaky(0.1)),
+        FullyConnectedLayer::with(Weights::<8, 2>::default(), Biases::default(), Identity),
+    ), MeanSquaredErrorLoss);
+    let error = linear.load(&saved[..]).unwrap_err();
+    assert!(matches!(&error, CheckpointError::Activation { layer: 2, expected: "identity", found } if found == "leaky_relu"));
+
+    let mut model = Model::new(chain(), MeanSquaredErrorLoss);
+    let mut corrupted = saved.clone();
+    corrupted[100] ^= 1;
+    assert!(matches!(model.load(&corrupted[..]), Err(CheckpointError::ChecksumMismatch { .. })));
+    assert!(matches!(model.load(&saved[..saved.len() / 2]), Err(CheckpointError::ChecksumMismatch { .. })));
+    assert!(model.load(&saved[..]).is_ok());
+}
+
+/// An activation written against nothing but the required methods.
+struct Softsign;
+
+impl ActivationFunction for Softsign {
+    fn get_f<S: Scalar>(&self) -> impl Fn(S) -> S + Sync + 'static {
+        |x| x / (S::one() + x.abs())
+    }
+
+    fn get_df<S: Scalar>(&self) -> impl Fn(S) -> S + Sync + 'static {
+        |x| S::one() / ((S::one() + x.abs()) * (S::one() + x.abs()))
+    }
+}
+
+#[test]
+fn activations_without_checkpoint_support_use_the_defaults() {
+    let chain = || (
+        FullyConnectedLayer::with(Weights::<3, 4>::default(), Biases::default(), Softsign),
+        FullyConnectedLayer::with(Weights::<4, 4>::default(), Biases::default(), Softsign),
+        FullyConnectedLayer::with(Weights::<4, 2>::default(), Biases::default(), Identity),
+    );
+    let saved = with_seed(3, || {
+        let mut saved = Vec::new();
+        Model::new(chain(), MeanSquaredErrorLoss).save(&mut saved).unwrap();
+        saved
+    });
+    let mut restored = Model::new(chain(), MeanSquaredErrorLoss);
+    restored.load(&saved[..]).unwrap();
+    let mut resaved = Vec::new();
+    restored.save(&mut resaved).unwrap();
+    assert_eq!(resaved, saved);
+
+    let mut linear = Model::new((
+        FullyConnectedLayer::with(Weights::<3, 4>::default(), Biases::default(), Identity),
+        FullyConnectedLayer::with(Weights::<4, 4>::default(), Biases::default(), Softsign),
+        FullyConnectedLayer::with(Weights::<4, 2>::default(), Biases::default(), Identity),
+    ), MeanSquaredErrorLoss);
+    let error = linear.load(&saved[..]).unwrap_err();
+    assert!(matches!(&error, CheckpointError::Activation { layer: 0, found, .. } if found == Softsign::name()), "{error}");
+}
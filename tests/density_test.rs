@@ -0,0 +1,53 @@
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+
+use mylittlemodel::linalg::{density, Matrix, Vector};
+
+// The threshold is process-wide, so this lives in its own test binary where
+// it can't change flavors under the unit tests.
+
+#[test]
+fn sparse_chains_follow_the_global_threshold() {
+    let hot = |i| Vector::<8>::one_hot(i);
+
+    // Without a threshold, a sparse accumulator stays sparse however full.
+    let mut acc = Vector::<8>::sparse();
+    for i in 0..8 {
+        acc += &hot(i);
+    }
+    assert!(matches!(acc, Vector::Sparse(_)));
+
+    density::set_threshold(Some(0.25));
+    assert_eq!(density::threshold(), Some(0.25));
+
+    let mut acc = Vector::<8>::sparse();
+    acc += &hot(0);
+    acc += &hot(1);
+    assert!(matches!(acc, Vector::Sparse(_)), "2 of 8 is at the threshold");
+    acc.axpy(2., &hot(2));
+    assert!(matches!(acc, Vector::Dense(_)), "3 of 8 is past it");
+    assert_eq!(acc, Vector::from_arr([1., 1., 2., 0., 0., 0., 0., 0.]));
+
+    // Cancelling back down to one entry, an eighth, crosses half the threshold.
+    let diff = &acc - &Vector::from_pairs([(1, 1.), (2, 2.)]);
+    assert!(matches!(diff, Vector::Sparse(_)));
+    assert_eq!(diff, Vector::from_pairs([(0, 1.)]));
+
+    // Products of sparse matrices that fill in end up dense.
+    let col = Matrix::<4, 4>::from_triplets((0..4).map(|r| (r, 0, 1.)));
+    let row = Matrix::<4, 4>::from_triplets((0..4).map(|c| (0, c, 1.)));
+    assert!(matches!(&col * &row, Matrix::Dense(_)));
+    assert!(matches!(&row * &col, Matrix::Sparse(_)));
+    assert_eq!((&col * &row).sum(), 16.);
+
+    // Dense-only arithmetic is never rebalanced.
+    let zeros = &Matrix::<4, 4>::from_arr([1.; 16]) - &Matrix::from_arr([1.; 16]);
+    assert!(matches!(zeros, Matrix::Dense(_)));
+
+    density::set_threshold(None);
+    let mut acc = Vector::<8>::sparse();
+    for i in 0..8 {
+        acc += &hot(i);
+    }
+    assert!(matches!(acc, Vector::Sparse(_)));
+}
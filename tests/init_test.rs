@@ -0,0 +1,123 @@
+#![allow(non_snake_case)]
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+
+use mylittlemodel::layer::connected::FullyConnectedLayer;
+use mylittlemodel::layer::ModelLayer;
+use mylittlemodel::linalg::{Matrix, Vector};
+use mylittlemodel::model::activation::{ActivationFunction, LeakyReLU};
+use mylittlemodel::model::loss::MeanSquaredErrorLoss;
+use mylittlemodel::model::weights::{self, Biases, Weights};
+use mylittlemodel::model::Model;
+use mylittlemodel::with_seed;
+
+const DEPTH: usize = 24;
+
+fn leaky() -> LeakyReLU {
+    LeakyReLU { slope_lt0: 0.1, slope_gte0: 1. }
+}
+
+fn mean_square<const D: usize>(v: &Vector<D>) -> f32 {
+    v.sum_of_squares() / D as f32
+}
+
+/// The mean square of the output of `DEPTH` layers 64 wide initialized by `init`,
+/// relative to that of the input.
+fn signal_through_stack(init: impl Fn() -> Weights<64, 64>) -> f32 {
+    with_seed(11, || {
+        let input = Vector::<64>::from_fun(|i| ((i * 7) % 13) as f32 / 6. - 1.);
+        let mut x = input.clone();
+        for _ in 0..DEPTH {
+            let mut layer = FullyConnectedLayer::with(init(), Biases::zeros(), leaky());
+            layer.forward(&x);
+            x = layer.nonlinear_output().clone();
+        }
+        mean_square(&x) / mean_square(&input)
+    })
+}
+
+#[test]
+fn deep_leaky_stacks_keep_their_signal_with_he_init() {
+    let he = signal_through_stack(|| Weights::he_normal(&leaky()));
+    assert!((0.1..10.).contains(&he), "He normal: {he}");
+    let he = signal_through_stack(|| Weights::he_uniform(&leaky()));
+    assert!((0.1..10.).contains(&he), "He uniform: {he}");
+
+    // Without the gain the signal halves at every layer.
+    let xavier = signal_through_stack(Weights::xavier_normal);
+    assert!(xavier < 1e-5, "Xavier normal: {xavier}");
+    let default = signal_through_stack(Weights::default);
+    assert!(default < 1e-10, "default: {default}");
+}
+
+#[test]
+fn schemes_follow_their_fans() {
+    let sample = |init: Weights<100, 200>| -> Matrix<200, 100> { with_seed(3, || init.into()) };
+
+    let he = sample(Weights::he_normal(&leaky()));
+    let expected = leaky().gain().powi(2) / 100.;
+    assert!((he.variance() / expected - 1.).abs() < 0.05, "{} vs {expected}", he.variance());
+
+    let xavier = sample(Weights::xavier_uniform());
+    let bound = (6f32 / 300.).sqrt();
+    assert!(xavier.max() <= bound && xavier.min() >= -bound);
+    assert!((xavier.variance() / (2. / 300.) - 1.).abs() < 0.05);
+
+    let truncated = sample(Weights::truncated_normal(1., 0.5));
+    assert!(truncated.max() <= 2. && truncated.min() >= 0.);
+    assert!((truncated.mean() - 1.).abs() < 0.01);
+}
+
+#[test]
+fn orthogonal_init_has_orthonormal_rows_or_columns() {
+    let tall: Matrix<5, 3> = with_seed(5, || Weights::orthogonal(2.).into());
+    let wide: Matrix<3, 5> = with_seed(5, || Weights::orthogonal(2.).into());
+    for gram in [&tall.T() * &tall, &wide * &wide.T()] {
+        let off = &gram - &(&Matrix::<3, 3>::I() * 4.);
+        assert!(off.norm_frobenius() < 1e-4, "{gram:?}");
+    }
+}
+
+#[test]
+fn lsuv_normalizes_every_layer() {
+    let chain = with_seed(21, || (
+        FullyConnectedLayer::with(Weights::<3, 50>::orthogonal(1.), Biases::default(), leaky()),
+        FullyConnectedLayer::with(Weights::<50, 50>::orthogonal(1.), Biases::default(), leaky()),
+        FullyConnectedLayer::with(Weights::<50, 3>::orthogonal(1.), Biases::default(), leaky()),
+    ));
+    let inputs: Vec<Vector<3>> = (0..32)
+        .map(|k| Vector::from_fun(|i| ((k * 5 + i * 3) % 11) as f32 - 5.))
+        .collect();
+
+    let mut model = Model::new(chain, MeanSquaredErrorLoss);
+    model.lsuv(&inputs, 0.05);
+
+    let variance = |outputs: &[Vector<50>]| {
+        let n = (outputs.len() * 50) as f32;
+        let mean = outputs.iter().map(Vector::sum).sum::<f32>() / n;
+        outputs.iter().map(Vector::sum_of_squares).sum::<f32>() / n - mean * mean
+    };
+    let (first, second): (Vec<_>, Vec<_>) = inputs.iter().map(|x| {
+        model.layers.0.forward(x);
+        model.layers.1.forward(model.layers.0.nonlinear_output());
+        (model.layers.0.linear_output().clone(), model.layers.1.linear_output().clone())
+    }).unzip();
+    for outputs in [first, second] {
+        assert!((variance(&outputs) - 1.).abs() <= 0.05, "{}", variance(&outputs));
+    }
+}
+
+#[test]
+fn lsuv_leaves_weights_alone_without_a_finite_variance() {
+    let mut layer = FullyConnectedLayer::with(Weights::<3, 4>::xavier_normal(), Biases::zeros(), leaky());
+    let before = layer.W.clone();
+    weights::lsuv(&mut layer, &[Vector::from_arr([f32::INFINITY, 1., 0.])], 0.1);
+    assert_eq!(layer.W, before);
+}
+
+#[test]
+#[should_panic(expected = "at least one input")]
+fn lsuv_needs_inputs() {
+    let mut layer = FullyConnectedLayer::with(Weights::<3, 4>::xavier_normal(), Biases::zeros(), leaky());
+    weights::lsuv(&mut layer, &[], 0.1);
+}
@@ -13,7 +13,8 @@ use mylittlemodel::model::weights::{Biases, Weights};
 
 #[test]
 fn kitchen_sink_test() {
-    let chain = (
+    // Seeded, so every run trains the same model.
+    let chain = mylittlemodel::with_seed(2024, || (
         FullyConnectedLayer::with(
             Weights::<3, 50>::default(),
             Biases::default(),
@@ -29,7 +30,7 @@ fn kitchen_sink_test() {
             Biases::default(),
             LeakyReLU { slope_lt0: 0.15, slope_gte0: 0.9 },
         ),
-    );
+    ));
 
     // an example fully specified by hand for comparing correctness
     let mut model = mylittlemodel::model::manual::ManualModelDoNotUse {
@@ -99,15 +100,40 @@ fn kitchen_sink_test() {
     // println!("{}\t{}", model.layers.1.W, model.layers.1.b);
     // println!("{}\t{}", model.layers.2.W, model.layers.2.b);
     // println!("");
+    let mut losses = Vec::new();
     for i in 0..5000 {
         model.train_single(&input, &target);
         if i % 100 == 0 {
             println!("[{i:>4}/5000] Loss: {}", model.loss);
         }
+        if i % 1000 == 0 {
+            losses.push(model.loss);
+        }
     }
+    losses.push(model.loss);
+
+    // Every 1000th step and the last. The SIMD kernels round differently
+    // from the scalar ones, which shows from the second checkpoint on.
+    #[cfg(feature = "simd")]
+    let expected = [1.2377795, 5.4897197e-7, 1.109403e-10, 1.6903279e-12, 6.635093e-13, 4.4054764e-13];
+    #[cfg(not(feature = "simd"))]
+    let expected = [1.2377795, 5.4898476e-7, 1.1066928e-10, 1.7138492e-12, 6.892487e-13, 4.353518e-13];
+    assert_eq!(losses, expected);
     // println!("");
     // println!("{}\t{}", model.layers.0.W, model.layers.0.b);
     // println!("{}\t{}", model.layers.1.W, model.layers.1.b);
     // println!("{}\t{}", model.layers.2.W, model.layers.2.b);
 
+    // The same model with int8 weights, against the f32 one.
+    let probes = [input, Vector::from_arr([0.5, -1., 2.]), Vector::from_arr([-3., 0.25, 1.5])];
+    let f32_outputs = probes.each_ref().map(|x| model.predict(x));
+    model.layers.0.W = model.layers.0.W.quantize_rows();
+    model.layers.1.W = model.layers.1.W.quantize_rows();
+    model.layers.2.W = model.layers.2.W.quantize_rows();
+    for (x, expected) in probes.iter().zip(&f32_outputs) {
+        let output = model.predict(x);
+        let drop = (&output - expected).sum_of_squares().sqrt() / expected.sum_of_squares().sqrt();
+        println!("int8 output {output:?} vs f32 {expected:?}, relative error {drop:.5}");
+        assert!(drop < 0.01, "quantizing lost {drop} of the output");
+    }
 }
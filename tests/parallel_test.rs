@@ -0,0 +1,50 @@
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+
+use mylittlemodel::linalg::{parallel, Matrix, Vector};
+
+// Thread count and threshold are process-wide, so this lives in its own test
+// binary where it can't change how the unit tests split their work.
+
+fn bits<const R: usize, const C: usize>(m: &Matrix<R, C>) -> Vec<u32> where [(); R*C]: Sized {
+    (0..R*C).map(|i| m[(i % R, i / R)].to_bits()).collect()
+}
+
+fn vector_bits<const D: usize>(v: &Vector<D>) -> Vec<u32> {
+    (0..D).map(|i| v[i].to_bits()).collect()
+}
+
+#[test]
+fn dense_products_are_bitwise_deterministic_across_threads() {
+    let a: Matrix<37, 300> = Matrix::from_vector(Vector::from_fun(|i| {
+        let (r, c) = (i % 37, i / 37);
+        ((r * 7 + c * 3) % 11) as f32 / 3. - 1.7
+    }));
+    let b: Matrix<300, 29> = Matrix::from_vector(Vector::from_fun(|i| {
+        let (r, c) = (i % 300, i / 300);
+        ((r * 5 + c * 13) % 17) as f32 / 9. - 0.8
+    }));
+    let v = Vector::<300>::from_fun(|i| (i % 13) as f32 / 5. - 1.1);
+    let w = Vector::<37>::from_fun(|i| (i % 7) as f32 / 3. - 0.9);
+
+    let run = || (
+        bits(&(&a * &b)),
+        vector_bits(&(&a * &v)),
+        vector_bits(&(&a.T() * &w)),
+        bits(&(&(&a - &a.hadamard(&a)) * 0.3)),
+    );
+
+    parallel::set_threshold(0);
+    parallel::set_num_threads(1);
+    let serial = run();
+    for threads in [2, 3, 8, 64] {
+        parallel::set_num_threads(threads);
+        let split = run();
+        assert!(split.0 == serial.0, "gemm differs with {threads} threads");
+        assert!(split.1 == serial.1, "column gemv differs with {threads} threads");
+        assert!(split.2 == serial.2, "row gemv differs with {threads} threads");
+        assert!(split.3 == serial.3, "elementwise differs with {threads} threads");
+    }
+    parallel::set_num_threads(0);
+    parallel::set_threshold(parallel::DEFAULT_THRESHOLD);
+}